@@ -376,7 +376,7 @@ resource "aws_apprunner_service" "r3l_app" {
 
   health_check_configuration {
     protocol            = "HTTP"
-    path                = "/api/health"
+    path                = "/api/health/live"
     interval            = 10
     timeout             = 5
     healthy_threshold   = 1
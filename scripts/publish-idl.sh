@@ -0,0 +1,67 @@
+#!/usr/bin/env bash
+#
+# Build the provenance_attestation IDL and publish it on-chain.
+#
+# Usage:
+#   ./scripts/publish-idl.sh
+#
+# Environment:
+#   PROGRAM_ID   deployed program address (default: declare_id! in lib.rs)
+#   CLUSTER      anchor cluster: localnet, devnet, mainnet or an RPC URL (default: localnet)
+#   WALLET       keypair that owns the IDL account (default: ~/.config/solana/id.json)
+#
+# The first run creates the IDL account (`anchor idl init`); later runs
+# upgrade it (`anchor idl upgrade`). Either way the on-chain IDL is fetched
+# back and compared with the local build.
+#
+set -euo pipefail
+
+PROGRAM_DIR="$(cd "$(dirname "$0")/.." && pwd)/services/provenance_attestation"
+PROGRAM_ID="${PROGRAM_ID:-63jq6M3t5NafYWcADqLDCLnhd5qPfEmCUcaA9iWh5YWz}"
+CLUSTER="${CLUSTER:-localnet}"
+WALLET="${WALLET:-$HOME/.config/solana/id.json}"
+IDL_PATH="target/idl/provenance_attestation.json"
+
+for tool in anchor python3; do
+    if ! command -v "$tool" &>/dev/null; then
+        echo "Error: $tool not found"
+        exit 1
+    fi
+done
+
+cd "$PROGRAM_DIR"
+ANCHOR_OPTS=(--provider.cluster "$CLUSTER" --provider.wallet "$WALLET")
+
+# 1. Build the IDL
+echo "Building IDL..."
+anchor build --idl target/idl
+if [ ! -f "$IDL_PATH" ]; then
+    echo "Error: $IDL_PATH was not generated"
+    exit 1
+fi
+
+# 2. Publish: init the first time, upgrade afterwards
+FETCHED="$(mktemp)"
+trap 'rm -f "$FETCHED"' EXIT
+
+if anchor idl fetch "$PROGRAM_ID" "${ANCHOR_OPTS[@]}" >/dev/null 2>&1; then
+    echo "Upgrading on-chain IDL for $PROGRAM_ID on $CLUSTER"
+    anchor idl upgrade --filepath "$IDL_PATH" "$PROGRAM_ID" "${ANCHOR_OPTS[@]}"
+else
+    echo "Publishing IDL for $PROGRAM_ID on $CLUSTER for the first time"
+    anchor idl init --filepath "$IDL_PATH" "$PROGRAM_ID" "${ANCHOR_OPTS[@]}"
+fi
+
+# 3. Verify the on-chain copy matches the local build
+anchor idl fetch "$PROGRAM_ID" "${ANCHOR_OPTS[@]}" -o "$FETCHED"
+if ! python3 - "$IDL_PATH" "$FETCHED" <<'EOF'
+import json, sys
+local, remote = (json.load(open(p)) for p in sys.argv[1:3])
+sys.exit(0 if local == remote else 1)
+EOF
+then
+    echo "Error: on-chain IDL does not match $IDL_PATH"
+    exit 1
+fi
+
+echo "On-chain IDL matches $IDL_PATH"
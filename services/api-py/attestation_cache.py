@@ -0,0 +1,81 @@
+"""Per-process cache of on-chain attestation lookups.
+
+`GET /api/attestation/{hash}` reads the attestation account over RPC on
+every request, so a page polling the same hash every few seconds costs
+one RPC call per poll. Results are kept for `ATTESTATION_CACHE_TTL_SECS`;
+misses (no account) for `ATTESTATION_CACHE_NEGATIVE_TTL_SECS`, so lookups
+of made-up hashes can't be used to hammer the RPC node either. The least
+recently used entry is evicted past `ATTESTATION_CACHE_SIZE`.
+
+Like the rate limiter, each worker keeps its own cache. Hits and misses
+are counted in r3l_cache_hits_total / r3l_cache_misses_total.
+"""
+
+import asyncio
+import time
+from collections import OrderedDict
+from functools import cache
+
+from config import Settings
+from metrics import CACHE_HITS, CACHE_MISSES
+from solana_read import lookup_attestation
+
+
+class TtlLruCache:
+    """At most `max_size` entries, each expiring `ttl` seconds after it was
+    stored (`negative_ttl` for `None` values). `name` is the metrics label."""
+
+    def __init__(self, name: str, max_size: int, ttl: float, negative_ttl: float):
+        self.name = name
+        self.max_size = max_size
+        self.ttl = ttl
+        self.negative_ttl = negative_ttl
+        self._entries: OrderedDict[str, tuple[float, object]] = OrderedDict()  # key -> (expires_at, value)
+
+    def get(self, key: str) -> tuple[bool, object]:
+        """`(True, value)` for a live entry, otherwise `(False, None)`."""
+        entry = self._entries.get(key)
+        if entry is None or entry[0] <= time.monotonic():
+            if entry is not None:
+                del self._entries[key]
+            CACHE_MISSES.labels(self.name).inc()
+            return False, None
+        self._entries.move_to_end(key)
+        CACHE_HITS.labels(self.name).inc()
+        return True, entry[1]
+
+    def put(self, key: str, value: object) -> None:
+        ttl = self.negative_ttl if value is None else self.ttl
+        if ttl <= 0 or self.max_size <= 0:
+            return
+        self._entries[key] = (time.monotonic() + ttl, value)
+        self._entries.move_to_end(key)
+        while len(self._entries) > self.max_size:
+            self._entries.popitem(last=False)
+
+    def clear(self) -> None:
+        self._entries.clear()
+
+
+@cache
+def attestation_cache() -> TtlLruCache:
+    settings = Settings()
+    return TtlLruCache(
+        "attestation",
+        settings.attestation_cache_size,
+        settings.attestation_cache_ttl_secs,
+        settings.attestation_cache_negative_ttl_secs,
+    )
+
+
+async def cached_lookup_attestation(settings: Settings, content_hash: str) -> dict | None:
+    """`lookup_attestation` through the cache. RPC errors are not cached."""
+    cache = attestation_cache()
+    hit, value = cache.get(content_hash)
+    if hit:
+        return value
+    value = await asyncio.to_thread(
+        lookup_attestation, settings.solana_rpc_url, settings.program_id, content_hash
+    )
+    cache.put(content_hash, value)
+    return value
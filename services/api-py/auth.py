@@ -1,5 +1,8 @@
+import secrets
+
 from fastapi import Header, HTTPException
 
+from config import Settings
 import db
 
 
@@ -36,3 +39,10 @@ async def require_org_admin(x_api_key: str = Header(...)) -> dict:
     if not org:
         raise HTTPException(404, "organization not found")
     return {"org": org, "key": org_key}
+
+
+async def require_admin_key(x_admin_key: str = Header(...)):
+    """Operator-only endpoints; disabled unless ADMIN_API_KEY is set."""
+    expected = Settings().admin_api_key
+    if not expected or not secrets.compare_digest(x_admin_key, expected):
+        raise HTTPException(403, "requires admin API key")
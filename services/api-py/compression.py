@@ -0,0 +1,120 @@
+"""Response compression for /api/ JSON responses (gzip, br, deflate).
+
+Picks an encoding from the client's Accept-Encoding header. Only JSON
+bodies are compressed: stored content downloads and the SPA are passed
+through untouched, so large files are never buffered here.
+"""
+
+import gzip
+import zlib
+
+import brotli
+
+MIN_SIZE = 1024  # bytes; smaller bodies aren't worth the CPU
+
+# Preference order when the client weighs encodings equally
+ENCODERS = {
+    "br": lambda body: brotli.compress(body, quality=5),
+    "gzip": lambda body: gzip.compress(body, compresslevel=6),
+    "deflate": lambda body: zlib.compress(body, 6),
+}
+
+
+def _weights(accept_encoding: str) -> dict[str, float]:
+    """q-value per supported encoding named (or matched by `*`) in Accept-Encoding."""
+    weights: dict[str, float] = {}
+    for part in accept_encoding.split(","):
+        name, _, params = part.strip().partition(";")
+        name = name.strip().lower()
+        q = 1.0
+        params = params.strip()
+        if params.startswith("q="):
+            try:
+                q = float(params[2:])
+            except ValueError:
+                q = 0.0
+        if name == "*":
+            for enc in ENCODERS:
+                weights.setdefault(enc, q)
+        elif name in ENCODERS:
+            weights[name] = q
+    return weights
+
+
+def choose_encoding(accept_encoding: str) -> str | None:
+    """Best supported encoding from an Accept-Encoding value, or None."""
+    weights = _weights(accept_encoding)
+    best = None
+    for enc in ENCODERS:
+        q = weights.get(enc, 0.0)
+        if q > 0 and (best is None or q > weights[best]):
+            best = enc
+    return best
+
+
+def accepts_encoding(accept_encoding: str, encoding: str) -> bool:
+    """True if the client accepts `encoding` at all (q > 0)."""
+    return _weights(accept_encoding).get(encoding, 0.0) > 0
+
+
+class CompressionMiddleware:
+    """ASGI middleware compressing JSON responses under `path_prefix`."""
+
+    def __init__(self, app, path_prefix: str = "/api/", min_size: int = MIN_SIZE):
+        self.app = app
+        self.path_prefix = path_prefix
+        self.min_size = min_size
+
+    async def __call__(self, scope, receive, send):
+        if scope["type"] != "http" or not scope["path"].startswith(self.path_prefix):
+            await self.app(scope, receive, send)
+            return
+
+        accept = ""
+        for key, value in scope["headers"]:
+            if key == b"accept-encoding":
+                accept = value.decode("latin-1")
+                break
+        encoding = choose_encoding(accept)
+        if encoding is None:
+            await self.app(scope, receive, send)
+            return
+
+        start = None
+        chunks: list[bytes] = []
+        passthrough = False
+
+        async def send_wrapper(message):
+            nonlocal start, passthrough
+            if message["type"] == "http.response.start":
+                headers = {k.lower(): v for k, v in message.get("headers", [])}
+                content_type = headers.get(b"content-type", b"")
+                if b"json" not in content_type or b"content-encoding" in headers:
+                    passthrough = True
+                    await send(message)
+                else:
+                    start = message
+                return
+
+            if passthrough or message["type"] != "http.response.body":
+                await send(message)
+                return
+
+            chunks.append(message.get("body", b""))
+            if message.get("more_body", False):
+                return
+
+            body = b"".join(chunks)
+            headers = [(k, v) for k, v in start.get("headers", []) if k.lower() != b"content-length"]
+            if not any(k.lower() == b"vary" and b"accept-encoding" in v.lower() for k, v in headers):
+                headers.append((b"vary", b"Accept-Encoding"))
+
+            if len(body) >= self.min_size:
+                body = ENCODERS[encoding](body)
+                headers.append((b"content-encoding", encoding.encode()))
+            headers.append((b"content-length", str(len(body)).encode()))
+
+            await send({**start, "headers": headers})
+            await send({"type": "http.response.body", "body": body})
+
+        await self.app(scope, receive, send_wrapper)
@@ -1,9 +1,9 @@
 import time
 
-from sqlalchemy import select, text
+from sqlalchemy import delete, func, or_, select, text
 from sqlalchemy.ext.asyncio import AsyncSession, async_sessionmaker, create_async_engine
 
-from models import Attestation, Customer, Organization, OrgApiKey, Base
+from models import Attestation, Customer, EdgeChallenge, Organization, OrgApiKey, Webhook, Base
 
 _engine = None
 _session_factory: async_sessionmaker[AsyncSession] | None = None
@@ -32,6 +32,20 @@ async def init_db(database_url: str):
             "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS stored BOOLEAN DEFAULT false",
             "ALTER TABLE customers ADD COLUMN IF NOT EXISTS privacy_mode BOOLEAN DEFAULT false",
             "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS private BOOLEAN DEFAULT false",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS phash VARCHAR",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS ingredients JSON",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS location_country VARCHAR",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS location_region VARCHAR",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS location_city VARCHAR",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS location_lat DOUBLE PRECISION",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS location_lon DOUBLE PRECISION",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS manifest_hash VARCHAR",
+            "CREATE INDEX IF NOT EXISTS ix_attestations_manifest_hash ON attestations(manifest_hash)",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS assertions_digest VARCHAR",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS software_agent_canonical VARCHAR",
+            "CREATE INDEX IF NOT EXISTS ix_attestations_software_agent_canonical ON attestations(software_agent_canonical)",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS revoked BOOLEAN DEFAULT false",
+            "ALTER TABLE attestations ADD COLUMN IF NOT EXISTS revocation_reason VARCHAR",
             "CREATE UNIQUE INDEX IF NOT EXISTS ix_customers_email ON customers(email) WHERE email IS NOT NULL",
         ]
         for sql in migrations:
@@ -46,6 +60,18 @@ async def close_db():
         _session_factory = None
 
 
+async def ping() -> bool:
+    """True if the database is initialized and answers a trivial query."""
+    if _engine is None:
+        return False
+    try:
+        async with _engine.connect() as conn:
+            await conn.execute(text("SELECT 1"))
+        return True
+    except Exception:
+        return False
+
+
 def get_session() -> AsyncSession:
     if _session_factory is None:
         raise RuntimeError("DB not initialized")
@@ -65,6 +91,7 @@ async def insert_attestation(
     issuer: str | None = None,
     common_name: str | None = None,
     software_agent: str | None = None,
+    software_agent_canonical: str | None = None,
     signing_time: str | None = None,
     cert_fingerprint: str | None = None,
     email_domain: str | None = None,
@@ -72,8 +99,17 @@ async def insert_attestation(
     submitted_by: str | None = None,
     verifier_version: str | None = None,
     trust_bundle_hash: str | None = None,
+    manifest_hash: str | None = None,
+    assertions_digest: str | None = None,
     tlsh_hash: str | None = None,
+    phash: str | None = None,
     clip_embedding: list[float] | None = None,
+    ingredients: list | None = None,
+    location_country: str | None = None,
+    location_region: str | None = None,
+    location_city: str | None = None,
+    location_lat: float | None = None,
+    location_lon: float | None = None,
     org_id: int | None = None,
     org_domain: str | None = None,
     content_type: str = "file",
@@ -107,6 +143,7 @@ async def insert_attestation(
             issuer=issuer,
             common_name=common_name,
             software_agent=software_agent,
+            software_agent_canonical=software_agent_canonical,
             signing_time=signing_time,
             cert_fingerprint=cert_fingerprint,
             email_domain=email_domain,
@@ -114,8 +151,17 @@ async def insert_attestation(
             submitted_by=submitted_by,
             verifier_version=verifier_version,
             trust_bundle_hash=trust_bundle_hash,
+            manifest_hash=manifest_hash,
+            assertions_digest=assertions_digest,
             tlsh_hash=tlsh_hash,
+            phash=phash,
             clip_embedding=clip_embedding,
+            ingredients=ingredients,
+            location_country=location_country,
+            location_region=location_region,
+            location_city=location_city,
+            location_lat=location_lat,
+            location_lon=location_lon,
             org_id=org_id,
             org_domain=org_domain,
             content_type=content_type,
@@ -143,17 +189,112 @@ async def get_attestation(content_hash: str) -> dict | None:
         return row.to_dict()
 
 
-async def list_attestations(include_private: bool = False) -> list[dict]:
+async def get_attestations(content_hashes: list[str]) -> dict[str, dict]:
+    """Fetch several attestation rows at once, keyed by content hash."""
+    if _session_factory is None or not content_hashes:
+        return {}
+    async with get_session() as session:
+        stmt = select(Attestation).where(Attestation.content_hash.in_(content_hashes))
+        rows = (await session.execute(stmt)).scalars().all()
+        return {r.content_hash: r.to_dict() for r in rows}
+
+
+async def mark_attestation_revoked(content_hash: str, reason: str):
+    """Mirror an on-chain revoke_attestation onto the DB row, if there is one."""
+    if _session_factory is None:
+        return
+    async with get_session() as session:
+        stmt = select(Attestation).where(Attestation.content_hash == content_hash)
+        row = (await session.execute(stmt)).scalar_one_or_none()
+        if row is None:
+            return
+        row.revoked = True
+        row.revocation_reason = reason
+        await session.commit()
+
+
+def _attestation_filters(
+    include_private: bool = False,
+    include_revoked: bool = False,
+    has_c2pa: bool | None = None,
+    trust_level: str | None = None,
+    proof_type: str | None = None,
+    issuer: str | None = None,
+    software_agent: str | None = None,
+    after: int | None = None,
+    before: int | None = None,
+    from_ts: int | None = None,
+    to_ts: int | None = None,
+    submitter: str | None = None,
+    kind: str | None = None,
+) -> list:
+    """WHERE clauses shared by list_attestations and count_attestations."""
+    conds = []
+    if not include_private:
+        conds.append(Attestation.private == False)
+    if not include_revoked:
+        conds.append(Attestation.revoked == False)
+    if has_c2pa is not None:
+        conds.append(Attestation.has_c2pa == has_c2pa)
+    if trust_level is not None:
+        conds.append(Attestation.trust_list_match == trust_level)
+    if proof_type is not None:
+        conds.append(Attestation.proof_type == proof_type)
+    if issuer is not None:
+        conds.append(Attestation.issuer == issuer)
+    if software_agent is not None:
+        conds.append(Attestation.software_agent_canonical == software_agent)
+    if after is not None:
+        conds.append(Attestation.created_at > after)
+    if before is not None:
+        conds.append(Attestation.created_at < before)
+    if from_ts is not None:
+        conds.append(Attestation.created_at >= from_ts)
+    if to_ts is not None:
+        conds.append(Attestation.created_at <= to_ts)
+    if submitter is not None:
+        conds.append(or_(Attestation.wallet_pubkey == submitter, Attestation.submitted_by == submitter))
+    if kind == "c2pa":
+        conds.append(Attestation.has_c2pa == True)
+    elif kind == "identity":
+        conds.append(Attestation.email_domain.is_not(None))
+    elif kind == "wallet":
+        conds.append(Attestation.wallet_pubkey.is_not(None))
+    return conds
+
+
+async def list_attestations(limit: int | None = None, offset: int = 0, **filters) -> list[dict]:
+    """List attestations, newest first. All given filters must match.
+
+    `after`/`before` are exclusive bounds, `from_ts`/`to_ts` inclusive.
+    `submitter` matches the submitting wallet pubkey or edge node name.
+    `kind` keeps attestations carrying that signal: c2pa (a manifest),
+    identity (an email domain) or wallet (a wallet signature).
+    `limit`/`offset` select one page; no limit returns every match.
+    """
     if _session_factory is None:
         return []
     async with get_session() as session:
-        stmt = select(Attestation).order_by(Attestation.created_at.desc())
-        if not include_private:
-            stmt = stmt.where(Attestation.private == False)
+        stmt = (
+            select(Attestation)
+            .where(*_attestation_filters(**filters))
+            .order_by(Attestation.created_at.desc(), Attestation.id.desc())
+            .offset(offset)
+            .limit(limit)
+        )
         rows = (await session.execute(stmt)).scalars().all()
         return [r.to_dict() for r in rows]
 
 
+async def count_attestations(**filters) -> int:
+    """Number of attestations list_attestations matches with the same filters."""
+    if _session_factory is None:
+        return 0
+    async with get_session() as session:
+        stmt = select(func.count()).select_from(Attestation).where(*_attestation_filters(**filters))
+        return (await session.execute(stmt)).scalar_one()
+
+
 # ── Customer functions ──────────────────────────────────────────────
 
 async def insert_customer(
@@ -294,6 +435,17 @@ async def get_all_with_tlsh() -> list[dict]:
         return [r.to_dict() for r in rows]
 
 
+async def get_all_phashes() -> list[tuple[str, str]]:
+    """Return (content_hash, phash) for all public attestations with a pHash."""
+    if _session_factory is None:
+        return []
+    async with get_session() as session:
+        stmt = select(Attestation.content_hash, Attestation.phash).where(
+            Attestation.phash.isnot(None), Attestation.private.is_(False),
+        )
+        return [(r.content_hash, r.phash) for r in (await session.execute(stmt)).all()]
+
+
 async def search_similar_clip(
     embedding: list[float], limit: int = 20,
 ) -> list[dict]:
@@ -357,6 +509,15 @@ async def get_organization_by_domain(domain: str) -> dict | None:
         return row.to_dict() if row else None
 
 
+async def get_organization_by_dns_token(dns_token: str) -> dict | None:
+    if _session_factory is None:
+        return None
+    async with get_session() as session:
+        stmt = select(Organization).where(Organization.dns_token == dns_token)
+        row = (await session.execute(stmt)).scalar_one_or_none()
+        return row.to_dict() if row else None
+
+
 async def get_organization_by_id(org_id: int) -> dict | None:
     if _session_factory is None:
         return None
@@ -434,3 +595,68 @@ async def revoke_org_api_key(key_id: int, org_id: int) -> bool:
         row.revoked = True
         await session.commit()
         return True
+
+
+# ── Webhook functions ──────────────────────────────────────────────
+
+async def insert_webhook(*, url: str, secret: str | None = None) -> dict:
+    if _session_factory is None:
+        raise RuntimeError("DB not initialized")
+    async with get_session() as session:
+        row = Webhook(url=url, secret=secret, created_at=int(time.time()))
+        session.add(row)
+        await session.commit()
+        await session.refresh(row)
+        return row.to_dict()
+
+
+async def list_webhooks() -> list[dict]:
+    if _session_factory is None:
+        return []
+    async with get_session() as session:
+        rows = (await session.execute(select(Webhook).order_by(Webhook.id))).scalars().all()
+        return [r.to_dict() for r in rows]
+
+
+async def delete_webhook(webhook_id: int) -> bool:
+    if _session_factory is None:
+        return False
+    async with get_session() as session:
+        row = await session.get(Webhook, webhook_id)
+        if not row:
+            return False
+        await session.delete(row)
+        await session.commit()
+        return True
+
+
+# ── Edge challenge functions ───────────────────────────────────────
+
+async def insert_edge_challenge(nonce: str, max_age_secs: int):
+    """Store a new challenge nonce and drop ones older than `max_age_secs`."""
+    if _session_factory is None:
+        raise RuntimeError("DB not initialized")
+    now = int(time.time())
+    async with get_session() as session:
+        await session.execute(delete(EdgeChallenge).where(EdgeChallenge.created_at < now - max_age_secs))
+        session.add(EdgeChallenge(nonce=nonce, created_at=now))
+        await session.commit()
+
+
+async def consume_edge_challenge(nonce: str, max_age_secs: int) -> bool:
+    """Delete the challenge if it exists and is fresh. True if it did, so
+    each nonce is accepted once even across workers."""
+    if _session_factory is None:
+        return False
+    async with get_session() as session:
+        stmt = (
+            delete(EdgeChallenge)
+            .where(
+                EdgeChallenge.nonce == nonce,
+                EdgeChallenge.created_at >= int(time.time()) - max_age_secs,
+            )
+            .returning(EdgeChallenge.nonce)
+        )
+        consumed = (await session.execute(stmt)).scalar_one_or_none()
+        await session.commit()
+        return consumed is not None
@@ -0,0 +1,57 @@
+"""Idempotency-Key replay for attestation submissions.
+
+An edge node that retries a POST after a timeout would otherwise pay for
+a second Solana transaction attempt. When a request carries
+`Idempotency-Key`, its successful response is remembered for 24 hours
+under `<api_key>:<idempotency_key>`; a repeat gets the stored body back
+with `Idempotency-Replayed: true` without touching the chain. Keys are
+scoped per API key, so clients can't collide with (or read) each other's
+responses, and requests without an API key or without the header are
+processed normally. Errors are not stored, so a failed request can be
+retried with the same key.
+
+Like the attestation lookup cache, each worker keeps its own store.
+"""
+
+from functools import cache
+
+from fastapi import HTTPException
+from fastapi.responses import JSONResponse
+
+from attestation_cache import TtlLruCache
+
+TTL_SECS = 24 * 3600
+MAX_ENTRIES = 10_000
+MAX_KEY_LEN = 255
+
+REPLAYED_HEADER = "Idempotency-Replayed"
+
+
+@cache
+def _store() -> TtlLruCache:
+    return TtlLruCache("idempotency", MAX_ENTRIES, TTL_SECS, 0)
+
+
+def store_key(api_key: str | None, idempotency_key: str | None) -> str | None:
+    """Store key for this request, or None if it isn't idempotent."""
+    if not api_key or not idempotency_key:
+        return None
+    if len(idempotency_key) > MAX_KEY_LEN:
+        raise HTTPException(400, f"Idempotency-Key longer than {MAX_KEY_LEN} characters")
+    return f"{api_key}:{idempotency_key}"
+
+
+def replay(key: str | None) -> JSONResponse | None:
+    """The stored response for `key`, if there is one."""
+    if key is None:
+        return None
+    hit, body = _store().get(key)
+    if not hit:
+        return None
+    return JSONResponse(body, headers={REPLAYED_HEADER: "true"})
+
+
+def remember(key: str | None, body: dict) -> dict:
+    if key is not None:
+        _store().put(key, body)
+    return body
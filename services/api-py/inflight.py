@@ -0,0 +1,32 @@
+"""Per-process guard against concurrent attestation submissions.
+
+Two requests for the same content hash would both miss the idempotency
+lookup, derive the same PDA and send a transaction; one then fails on-chain.
+Holding the hash for the duration of a submission turns the loser into a
+409 the client can retry, after which the lookup finds the attestation.
+"""
+
+from contextlib import contextmanager
+
+from fastapi import HTTPException
+
+RETRY_AFTER_SECONDS = 5
+
+_in_flight_attestations: set[str] = set()
+
+
+@contextmanager
+def attestation_in_flight(content_hash_hex: str):
+    """Claim `content_hash_hex` until the block exits, or raise 409 if taken."""
+    key = content_hash_hex.lower()
+    if key in _in_flight_attestations:
+        raise HTTPException(
+            409,
+            "an attestation for this content hash is already being submitted",
+            headers={"Retry-After": str(RETRY_AFTER_SECONDS)},
+        )
+    _in_flight_attestations.add(key)
+    try:
+        yield
+    finally:
+        _in_flight_attestations.discard(key)
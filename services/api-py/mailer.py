@@ -0,0 +1,111 @@
+"""Pooled SMTP connections for verification emails.
+
+Opening an SMTP_SSL connection (TLS handshake + login) per email exhausts
+the server's connection limit when many verifications start at once. The
+pool keeps at most `smtp_pool_size` connections open and reuses idle ones,
+replacing any the server has dropped.
+
+Call init_mailer(settings) once at startup, then send_email(msg) from a
+worker thread (asyncio.to_thread) — sending blocks.
+"""
+
+import logging
+import queue
+import smtplib
+import threading
+from email.message import Message
+
+log = logging.getLogger(__name__)
+
+SMTP_SSL_PORT = 465
+
+
+class SmtpPool:
+    """At most `size` logged-in SMTP_SSL connections, shared across threads."""
+
+    def __init__(self, host: str, user: str, password: str, timeout: int, size: int):
+        self.host = host
+        self.user = user
+        self.password = password
+        self.timeout = timeout
+        self._idle: queue.LifoQueue[smtplib.SMTP_SSL] = queue.LifoQueue()
+        self._slots = threading.BoundedSemaphore(max(size, 1))
+
+    def _connect(self) -> smtplib.SMTP_SSL:
+        server = smtplib.SMTP_SSL(self.host, SMTP_SSL_PORT, timeout=self.timeout)
+        try:
+            server.login(self.user, self.password)
+        except BaseException:
+            server.close()
+            raise
+        return server
+
+    def _checkout(self) -> smtplib.SMTP_SSL:
+        """An idle connection that still answers NOOP, else a new one."""
+        while True:
+            try:
+                server = self._idle.get_nowait()
+            except queue.Empty:
+                return self._connect()
+            try:
+                if server.noop()[0] == 250:
+                    return server
+            except (smtplib.SMTPException, OSError):
+                pass
+            server.close()
+
+    def send(self, msg: Message):
+        # Waits for a free connection rather than opening one past the limit
+        if not self._slots.acquire(timeout=self.timeout):
+            raise TimeoutError("no free SMTP connection")
+        try:
+            server = self._checkout()
+            try:
+                server.send_message(msg)
+            except BaseException:
+                server.close()
+                raise
+            self._idle.put(server)
+        finally:
+            self._slots.release()
+
+    def close(self):
+        while True:
+            try:
+                server = self._idle.get_nowait()
+            except queue.Empty:
+                return
+            try:
+                server.quit()
+            except (smtplib.SMTPException, OSError):
+                server.close()
+
+
+_pool: SmtpPool | None = None
+
+
+def init_mailer(settings) -> SmtpPool | None:
+    """Create the pool if SMTP is configured (dev mode sends no email)."""
+    global _pool
+    if not settings.smtp_host:
+        return None
+    _pool = SmtpPool(
+        host=settings.smtp_host,
+        user=settings.smtp_user,
+        password=settings.smtp_pass,
+        timeout=settings.smtp_timeout_secs,
+        size=settings.smtp_pool_size,
+    )
+    log.info("SMTP: %s (pool size %d)", settings.smtp_host, settings.smtp_pool_size)
+    return _pool
+
+
+def close_mailer():
+    if _pool is not None:
+        _pool.close()
+
+
+def send_email(msg: Message):
+    if _pool is None:
+        raise RuntimeError("Mailer not initialized — call init_mailer() first")
+    _pool.send(msg)
@@ -1,3 +1,4 @@
+import logging
 import os
 import threading
 
@@ -8,25 +9,49 @@ load_dotenv(os.path.join(os.path.dirname(__file__), "../../.env"))
 
 from pathlib import Path
 
-from fastapi import FastAPI
+from fastapi import Depends, FastAPI
+from fastapi.exceptions import RequestValidationError
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.responses import FileResponse
 from fastapi.staticfiles import StaticFiles
+from starlette.exceptions import HTTPException
 
+from compression import CompressionMiddleware
 from config import Settings
-from routes import verify, attest, prove, submit, attestation, edge, query, similar, org, did_route, auth_routes, content, developer
+from mailer import close_mailer, init_mailer
+from metrics import MetricsMiddleware, metrics_endpoint
+from openapi import build_openapi
+from ratelimit import limit_by_ip
+from request_id import (
+    LOG_FORMAT,
+    RequestIdMiddleware,
+    http_exception_handler,
+    install_log_request_ids,
+    unhandled_exception_handler,
+    validation_exception_handler,
+)
+from revocation import start_revocation_refresh, stop_revocation_refresh
+from routes import verify, attest, prove, submit, attestation, edge, query, similar, org, did_route, auth_routes, content, developer, graphql_api, deduplicate, health, idl, admin, webhooks, stats
 import db
-from similarity import init_similarity
+from similarity import init_similarity, load_phash_index
 from storage import init_storage
 
+install_log_request_ids()
+logging.basicConfig(level=logging.INFO, format=LOG_FORMAT)
+
 settings = Settings()
-app = FastAPI()
+# Under /api/ so the SPA keeps its own /docs page
+app = FastAPI(openapi_url="/api/openapi.json", docs_url="/api/docs", redoc_url=None)
+app.openapi = lambda: build_openapi(app)
 
 
 @app.on_event("startup")
 async def startup():
     await db.init_db(settings.database_url)
+    await load_phash_index()
     init_storage(settings)
+    init_mailer(settings)
+    start_revocation_refresh(settings)
     # Load CLIP model in background so health checks pass immediately
     threading.Thread(target=init_similarity, daemon=True).start()
 
@@ -34,6 +59,8 @@ async def startup():
 @app.on_event("shutdown")
 async def shutdown():
     await db.close_db()
+    close_mailer()
+    stop_revocation_refresh()
 
 # CORS — allow all (matches Rust API)
 app.add_middleware(
@@ -41,12 +68,20 @@ app.add_middleware(
     allow_origins=["*"],
     allow_methods=["*"],
     allow_headers=["*"],
+    expose_headers=["X-Total-Count", "X-Request-Id"],
 )
 
+# gzip / br / deflate for /api/ JSON responses over 1 KB
+app.add_middleware(CompressionMiddleware, path_prefix="/api/")
 
-@app.get("/api/health")
-async def health():
-    return "ok"
+# Per-route request counts and latency for /metrics
+app.add_middleware(MetricsMiddleware)
+
+# X-Request-Id on every response and log line (outermost, so it covers the rest)
+app.add_middleware(RequestIdMiddleware)
+app.add_exception_handler(HTTPException, http_exception_handler)
+app.add_exception_handler(RequestValidationError, validation_exception_handler)
+app.add_exception_handler(Exception, unhandled_exception_handler)
 
 
 @app.post("/api/admin/reset-db")
@@ -55,26 +90,38 @@ async def reset_db():
     from sqlalchemy import text
     from models import Base
     async with db._engine.begin() as conn:
-        await conn.execute(text("DROP TABLE IF EXISTS attestations, customers, organizations, org_api_keys CASCADE"))
+        await conn.execute(text("DROP TABLE IF EXISTS attestations, customers, organizations, org_api_keys, webhooks CASCADE"))
         await conn.execute(text("CREATE EXTENSION IF NOT EXISTS vector"))
         await conn.run_sync(Base.metadata.create_all)
     return {"status": "ok", "message": "All tables dropped and recreated"}
 
 
 # API routes
-app.include_router(verify.router, prefix="/api")
+app.include_router(health.router, prefix="/api/health", dependencies=[Depends(limit_by_ip)])
+app.include_router(verify.router, prefix="/api", dependencies=[Depends(limit_by_ip)])
 app.include_router(attest.router, prefix="/api")
 app.include_router(prove.router, prefix="/api")
 app.include_router(submit.router, prefix="/api")
 app.include_router(attestation.router, prefix="/api")
+app.include_router(stats.router, prefix="/api")
 app.include_router(auth_routes.router, prefix="/api/auth")
 app.include_router(edge.router, prefix="/api/edge")
+app.include_router(admin.router, prefix="/api/admin")
+app.include_router(webhooks.router, prefix="/api/webhooks")
 app.include_router(query.router, prefix="/api")
 app.include_router(similar.router, prefix="/api/v1/similar")
+app.include_router(deduplicate.router, prefix="/api")
+app.include_router(idl.router, prefix="/api")
 app.include_router(org.router, prefix="/api/org")
 app.include_router(did_route.router, prefix="/api")
 app.include_router(content.router, prefix="/api")
 app.include_router(developer.router, prefix="/api/v1")
+app.include_router(graphql_api.router, prefix="/api/graphql")
+if settings.graphiql_enabled:
+    app.include_router(graphql_api.graphiql_router, prefix="/api/graphiql")
+
+# Prometheus scrape target; no API key or rate limit (must be before SPA fallback)
+app.add_api_route("/metrics", metrics_endpoint, methods=["GET"], include_in_schema=False)
 
 # .well-known DID document (must be before SPA fallback)
 from routes.did_route import platform_did
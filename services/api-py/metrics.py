@@ -0,0 +1,100 @@
+"""Prometheus metrics, served as text at GET /metrics.
+
+Everything is registered in `REGISTRY` rather than the prometheus_client
+default so /metrics shows only what is defined here (plus process stats).
+Values are per process: with several workers each exposes its own
+counts, so scrape every worker or run one.
+"""
+
+import time
+
+from prometheus_client import (
+    CONTENT_TYPE_LATEST,
+    CollectorRegistry,
+    Counter,
+    Histogram,
+    ProcessCollector,
+    generate_latest,
+)
+from starlette.responses import Response
+
+REGISTRY = CollectorRegistry()
+ProcessCollector(registry=REGISTRY)
+
+# Routes under this prefix also count towards r3l_verify_requests_total
+VERIFY_PATH_PREFIX = "/api/verify"
+
+HTTP_REQUESTS = Counter(
+    "r3l_http_requests_total", "HTTP requests by route template and status code",
+    ["method", "route", "status"], registry=REGISTRY,
+)
+HTTP_LATENCY = Histogram(
+    "r3l_http_request_duration_seconds", "HTTP request latency by route template",
+    ["method", "route"], registry=REGISTRY,
+)
+VERIFY_REQUESTS = Counter(
+    "r3l_verify_requests_total", "/api/verify* requests by outcome",
+    ["result"], registry=REGISTRY,
+)
+ATTEST_REQUESTS = Counter(
+    "r3l_attest_requests_total", "Attestations created, by proof type",
+    ["proof_type"], registry=REGISTRY,
+)
+RPC_LATENCY = Histogram(
+    "r3l_rpc_latency_seconds", "Solana RPC call latency",
+    ["operation"], registry=REGISTRY,
+)
+CACHE_HITS = Counter(
+    "r3l_cache_hits_total", "In-process cache hits",
+    ["cache"], registry=REGISTRY,
+)
+CACHE_MISSES = Counter(
+    "r3l_cache_misses_total", "In-process cache misses",
+    ["cache"], registry=REGISTRY,
+)
+PROOF_GENERATION = Histogram(
+    "r3l_proof_generation_seconds", "Wall time of the prover binary per /api/prove request",
+    buckets=(1, 5, 15, 30, 60, 120, 300, 600, 1200, float("inf")),
+    registry=REGISTRY,
+)
+
+
+async def metrics_endpoint():
+    return Response(generate_latest(REGISTRY), media_type=CONTENT_TYPE_LATEST)
+
+
+class MetricsMiddleware:
+    """ASGI middleware recording request count and latency per route.
+
+    Labels use the matched route template (`/api/attestation/{hash}`), not
+    the raw path, so label cardinality stays bounded; unmatched paths are
+    recorded as `unmatched`.
+    """
+
+    def __init__(self, app):
+        self.app = app
+
+    async def __call__(self, scope, receive, send):
+        if scope["type"] != "http":
+            await self.app(scope, receive, send)
+            return
+
+        status = 500
+        start = time.perf_counter()
+
+        async def send_wrapper(message):
+            nonlocal status
+            if message["type"] == "http.response.start":
+                status = message["status"]
+            await send(message)
+
+        try:
+            await self.app(scope, receive, send_wrapper)
+        finally:
+            route = scope.get("route")
+            template = getattr(route, "path", None) or "unmatched"
+            method = scope["method"]
+            HTTP_REQUESTS.labels(method, template, str(status)).inc()
+            HTTP_LATENCY.labels(method, template).observe(time.perf_counter() - start)
+            if template.startswith(VERIFY_PATH_PREFIX):
+                VERIFY_REQUESTS.labels("ok" if status < 400 else "error").inc()
@@ -1,4 +1,4 @@
-from sqlalchemy import BigInteger, Boolean, Column, Integer, String, UniqueConstraint
+from sqlalchemy import JSON, BigInteger, Boolean, Column, Float, Integer, String, UniqueConstraint
 from sqlalchemy.orm import DeclarativeBase, Mapped, mapped_column
 from pgvector.sqlalchemy import Vector
 
@@ -23,6 +23,7 @@ class Attestation(Base):
     issuer: Mapped[str | None] = mapped_column(String)
     common_name: Mapped[str | None] = mapped_column(String)
     software_agent: Mapped[str | None] = mapped_column(String)
+    software_agent_canonical: Mapped[str | None] = mapped_column(String, index=True)
     signing_time: Mapped[str | None] = mapped_column(String)
     cert_fingerprint: Mapped[str | None] = mapped_column(String)
     email_domain: Mapped[str | None] = mapped_column(String)
@@ -32,14 +33,25 @@ class Attestation(Base):
     org_domain: Mapped[str | None] = mapped_column(String)
     verifier_version: Mapped[str | None] = mapped_column(String)
     trust_bundle_hash: Mapped[str | None] = mapped_column(String)
+    manifest_hash: Mapped[str | None] = mapped_column(String, index=True)
+    assertions_digest: Mapped[str | None] = mapped_column(String)
     tlsh_hash: Mapped[str | None] = mapped_column(String)
+    phash: Mapped[str | None] = mapped_column(String)  # 64-bit DCT pHash, 16 hex chars
     clip_embedding = Column(Vector(512), nullable=True)
+    ingredients = Column(JSON, nullable=True)  # verifier `ingredients` of the active manifest
+    location_country: Mapped[str | None] = mapped_column(String)
+    location_region: Mapped[str | None] = mapped_column(String)
+    location_city: Mapped[str | None] = mapped_column(String)
+    location_lat: Mapped[float | None] = mapped_column(Float)
+    location_lon: Mapped[float | None] = mapped_column(Float)
     content_type: Mapped[str] = mapped_column(String, nullable=False, default="file")
     source_url: Mapped[str | None] = mapped_column(String)
     mime_type: Mapped[str | None] = mapped_column(String)
     content_size: Mapped[int | None] = mapped_column(Integer)
     stored: Mapped[bool] = mapped_column(Boolean, nullable=False, default=False)
     private: Mapped[bool] = mapped_column(Boolean, nullable=False, default=False)
+    revoked: Mapped[bool] = mapped_column(Boolean, nullable=False, default=False)
+    revocation_reason: Mapped[str | None] = mapped_column(String)
     created_at: Mapped[int] = mapped_column(BigInteger, nullable=False)
 
     def to_dict(self) -> dict:
@@ -100,3 +112,23 @@ class OrgApiKey(Base):
 
     def to_dict(self) -> dict:
         return {c.name: getattr(self, c.name) for c in self.__table__.columns}
+
+
+class Webhook(Base):
+    __tablename__ = "webhooks"
+
+    id: Mapped[int] = mapped_column(Integer, primary_key=True)
+    url: Mapped[str] = mapped_column(String, nullable=False)
+    secret: Mapped[str | None] = mapped_column(String)
+    created_at: Mapped[int] = mapped_column(BigInteger, nullable=False)
+
+    def to_dict(self) -> dict:
+        return {c.name: getattr(self, c.name) for c in self.__table__.columns}
+
+
+class EdgeChallenge(Base):
+    """Single-use nonce handed out by GET /api/edge/challenge."""
+    __tablename__ = "edge_challenges"
+
+    nonce: Mapped[str] = mapped_column(String, primary_key=True)
+    created_at: Mapped[int] = mapped_column(BigInteger, nullable=False, index=True)
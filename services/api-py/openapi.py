@@ -0,0 +1,50 @@
+"""OpenAPI spec for the API, served at /api/openapi.json (Swagger UI at /api/docs).
+
+FastAPI derives paths and schemas from the routes. The key headers are
+plain `Header` parameters, so this adds the matching security schemes and
+marks each operation that takes `X-API-Key` or `X-Admin-Key` with them;
+an optional key is listed as an alternative to no authentication.
+"""
+
+from fastapi import FastAPI
+from fastapi.openapi.utils import get_openapi
+
+from versioning import VERIFIER_VERSION
+
+SECURITY_SCHEMES = {
+    "ApiKey": {"type": "apiKey", "in": "header", "name": "X-API-Key"},
+    "AdminKey": {"type": "apiKey", "in": "header", "name": "X-Admin-Key"},
+}
+HEADER_SCHEMES = {"x-api-key": "ApiKey", "x-admin-key": "AdminKey"}
+
+
+def _operation_security(operation: dict) -> list[dict] | None:
+    security: list[dict] = []
+    optional = False
+    for param in operation.get("parameters", []):
+        scheme = HEADER_SCHEMES.get(param.get("name", "").lower())
+        if param.get("in") == "header" and scheme:
+            security.append({scheme: []})
+            optional = optional or not param.get("required", False)
+    if not security:
+        return None
+    return [{}, *security] if optional else security
+
+
+def build_openapi(app: FastAPI) -> dict:
+    if app.openapi_schema:
+        return app.openapi_schema
+    schema = get_openapi(
+        title="R3L API",
+        version=VERIFIER_VERSION,
+        description="C2PA verification and on-chain provenance attestation.",
+        routes=app.routes,
+    )
+    schema.setdefault("components", {})["securitySchemes"] = SECURITY_SCHEMES
+    for path_item in schema["paths"].values():
+        for operation in path_item.values():
+            security = _operation_security(operation)
+            if security:
+                operation["security"] = security
+    app.openapi_schema = schema
+    return schema
@@ -0,0 +1,42 @@
+"""Per-process fan-out of proof generation progress.
+
+POST /api/prove publishes a stage event for each step of the prover run
+(executing, proving, verifying, done or failed) under the content hash;
+GET /api/prove/stream subscribers receive them as server-sent events.
+The latest event per hash is kept while the proof runs so a client that
+subscribes late still sees the current stage.
+"""
+
+import asyncio
+from contextlib import contextmanager
+
+FINAL_STAGES = ("done", "failed")
+
+_subscribers: dict[str, set[asyncio.Queue]] = {}
+_latest: dict[str, dict] = {}
+
+
+def publish(content_hash_hex: str, event: dict):
+    key = content_hash_hex.lower()
+    if event["stage"] in FINAL_STAGES:
+        _latest.pop(key, None)
+    else:
+        _latest[key] = event
+    for queue in _subscribers.get(key, ()):
+        queue.put_nowait(event)
+
+
+@contextmanager
+def subscribe(content_hash_hex: str):
+    """Queue of progress events for `content_hash_hex` until the block exits."""
+    key = content_hash_hex.lower()
+    queue: asyncio.Queue = asyncio.Queue()
+    if key in _latest:
+        queue.put_nowait(_latest[key])
+    _subscribers.setdefault(key, set()).add(queue)
+    try:
+        yield queue
+    finally:
+        _subscribers[key].discard(queue)
+        if not _subscribers[key]:
+            del _subscribers[key]
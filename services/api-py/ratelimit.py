@@ -0,0 +1,84 @@
+"""Per-process request rate limits.
+
+Token buckets keyed by client IP (unauthenticated routes) or API key
+(edge routes). A request that finds its bucket empty gets a 429 with
+`Retry-After` set to the seconds until a token is available.
+
+Buckets live in this process only, like the in-flight guard; with
+several workers each enforces the limit separately.
+"""
+
+import math
+import time
+from functools import cache
+
+from fastapi import Header, HTTPException, Request
+
+from config import Settings
+
+# Full (idle) buckets are dropped once this many keys are tracked
+MAX_TRACKED_KEYS = 10_000
+
+
+class TokenBucket:
+    """`rate` tokens per second per key, holding at most `burst`."""
+
+    def __init__(self, rate: float, burst: float):
+        self.rate = rate
+        self.burst = burst
+        self._buckets: dict[str, tuple[float, float]] = {}  # key -> (tokens, updated_at)
+
+    def acquire(self, key: str) -> float:
+        """Take a token for `key`. Returns 0 on success, otherwise the
+        seconds until a token is available."""
+        now = time.monotonic()
+        tokens, updated_at = self._buckets.get(key, (self.burst, now))
+        tokens = min(self.burst, tokens + (now - updated_at) * self.rate)
+        if tokens < 1:
+            self._buckets[key] = (tokens, now)
+            return (1 - tokens) / self.rate
+        self._buckets[key] = (tokens - 1, now)
+        if len(self._buckets) > MAX_TRACKED_KEYS:
+            self._prune(now)
+        return 0.0
+
+    def _prune(self, now: float):
+        self._buckets = {
+            key: (tokens, updated_at)
+            for key, (tokens, updated_at) in self._buckets.items()
+            if tokens + (now - updated_at) * self.rate < self.burst
+        }
+
+
+@cache
+def ip_limiter() -> TokenBucket:
+    per_min = Settings().rate_limit_ip_per_min
+    return TokenBucket(rate=per_min / 60, burst=per_min)
+
+
+@cache
+def api_key_limiter() -> TokenBucket:
+    rps = Settings().rate_limit_rps
+    return TokenBucket(rate=rps, burst=max(1.0, rps))
+
+
+def _check(limiter: TokenBucket, key: str):
+    wait = limiter.acquire(key)
+    if wait:
+        raise HTTPException(
+            429,
+            "rate limit exceeded",
+            headers={"Retry-After": str(math.ceil(wait))},
+        )
+
+
+async def limit_by_ip(request: Request):
+    """Shared limit for unauthenticated routes, keyed by client IP."""
+    _check(ip_limiter(), request.client.host if request.client else "unknown")
+
+
+async def limit_by_api_key(x_api_key: str | None = Header(None)):
+    """Per-key limit for edge routes. Requests without a key are left to
+    the auth dependency to reject."""
+    if x_api_key:
+        _check(api_key_limiter(), x_api_key)
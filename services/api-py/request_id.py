@@ -0,0 +1,105 @@
+"""Per-request correlation IDs.
+
+Every request gets an ID: the incoming `X-Request-Id` header when it is a
+reasonable token (forwarded by the edge proxy), otherwise a new UUID4. The
+ID is echoed in the `X-Request-Id` response header, added to error bodies
+as `request_id`, and stamped on every log record made while the request
+is handled, so a client-reported error can be matched to its log lines.
+"""
+
+import logging
+import re
+import uuid
+from contextvars import ContextVar
+
+from fastapi import Request
+from fastapi.encoders import jsonable_encoder
+from fastapi.exceptions import RequestValidationError
+from fastapi.responses import JSONResponse
+from starlette.exceptions import HTTPException
+
+HEADER = "X-Request-Id"
+# Forwarded IDs are reused only if they look like an ID, not arbitrary text
+FORWARDED_ID_RE = re.compile(r"[A-Za-z0-9._:-]{1,128}")
+
+LOG_FORMAT = "%(asctime)s %(levelname)s [%(request_id)s] %(name)s: %(message)s"
+
+log = logging.getLogger(__name__)
+
+_request_id: ContextVar[str] = ContextVar("request_id", default="-")
+
+
+def current_request_id() -> str:
+    """ID of the request being handled, or "-" outside a request."""
+    return _request_id.get()
+
+
+class RequestIdMiddleware:
+    """ASGI middleware assigning the request ID and setting `X-Request-Id`."""
+
+    def __init__(self, app):
+        self.app = app
+
+    async def __call__(self, scope, receive, send):
+        if scope["type"] != "http":
+            await self.app(scope, receive, send)
+            return
+
+        forwarded = None
+        for name, value in scope["headers"]:
+            if name == b"x-request-id":
+                forwarded = value.decode("latin-1")
+                break
+        request_id = forwarded if forwarded and FORWARDED_ID_RE.fullmatch(forwarded) else str(uuid.uuid4())
+        _request_id.set(request_id)
+
+        async def send_wrapper(message):
+            if message["type"] == "http.response.start":
+                headers = [(k, v) for k, v in message.get("headers", []) if k.lower() != b"x-request-id"]
+                headers.append((b"x-request-id", request_id.encode()))
+                message = {**message, "headers": headers}
+            await send(message)
+
+        await self.app(scope, receive, send_wrapper)
+
+
+def install_log_request_ids():
+    """Give every log record a `request_id` attribute, for LOG_FORMAT."""
+    factory = logging.getLogRecordFactory()
+
+    def record_factory(*args, **kwargs):
+        record = factory(*args, **kwargs)
+        record.request_id = _request_id.get()
+        return record
+
+    logging.setLogRecordFactory(record_factory)
+
+
+def _error_body(error, request_id: str) -> dict:
+    # `detail` is what existing clients read; `error` carries the same message
+    return {"detail": error, "error": error, "request_id": request_id}
+
+
+async def http_exception_handler(request: Request, exc: HTTPException) -> JSONResponse:
+    return JSONResponse(
+        _error_body(exc.detail, current_request_id()),
+        status_code=exc.status_code,
+        headers=exc.headers,
+    )
+
+
+async def validation_exception_handler(request: Request, exc: RequestValidationError) -> JSONResponse:
+    body = _error_body(jsonable_encoder(exc.errors()), current_request_id())
+    body["error"] = "invalid request"
+    return JSONResponse(body, status_code=422)
+
+
+async def unhandled_exception_handler(request: Request, exc: Exception) -> JSONResponse:
+    # Runs outside RequestIdMiddleware, so the header is set here
+    request_id = current_request_id()
+    log.exception("unhandled error on %s %s", request.method, request.url.path)
+    return JSONResponse(
+        {"error": "internal server error", "request_id": request_id},
+        status_code=500,
+        headers={HEADER: request_id},
+    )
@@ -0,0 +1,56 @@
+"""Revoked edge node API keys, mirrored from on-chain RevokedApiKey PDAs.
+
+Each revocation is a PDA seeded by sha256(api_key), so the list survives
+API restarts and is public. Checking the chain on every /api/edge/attest
+would add an RPC round trip per request; instead the hashes are cached
+here and reloaded every `revocation_refresh_secs`. Revocations are
+permanent, so a refresh only ever adds to the set.
+"""
+
+import asyncio
+import hashlib
+import logging
+
+from solana_read import list_revoked_api_keys
+
+log = logging.getLogger(__name__)
+
+_revoked_key_hashes: set[bytes] = set()
+_refresh_task: asyncio.Task | None = None
+
+
+def api_key_hash(api_key: str) -> bytes:
+    return hashlib.sha256(api_key.encode()).digest()
+
+
+def is_revoked(api_key: str) -> bool:
+    return api_key_hash(api_key) in _revoked_key_hashes
+
+
+def mark_revoked(key_hash: bytes):
+    """Record a revocation this process just sent, ahead of the next refresh."""
+    _revoked_key_hashes.add(key_hash)
+
+
+async def refresh_revoked_keys(settings):
+    hashes = await asyncio.to_thread(list_revoked_api_keys, settings.solana_rpc_url, settings.program_id)
+    _revoked_key_hashes.update(hashes)
+
+
+async def _refresh_loop(settings):
+    while True:
+        try:
+            await refresh_revoked_keys(settings)
+        except Exception as e:
+            log.warning("revoked API key refresh failed: %s", e)
+        await asyncio.sleep(settings.revocation_refresh_secs)
+
+
+def start_revocation_refresh(settings):
+    global _refresh_task
+    _refresh_task = asyncio.create_task(_refresh_loop(settings))
+
+
+def stop_revocation_refresh():
+    if _refresh_task is not None:
+        _refresh_task.cancel()
@@ -0,0 +1,92 @@
+import asyncio
+
+from fastapi import APIRouter, Depends, HTTPException
+from pydantic import BaseModel
+from solders.pubkey import Pubkey
+
+from auth import require_admin_key
+from config import Settings
+from revocation import api_key_hash, is_revoked, mark_revoked
+from solana_read import lookup_attestation
+from solana_tx import (
+    ATTESTATION_SEED,
+    REVOKED_SEED,
+    build_and_send_tx,
+    encode_revoke_api_key_data,
+    encode_revoke_attestation_data,
+    find_pda,
+)
+import db
+
+router = APIRouter(dependencies=[Depends(require_admin_key)])
+
+# Same bound as the program's Attestation::MAX_STRING_LEN
+MAX_REASON_LEN = 128
+
+
+class RevokeAttestationRequest(BaseModel):
+    reason: str
+
+
+@router.post("/nodes/{pubkey}/revoke")
+async def revoke_node(pubkey: str):
+    """Revoke an edge node's API key by writing a RevokedApiKey PDA on-chain."""
+    settings = Settings()
+
+    customer = await db.get_customer_by_wallet(pubkey)
+    if not customer:
+        raise HTTPException(404, "edge node not found")
+
+    key_hash = api_key_hash(customer["api_key"])
+    program_id = Pubkey.from_string(settings.program_id)
+    pda, _ = find_pda([REVOKED_SEED, key_hash], program_id)
+
+    if is_revoked(customer["api_key"]):
+        return {"status": "already_revoked", "pubkey": pubkey, "pda": str(pda)}
+
+    sig, pda_str = await asyncio.to_thread(
+        build_and_send_tx,
+        settings.solana_rpc_url,
+        settings.solana_keypair_path,
+        settings.program_id,
+        encode_revoke_api_key_data(key_hash),
+        pda,
+        priority_fee_strategy=settings.priority_fee_strategy,
+    )
+    mark_revoked(key_hash)
+
+    return {"status": "revoked", "pubkey": pubkey, "pda": pda_str, "tx_signature": sig}
+
+
+@router.post("/attestations/{content_hash}/revoke")
+async def revoke_attestation(content_hash: str, req: RevokeAttestationRequest):
+    """Mark an attestation revoked on-chain; the record stays readable."""
+    settings = Settings()
+    if len(req.reason.encode()) > MAX_REASON_LEN:
+        raise HTTPException(400, f"reason exceeds {MAX_REASON_LEN} bytes")
+
+    attestation = await asyncio.to_thread(
+        lookup_attestation, settings.solana_rpc_url, settings.program_id, content_hash
+    )
+    if attestation is None:
+        raise HTTPException(404, "attestation not found")
+    content_hash = content_hash.lower()
+    if attestation["revoked"]:
+        return {"status": "already_revoked", "content_hash": content_hash}
+
+    content_hash_bytes = bytes.fromhex(content_hash)
+    program_id = Pubkey.from_string(settings.program_id)
+    pda, _ = find_pda([ATTESTATION_SEED, content_hash_bytes], program_id)
+
+    sig, pda_str = await asyncio.to_thread(
+        build_and_send_tx,
+        settings.solana_rpc_url,
+        settings.solana_keypair_path,
+        settings.program_id,
+        encode_revoke_attestation_data(content_hash_bytes, req.reason),
+        pda,
+        priority_fee_strategy=settings.priority_fee_strategy,
+    )
+    await db.mark_attestation_revoked(content_hash, req.reason)
+
+    return {"status": "revoked", "content_hash": content_hash, "pda": pda_str, "tx_signature": sig}
@@ -1,16 +1,20 @@
 import asyncio
 import hashlib
+import os
 
 import httpx
-from fastapi import APIRouter, File, Form, Header, HTTPException, UploadFile
+from fastapi import APIRouter, Depends, Form, Header, HTTPException, UploadFile
 from nacl.signing import VerifyKey
 from nacl.exceptions import BadSignatureError
 from pydantic import BaseModel
 import base58
 
 from config import Settings
-from similarity import compute_tlsh, compute_clip_embedding
-from routes.verify import run_verifier, validate_upload
+import idempotency
+from inflight import attestation_in_flight
+from metrics import ATTEST_REQUESTS
+from similarity import add_to_phash_index, compute_clip_embedding, compute_phash, compute_tlsh
+from routes.verify import run_verifier_file, save_upload, upload_file, validate_content_type
 from versioning import VERIFIER_VERSION, compute_trust_bundle_hash
 from solana_tx import (
     ATTESTATION_SEED,
@@ -20,6 +24,7 @@ from solana_tx import (
     find_pda,
 )
 from storage import get_storage
+from webhook_delivery import notify_attestation_created
 import db
 from solana_read import lookup_attestation
 from solders.pubkey import Pubkey
@@ -31,13 +36,20 @@ MAX_FILE_SIZE = 50 * 1024 * 1024  # 50 MB
 
 # ── Shared helper ──────────────────────────────────────────────────
 
-async def _submit_attestation(
+async def _submit_attestation(*, content_hash_hex: str, **kwargs) -> dict:
+    """Shared attestation entry point; one in-flight submission per content hash."""
+    with attestation_in_flight(content_hash_hex):
+        return await _submit_attestation_unguarded(content_hash_hex=content_hash_hex, **kwargs)
+
+
+async def _submit_attestation_unguarded(
     *,
     settings: Settings,
     content_hash_hex: str,
     verify_output: dict,
     tlsh_hash: str | None,
     clip_embedding: list[float] | None,
+    phash: str | None = None,
     content_type: str = "file",
     source_url: str | None = None,
     mime_type: str | None = None,
@@ -70,7 +82,7 @@ async def _submit_attestation(
     # Wallet identity
     email_domain = ""
     email_hash = b"\x00" * 32
-    wallet_bytes = b"\x00" * 32
+    wallets: list[bytes] = []
     resolved_wallet = None
     ed25519_ix = None
 
@@ -89,19 +101,19 @@ async def _submit_attestation(
             verify_key.verify(wallet_message.encode(), sig_bytes)
         except BadSignatureError:
             raise HTTPException(400, "invalid wallet signature")
-        wallet_bytes = pk_bytes
+        wallets = [pk_bytes]
         resolved_wallet = wallet_pubkey
         ed25519_ix = create_ed25519_instruction(pk_bytes, sig_bytes, wallet_message.encode())
 
     # Privacy mode: keep identity in Postgres but zero it out for Solana
     if privacy_mode:
-        wallet_bytes = b"\x00" * 32
+        wallets = []
         ed25519_ix = None
         email_domain = ""
         email_hash = b"\x00" * 32
 
     # Versioning
-    trust_hash = compute_trust_bundle_hash(settings.trust_dir)
+    trust_hash = verify_output.get("trust_bundle_hash") or compute_trust_bundle_hash(settings.trust_dir)
 
     sig = None
     pda_str = str(pda)
@@ -122,9 +134,12 @@ async def _submit_attestation(
             cert_fingerprint=verify_output.get("cert_fingerprint") or "",
             email_domain=email_domain,
             email_hash=email_hash,
-            wallet=wallet_bytes,
+            wallets=wallets,
             verifier_version=VERIFIER_VERSION,
             trust_bundle_hash=trust_hash,
+            manifest_hash=verify_output.get("manifest_hash") or "",
+            assertions_digest=verify_output.get("assertions_digest") or "",
+            software_agent_canonical=verify_output.get("software_agent_canonical") or "",
         )
 
         # Send Solana tx
@@ -138,6 +153,7 @@ async def _submit_attestation(
             pda,
             200_000,
             extra_ixs,
+            priority_fee_strategy=settings.priority_fee_strategy,
         )
 
     # DB insert
@@ -152,14 +168,24 @@ async def _submit_attestation(
         issuer=verify_output.get("issuer") or "",
         common_name=verify_output.get("common_name") or "",
         software_agent=verify_output.get("software_agent") or "",
+        software_agent_canonical=verify_output.get("software_agent_canonical") or None,
         signing_time=verify_output.get("signing_time") or "",
         cert_fingerprint=verify_output.get("cert_fingerprint") or "",
         email_domain=email_domain or None,
         wallet_pubkey=resolved_wallet,
         verifier_version=VERIFIER_VERSION,
         trust_bundle_hash=trust_hash,
+        manifest_hash=verify_output.get("manifest_hash") or None,
+        assertions_digest=verify_output.get("assertions_digest") or None,
         tlsh_hash=tlsh_hash,
+        phash=phash,
         clip_embedding=clip_embedding,
+        ingredients=verify_output.get("ingredients"),
+        location_country=verify_output.get("location_country"),
+        location_region=verify_output.get("location_region"),
+        location_city=verify_output.get("location_city"),
+        location_lat=verify_output.get("location_lat"),
+        location_lon=verify_output.get("location_lon"),
         content_type=content_type,
         source_url=source_url,
         mime_type=mime_type,
@@ -167,6 +193,11 @@ async def _submit_attestation(
         stored=stored,
         private=private_mode,
     )
+    ATTEST_REQUESTS.labels("trusted_verifier").inc()
+    if not private_mode:
+        notify_attestation_created(content_hash_hex, verify_output.get("trust_list_match"))
+    if phash and not private_mode:
+        add_to_phash_index(content_hash_hex, phash)
 
     result = {
         "signature": sig,
@@ -184,26 +215,39 @@ async def _submit_attestation(
 
 @router.post("/attest")
 async def attest(
-    file: UploadFile = File(...),
+    file: UploadFile = Depends(upload_file),
     store_content: str = Form("true"),
     private_mode: str = Form("false"),
     wallet_pubkey: str = Form(None),
     wallet_message: str = Form(None),
     wallet_signature: str = Form(None),
     x_api_key: str | None = Header(None),
+    idempotency_key: str | None = Header(None),
 ):
     settings = Settings()
     caller = await db.get_customer_by_api_key(x_api_key) if x_api_key else None
-    file_bytes = await file.read()
-    validate_upload(file_bytes, file.content_type)
+    key = idempotency.store_key(caller["api_key"] if caller else None, idempotency_key)
+    if replayed := idempotency.replay(key):
+        return replayed
+    validate_content_type(file.content_type)
+
+    # Stream to disk first so oversized uploads are rejected without buffering them.
+    # Similarity hashing and storage still need the (size-capped) bytes.
+    path, content_hash = await save_upload(file)
+    try:
+        with open(path, "rb") as f:
+            file_bytes = f.read()
+
+        # Verify file (C2PA extraction)
+        verify_output = await run_verifier_file(path, file.filename or "upload", content_hash, settings)
+    finally:
+        os.unlink(path)
 
     # Compute similarity hashes
     file_tlsh = compute_tlsh(file_bytes)
+    file_phash = compute_phash(file_bytes)
     file_clip = compute_clip_embedding(file_bytes, file.content_type)
 
-    # Verify file (C2PA extraction)
-    verify_output = await run_verifier(file_bytes, file.filename or "upload", settings)
-
     content_hash_hex = verify_output.get("content_hash")
     if not content_hash_hex:
         raise HTTPException(500, "no content hash from verifier")
@@ -217,12 +261,13 @@ async def attest(
 
     is_private = private_mode.lower() not in ("false", "0", "no")
 
-    return await _submit_attestation(
+    result = await _submit_attestation(
         settings=settings,
         content_hash_hex=content_hash_hex,
         verify_output=verify_output,
         tlsh_hash=file_tlsh,
         clip_embedding=file_clip,
+        phash=file_phash,
         content_type="file",
         mime_type=file.content_type,
         content_size=len(file_bytes),
@@ -233,6 +278,7 @@ async def attest(
         privacy_mode=caller.get("privacy_mode", False) if caller else False,
         private_mode=is_private,
     )
+    return idempotency.remember(key, result)
 
 
 # ── POST /api/attest/url ──────────────────────────────────────────
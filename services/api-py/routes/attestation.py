@@ -1,13 +1,157 @@
 import asyncio
+import base64
+import csv
+import io
+import math
+import time
+import zlib
+from datetime import datetime, timezone
 
-from fastapi import APIRouter, HTTPException
+from fastapi import APIRouter, Header, HTTPException, Query, Response
+from fastapi.responses import JSONResponse, StreamingResponse
+from pydantic import BaseModel
 
+from attestation_cache import cached_lookup_attestation
+from compression import accepts_encoding
 from config import Settings
 import db
-from solana_read import lookup_attestation
+from solana_read import fetch_all_attestations, lookup_attestations
 
 router = APIRouter()
 
+TRUST_LEVELS = {"official", "curated", "untrusted"}
+PROOF_TYPES = {"zk_groth16", "trusted_verifier"}
+ATTESTATION_KINDS = {"c2pa", "identity", "wallet"}
+
+# /attestations page size; larger `limit` values are clamped
+DEFAULT_PAGE_SIZE = 50
+MAX_PAGE_SIZE = 200
+
+# On-chain Attestation fields compared by /attestations/diff, in account order
+DIFF_FIELDS = [
+    "content_hash",
+    "has_c2pa",
+    "trust_list_match",
+    "validation_state",
+    "digital_source_type",
+    "issuer",
+    "common_name",
+    "software_agent",
+    "signing_time",
+    "cert_fingerprint",
+    "submitted_by",
+    "timestamp",
+    "proof_type",
+    "email_domain",
+    "wallet_pubkey",
+    "verifier_version",
+    "trust_bundle_hash",
+    "manifest_hash",
+    "assertions_digest",
+    "expiry_timestamp",
+    "renewal_count",
+    "revoked",
+    "revocation_reason",
+]
+
+# /attestations/export.csv columns, in order; timestamp_utc is derived from `timestamp`
+EXPORT_COLUMNS = [
+    "content_hash",
+    "has_c2pa",
+    "trust_list_match",
+    "validation_state",
+    "issuer",
+    "common_name",
+    "signing_time",
+    "cert_fingerprint",
+    "submitted_by",
+    "timestamp_utc",
+    "proof_type",
+]
+EXPORT_CHUNK_ROWS = 500  # rows encoded per streamed chunk
+
+
+# Provenance graph traversal limits (/attestation/{hash}/graph)
+GRAPH_MAX_DEPTH = 10
+GRAPH_MAX_BREADTH = 20  # nodes fetched per BFS level
+GRAPH_SYNC_TIMEOUT = 5.0  # seconds before answering 202 and finishing in the background
+GRAPH_CACHE_TTL = 300
+
+# content hash -> (built_at, graph); in-flight builds are kept in _graph_tasks
+_graph_cache: dict[str, tuple[float, dict]] = {}
+_graph_tasks: dict[str, asyncio.Task] = {}
+
+
+class DiffRequest(BaseModel):
+    hash1: str
+    hash2: str
+
+
+def _diff_value(v) -> str:
+    if v is None:
+        return ""
+    if isinstance(v, bool):
+        return "true" if v else "false"
+    return str(v)
+
+
+def domain_expiry(expiry_timestamp: int | None) -> dict:
+    """domain_expires_at / domain_days_remaining from the on-chain
+    expiry_timestamp (None when the attestation has no email domain)."""
+    if not expiry_timestamp:
+        return {"domain_expires_at": None, "domain_days_remaining": None}
+    days = max(0, (expiry_timestamp - int(time.time())) // 86400)
+    return {"domain_expires_at": expiry_timestamp, "domain_days_remaining": days}
+
+
+def attestation_response(row: dict) -> dict:
+    """Shape a DB attestation row like the on-chain lookup response."""
+    result = {
+        "content_hash": row["content_hash"],
+        "has_c2pa": row["has_c2pa"],
+        "trust_list_match": row["trust_list_match"] or "",
+        "validation_state": row["validation_state"] or "",
+        "digital_source_type": row["digital_source_type"] or "",
+        "issuer": row["issuer"] or "",
+        "common_name": row["common_name"] or "",
+        "software_agent": row["software_agent"] or "",
+        "signing_time": row["signing_time"] or "",
+        "cert_fingerprint": row["cert_fingerprint"] or "",
+        "submitted_by": row["submitted_by"] or "",
+        "timestamp": row["created_at"],
+        "proof_type": row["proof_type"],
+        "revoked": row.get("revoked", False),
+        "revocation_reason": row.get("revocation_reason"),
+    }
+    if row.get("software_agent_canonical"):
+        result["software_agent_canonical"] = row["software_agent_canonical"]
+    if row.get("email_domain"):
+        result["email_domain"] = row["email_domain"]
+    if row.get("wallet_pubkey"):
+        result["wallet_pubkey"] = row["wallet_pubkey"]
+    if row.get("verifier_version"):
+        result["verifier_version"] = row["verifier_version"]
+    if row.get("trust_bundle_hash"):
+        result["trust_bundle_hash"] = row["trust_bundle_hash"]
+    if row.get("manifest_hash"):
+        result["manifest_hash"] = row["manifest_hash"]
+    if row.get("assertions_digest"):
+        result["assertions_digest"] = row["assertions_digest"]
+    if row.get("org_domain"):
+        result["org_domain"] = row["org_domain"]
+    for field in ("location_country", "location_region", "location_city", "location_lat", "location_lon"):
+        if row.get(field) is not None:
+            result[field] = row[field]
+    result["content_type"] = row.get("content_type", "file")
+    if row.get("source_url"):
+        result["source_url"] = row["source_url"]
+    if row.get("mime_type"):
+        result["mime_type"] = row["mime_type"]
+    if row.get("content_size"):
+        result["content_size"] = row["content_size"]
+    result["stored"] = row.get("stored", False)
+    return result
+
 
 @router.get("/attestation/{hash}")
 async def lookup(hash: str):
@@ -15,64 +159,193 @@ async def lookup(hash: str):
     row = await db.get_attestation(hash)
     if row and row.get("private", False):
         raise HTTPException(404, "attestation not found")
+    settings = Settings()
     if row:
-        result = {
-            "content_hash": row["content_hash"],
-            "has_c2pa": row["has_c2pa"],
-            "trust_list_match": row["trust_list_match"] or "",
-            "validation_state": row["validation_state"] or "",
-            "digital_source_type": row["digital_source_type"] or "",
-            "issuer": row["issuer"] or "",
-            "common_name": row["common_name"] or "",
-            "software_agent": row["software_agent"] or "",
-            "signing_time": row["signing_time"] or "",
-            "cert_fingerprint": row["cert_fingerprint"] or "",
-            "submitted_by": row["submitted_by"] or "",
-            "timestamp": row["created_at"],
-            "proof_type": row["proof_type"],
-        }
+        result = attestation_response(row)
+        # Renewals only happen on-chain, so the expiry is always read from there
+        expiry = None
         if row.get("email_domain"):
-            result["email_domain"] = row["email_domain"]
-        if row.get("wallet_pubkey"):
-            result["wallet_pubkey"] = row["wallet_pubkey"]
-        if row.get("verifier_version"):
-            result["verifier_version"] = row["verifier_version"]
-        if row.get("trust_bundle_hash"):
-            result["trust_bundle_hash"] = row["trust_bundle_hash"]
-        if row.get("org_domain"):
-            result["org_domain"] = row["org_domain"]
-        result["content_type"] = row.get("content_type", "file")
-        if row.get("source_url"):
-            result["source_url"] = row["source_url"]
-        if row.get("mime_type"):
-            result["mime_type"] = row["mime_type"]
-        if row.get("content_size"):
-            result["content_size"] = row["content_size"]
-        result["stored"] = row.get("stored", False)
-        return result
+            try:
+                on_chain = await cached_lookup_attestation(settings, hash)
+                expiry = (on_chain or {}).get("expiry_timestamp")
+            except Exception:
+                pass
+        return {**result, **domain_expiry(expiry)}
 
     # Fall back to on-chain lookup
-    settings = Settings()
-    result = await asyncio.to_thread(
-        lookup_attestation, settings.solana_rpc_url, settings.program_id, hash
-    )
+    result = await cached_lookup_attestation(settings, hash)
     if result is None:
         raise HTTPException(404, "attestation not found")
+    return {**result, **domain_expiry(result.get("expiry_timestamp"))}
+
+
+def _ingredient_hash(ingredient: dict) -> str | None:
+    """Content hash of a C2PA ingredient as 64 hex chars (accepts hex or base64)."""
+    value = ingredient.get("hash")
+    if not isinstance(value, str) or not value:
+        return None
+    try:
+        raw = bytes.fromhex(value)
+    except ValueError:
+        try:
+            raw = base64.b64decode(value, validate=True)
+        except ValueError:
+            return None
+    return raw.hex() if len(raw) == 32 else None
+
+
+async def _build_graph(root: str, settings: Settings) -> dict:
+    """BFS over ingredient hashes, one batched on-chain lookup per level."""
+    nodes: dict[str, dict | None] = {}
+    edges: list[dict] = []
+    level = [root]
+    depth = 0
+
+    while level:
+        level = level[:GRAPH_MAX_BREADTH]
+        rows = await db.get_attestations(level)
+        on_chain = [h for h in level if h not in rows or rows[h].get("private", False)]
+        if on_chain:
+            results = await asyncio.to_thread(
+                lookup_attestations, settings.solana_rpc_url, settings.program_id, on_chain
+            )
+            chain = dict(zip(on_chain, results))
+        else:
+            chain = {}
+
+        next_level: list[str] = []
+        for h in level:
+            row = rows.get(h)
+            if row and not row.get("private", False):
+                nodes[h] = attestation_response(row)
+            else:
+                nodes[h] = chain.get(h)
+
+            # Ingredient lists are only recorded off-chain, at attest time
+            if depth >= GRAPH_MAX_DEPTH or not row or row.get("private", False):
+                continue
+            for ingredient in row.get("ingredients") or []:
+                if not isinstance(ingredient, dict):
+                    continue
+                child = _ingredient_hash(ingredient)
+                if child is None:
+                    continue
+                edges.append({
+                    "from": h,
+                    "to": child,
+                    "label": ingredient.get("relationship") or "componentOf",
+                })
+                if child not in nodes and child not in next_level:
+                    next_level.append(child)
+
+        level = next_level
+        depth += 1
+
+    # Drop edges to ingredients cut off by the breadth limit
+    edges = [e for e in edges if e["to"] in nodes]
+    return {
+        "nodes": [{"id": h, "attestation": att} for h, att in nodes.items()],
+        "edges": edges,
+    }
+
+
+def _graph_or_404(result: dict) -> dict:
+    if result["nodes"][0]["attestation"] is None and not result["edges"]:
+        raise HTTPException(404, "attestation not found")
     return result
 
 
+@router.get("/attestation/{hash}/graph")
+async def graph(hash: str):
+    """Provenance graph rooted at `hash`, following C2PA ingredients."""
+    now = time.time()
+    for key in [k for k, (built_at, _) in _graph_cache.items() if now - built_at > GRAPH_CACHE_TTL]:
+        del _graph_cache[key]
+
+    cached = _graph_cache.get(hash)
+    if cached:
+        return _graph_or_404(cached[1])
+
+    task = _graph_tasks.get(hash)
+    if task is None:
+        task = asyncio.create_task(_build_graph(hash, Settings()))
+        _graph_tasks[hash] = task
+
+        def _store(t: asyncio.Task, key: str = hash):
+            _graph_tasks.pop(key, None)
+            if not t.cancelled() and t.exception() is None:
+                _graph_cache[key] = (time.time(), t.result())
+
+        task.add_done_callback(_store)
+
+    try:
+        result = await asyncio.wait_for(asyncio.shield(task), GRAPH_SYNC_TIMEOUT)
+    except asyncio.TimeoutError:
+        return JSONResponse({"processing": True}, status_code=202)
+    return _graph_or_404(result)
+
+
 @router.get("/attestations")
-async def list_all():
-    rows = await db.list_attestations()
+async def list_all(
+    response: Response,
+    has_c2pa: bool | None = None,
+    trust_level: str | None = None,
+    proof_type: str | None = None,
+    issuer: str | None = None,
+    software_agent: str | None = None,
+    after: int | None = None,
+    before: int | None = None,
+    from_ts: int | None = Query(None, alias="from"),
+    to_ts: int | None = Query(None, alias="to"),
+    submitter: str | None = None,
+    kind: str | None = None,
+    include_revoked: bool = False,
+    page: int = Query(1, ge=1),
+    limit: int = Query(DEFAULT_PAGE_SIZE, ge=1),
+):
+    """One page of matching attestations, newest first. Pages past the
+    end come back with no items.
+
+    Filters combine with AND. `from`/`to` are inclusive unix timestamps
+    (`after`/`before` exclusive); `submitter` is a wallet pubkey or edge
+    node name; `kind` is c2pa, identity or wallet."""
+    if trust_level is not None and trust_level not in TRUST_LEVELS:
+        raise HTTPException(400, f"trust_level must be one of {sorted(TRUST_LEVELS)}")
+    if proof_type is not None and proof_type not in PROOF_TYPES:
+        raise HTTPException(400, f"proof_type must be one of {sorted(PROOF_TYPES)}")
+    if kind is not None and kind not in ATTESTATION_KINDS:
+        raise HTTPException(400, f"kind must be one of {sorted(ATTESTATION_KINDS)}")
+
+    filters = dict(
+        include_revoked=include_revoked,
+        has_c2pa=has_c2pa,
+        trust_level=trust_level,
+        proof_type=proof_type,
+        issuer=issuer,
+        software_agent=software_agent,
+        after=after,
+        before=before,
+        from_ts=from_ts,
+        to_ts=to_ts,
+        submitter=submitter,
+        kind=kind,
+    )
+    limit = min(limit, MAX_PAGE_SIZE)
+    rows = await db.list_attestations(limit=limit, offset=(page - 1) * limit, **filters)
+    total = await db.count_attestations(**filters)
+    response.headers["X-Total-Count"] = str(total)
     items = []
     for row in rows:
         item = {
             "content_hash": row["content_hash"],
             "proof_type": row["proof_type"],
+            "has_c2pa": row.get("has_c2pa", False),
             "timestamp": row["created_at"],
         }
         if row.get("issuer"):
             item["issuer"] = row["issuer"]
+        if row.get("software_agent_canonical"):
+            item["software_agent_canonical"] = row["software_agent_canonical"]
         if row.get("trust_list_match"):
             item["trust_list_match"] = row["trust_list_match"]
         if row.get("email_domain"):
@@ -81,9 +354,101 @@ async def list_all():
             item["wallet_pubkey"] = row["wallet_pubkey"]
         if row.get("org_domain"):
             item["org_domain"] = row["org_domain"]
+        if row.get("revoked"):
+            item["revoked"] = True
         item["content_type"] = row.get("content_type", "file")
         if row.get("source_url"):
             item["source_url"] = row["source_url"]
         item["stored"] = row.get("stored", False)
         items.append(item)
-    return items
+    return {
+        "items": items,
+        "total": total,
+        "page": page,
+        "limit": limit,
+        "pages": math.ceil(total / limit),
+    }
+
+
+def _csv_chunks(attestations: list[dict]):
+    """CSV text for `attestations`, header first, EXPORT_CHUNK_ROWS rows per chunk."""
+    buf = io.StringIO()
+    writer = csv.writer(buf)
+    writer.writerow(EXPORT_COLUMNS)
+    for i, att in enumerate(attestations, 1):
+        row = {
+            **att,
+            "has_c2pa": "true" if att["has_c2pa"] else "false",
+            "timestamp_utc": datetime.fromtimestamp(att["timestamp"], timezone.utc).strftime("%Y-%m-%dT%H:%M:%SZ"),
+        }
+        writer.writerow([row.get(col, "") for col in EXPORT_COLUMNS])
+        if i % EXPORT_CHUNK_ROWS == 0:
+            yield buf.getvalue().encode()
+            buf.seek(0)
+            buf.truncate()
+    yield buf.getvalue().encode()
+
+
+def _gzip_chunks(chunks):
+    compressor = zlib.compressobj(6, zlib.DEFLATED, 16 + zlib.MAX_WBITS)  # gzip container
+    for chunk in chunks:
+        if out := compressor.compress(chunk):
+            yield out
+    yield compressor.flush()
+
+
+@router.post("/attestations/export.csv")
+async def export_csv(
+    start_ts: int | None = None,
+    end_ts: int | None = None,
+    accept_encoding: str = Header(""),
+):
+    """Every on-chain attestation as a CSV download, oldest first.
+
+    `start_ts` / `end_ts` (unix seconds, inclusive) are applied after the
+    accounts are fetched. Rows are encoded as the response streams, and
+    gzipped on the fly when the client accepts it.
+    """
+    settings = Settings()
+    attestations = await asyncio.to_thread(
+        fetch_all_attestations, settings.solana_rpc_url, settings.program_id
+    )
+    attestations = [
+        a for a in attestations
+        if (start_ts is None or a["timestamp"] >= start_ts) and (end_ts is None or a["timestamp"] <= end_ts)
+    ]
+    attestations.sort(key=lambda a: a["timestamp"])
+
+    headers = {
+        "Content-Disposition": 'attachment; filename="attestations.csv"',
+        "Vary": "Accept-Encoding",
+    }
+    chunks = _csv_chunks(attestations)
+    if accepts_encoding(accept_encoding, "gzip"):
+        chunks = _gzip_chunks(chunks)
+        headers["Content-Encoding"] = "gzip"
+    return StreamingResponse(chunks, media_type="text/csv; charset=utf-8", headers=headers)
+
+
+@router.post("/attestations/diff")
+async def diff(req: DiffRequest):
+    settings = Settings()
+    att1, att2 = await asyncio.to_thread(
+        lookup_attestations, settings.solana_rpc_url, settings.program_id, [req.hash1, req.hash2]
+    )
+    if att1 is None:
+        raise HTTPException(404, f"attestation not found: {req.hash1}")
+    if att2 is None:
+        raise HTTPException(404, f"attestation not found: {req.hash2}")
+
+    fields = []
+    for name in DIFF_FIELDS:
+        value1 = _diff_value(att1.get(name))
+        value2 = _diff_value(att2.get(name))
+        fields.append({"name": name, "value1": value1, "value2": value2, "changed": value1 != value2})
+
+    changed_count = sum(f["changed"] for f in fields)
+    return {
+        "fields": fields,
+        "summary": {"changed_count": changed_count, "identical": changed_count == 0},
+    }
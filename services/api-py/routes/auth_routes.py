@@ -1,7 +1,6 @@
 import asyncio
 import random
 import secrets
-import smtplib
 import string
 from dataclasses import dataclass, field
 from datetime import datetime, timedelta
@@ -16,10 +15,10 @@ import base58
 import db
 from auth import require_api_key
 from config import Settings
+from mailer import send_email
 
 router = APIRouter()
 
-EXPIRY = timedelta(minutes=30)
 MAX_ATTEMPTS = 5
 CHALLENGE_EXPIRY = timedelta(minutes=5)
 
@@ -34,10 +33,12 @@ def _generate_code() -> str:
     return "".join(random.choices(string.digits, k=6))
 
 
-def _send_email(settings: Settings, msg: MIMEText):
-    with smtplib.SMTP_SSL(settings.smtp_host, 465) as server:
-        server.login(settings.smtp_user, settings.smtp_pass)
-        server.send_message(msg)
+def _expiry() -> timedelta:
+    return timedelta(seconds=Settings().identity_expiry_secs)
+
+
+def _expiry_minutes() -> int:
+    return Settings().identity_expiry_secs // 60
 
 
 # ── In-memory stores ────────────────────────────────────────────────
@@ -47,6 +48,7 @@ class EmailCode:
     email: str
     code: str
     attempts: int = 0
+    resends: int = 0
     created_at: datetime = field(default_factory=datetime.now)
 
 
@@ -58,15 +60,36 @@ class WalletChallenge:
 
 _email_codes: dict[str, EmailCode] = {}  # keyed by lowercase email
 _wallet_challenges: dict[str, WalletChallenge] = {}  # keyed by nonce
+_last_email_cleanup = datetime.min
 
 
 def _clean_expired_emails():
+    global _last_email_cleanup
+    settings = Settings()
     now = datetime.now()
-    expired = [k for k, v in _email_codes.items() if now - v.created_at > EXPIRY]
+    if now - _last_email_cleanup < timedelta(seconds=settings.token_cleanup_interval_secs):
+        return
+    _last_email_cleanup = now
+    expiry = timedelta(seconds=settings.identity_expiry_secs)
+    expired = [k for k, v in _email_codes.items() if now - v.created_at > expiry]
     for k in expired:
         del _email_codes[k]
 
 
+def _issue_email_code(email: str) -> str:
+    """Store a fresh code for email, counting re-sends against MAX_RESENDS."""
+    _clean_expired_emails()
+    resends = 0
+    prev = _email_codes.get(email)
+    if prev and datetime.now() - prev.created_at <= _expiry():
+        resends = prev.resends + 1
+        if resends > Settings().max_resends:
+            raise HTTPException(429, "too many codes requested — try again later")
+    code = _generate_code()
+    _email_codes[email] = EmailCode(email=email, code=code, resends=resends)
+    return code
+
+
 def _clean_expired_challenges():
     now = datetime.now()
     expired = [k for k, v in _wallet_challenges.items() if now - v.created_at > CHALLENGE_EXPIRY]
@@ -104,9 +127,7 @@ async def email_start(req: EmailStartRequest):
     if "." not in domain or len(domain) < 3:
         raise HTTPException(400, "invalid email domain")
 
-    _clean_expired_emails()
-    code = _generate_code()
-    _email_codes[email] = EmailCode(email=email, code=code)
+    code = _issue_email_code(email)
 
     settings = Settings()
     resp = {"status": "pending", "email": email}
@@ -120,7 +141,7 @@ async def email_start(req: EmailStartRequest):
 <p style="color:#9ca3af;margin:0 0 24px;">Verify your email to create your account.</p>
 <p style="color:#e5e5e5;margin:0 0 8px;">Your verification code:</p>
 <p style="font-size:36px;font-weight:700;color:#facc15;letter-spacing:8px;margin:0 0 24px;">{code}</p>
-<p style="color:#6b7280;font-size:12px;margin:0;">This code expires in 30 minutes.</p>
+<p style="color:#6b7280;font-size:12px;margin:0;">This code expires in {_expiry_minutes()} minutes.</p>
 </div>
 </body></html>"""
         from_addr = settings.smtp_from or settings.smtp_user
@@ -130,7 +151,7 @@ async def email_start(req: EmailStartRequest):
         msg["To"] = email
 
         try:
-            await asyncio.to_thread(_send_email, settings, msg)
+            await asyncio.to_thread(send_email, msg)
         except Exception as e:
             raise HTTPException(500, f"failed to send email: {e}")
     else:
@@ -149,7 +170,7 @@ async def email_verify(req: EmailVerifyRequest):
     if not entry:
         raise HTTPException(404, "no verification pending for this email")
 
-    if datetime.now() - entry.created_at > EXPIRY:
+    if datetime.now() - entry.created_at > _expiry():
         del _email_codes[email]
         raise HTTPException(410, "code expired \u2014 request a new code")
 
@@ -324,9 +345,7 @@ async def link_email_start(req: EmailStartRequest, caller: dict = Depends(requir
 
     # Allow even if email belongs to another account — merge happens at verify time
 
-    _clean_expired_emails()
-    code = _generate_code()
-    _email_codes[email] = EmailCode(email=email, code=code)
+    code = _issue_email_code(email)
 
     settings = Settings()
     resp = {"status": "pending", "email": email}
@@ -340,7 +359,7 @@ async def link_email_start(req: EmailStartRequest, caller: dict = Depends(requir
 <p style="color:#9ca3af;margin:0 0 24px;">Verify your email to link it to your account.</p>
 <p style="color:#e5e5e5;margin:0 0 8px;">Your verification code:</p>
 <p style="font-size:36px;font-weight:700;color:#facc15;letter-spacing:8px;margin:0 0 24px;">{code}</p>
-<p style="color:#6b7280;font-size:12px;margin:0;">This code expires in 30 minutes.</p>
+<p style="color:#6b7280;font-size:12px;margin:0;">This code expires in {_expiry_minutes()} minutes.</p>
 </div>
 </body></html>"""
         from_addr = settings.smtp_from or settings.smtp_user
@@ -350,7 +369,7 @@ async def link_email_start(req: EmailStartRequest, caller: dict = Depends(requir
         msg["To"] = email
 
         try:
-            await asyncio.to_thread(_send_email, settings, msg)
+            await asyncio.to_thread(send_email, msg)
         except Exception as e:
             raise HTTPException(500, f"failed to send email: {e}")
     else:
@@ -372,7 +391,7 @@ async def link_email_verify(req: EmailVerifyRequest, caller: dict = Depends(requ
     if not entry:
         raise HTTPException(404, "no verification pending for this email")
 
-    if datetime.now() - entry.created_at > EXPIRY:
+    if datetime.now() - entry.created_at > _expiry():
         del _email_codes[email]
         raise HTTPException(410, "code expired \u2014 request a new code")
 
@@ -0,0 +1,40 @@
+import re
+
+from fastapi import APIRouter, HTTPException
+from pydantic import BaseModel
+
+import db
+from routes.attestation import attestation_response
+from similarity import search_phash
+
+router = APIRouter()
+
+DEFAULT_THRESHOLD = 10
+
+_PHASH_RE = re.compile(r"^[0-9a-fA-F]{16}$")
+
+
+class DedupRequest(BaseModel):
+    phash: str
+    threshold: int | None = None
+
+
+@router.post("/deduplicate")
+async def deduplicate(req: DedupRequest):
+    if not _PHASH_RE.match(req.phash):
+        raise HTTPException(400, "phash must be 16 hex characters")
+    threshold = DEFAULT_THRESHOLD if req.threshold is None else req.threshold
+    if not 0 <= threshold <= 64:
+        raise HTTPException(400, "threshold must be between 0 and 64")
+
+    results = []
+    for content_hash, distance in search_phash(req.phash, threshold):
+        row = await db.get_attestation(content_hash)
+        if row is None or row.get("private", False):
+            continue
+        results.append({
+            "content_hash": content_hash,
+            "distance": distance,
+            "attestation": attestation_response(row),
+        })
+    return results
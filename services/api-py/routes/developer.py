@@ -11,7 +11,7 @@ import base58
 
 from auth import require_api_key
 from config import Settings
-from similarity import compute_tlsh, compute_clip_embedding
+from similarity import compute_clip_embedding, compute_phash, compute_tlsh
 from routes.verify import run_verifier, validate_upload
 from routes.attest import _submit_attestation, MAX_FILE_SIZE
 from storage import get_storage
@@ -165,13 +165,13 @@ async def _process_verify_identity(req: VerifyIdentityRequest, caller: dict) ->
             raise HTTPException(400, "invalid email domain")
 
         if has_code:
-            from routes.auth_routes import _email_codes, EXPIRY, MAX_ATTEMPTS
+            from routes.auth_routes import _email_codes, _expiry, MAX_ATTEMPTS
             from datetime import datetime
 
             entry = _email_codes.get(email)
             if not entry:
                 raise HTTPException(404, "no verification pending for this email")
-            if datetime.now() - entry.created_at > EXPIRY:
+            if datetime.now() - entry.created_at > _expiry():
                 del _email_codes[email]
                 raise HTTPException(410, "code expired")
             if entry.attempts >= MAX_ATTEMPTS:
@@ -200,11 +200,10 @@ async def _process_verify_identity(req: VerifyIdentityRequest, caller: dict) ->
                 result["email"] = email
             result["email_status"] = "verified"
         else:
-            from routes.auth_routes import _email_codes, _clean_expired_emails, _generate_code, _send_email, EmailCode
+            from mailer import send_email
+            from routes.auth_routes import _expiry_minutes, _issue_email_code
 
-            _clean_expired_emails()
-            code = _generate_code()
-            _email_codes[email] = EmailCode(email=email, code=code)
+            code = _issue_email_code(email)
 
             settings = Settings()
             result["email"] = email
@@ -220,7 +219,7 @@ async def _process_verify_identity(req: VerifyIdentityRequest, caller: dict) ->
 <p style="color:#9ca3af;margin:0 0 24px;">Verify your email address.</p>
 <p style="color:#e5e5e5;margin:0 0 8px;">Your verification code:</p>
 <p style="font-size:36px;font-weight:700;color:#facc15;letter-spacing:8px;margin:0 0 24px;">{code}</p>
-<p style="color:#6b7280;font-size:12px;margin:0;">This code expires in 30 minutes.</p>
+<p style="color:#6b7280;font-size:12px;margin:0;">This code expires in {_expiry_minutes()} minutes.</p>
 </div>
 </body></html>"""
                 from_addr = settings.smtp_from or settings.smtp_user
@@ -229,7 +228,7 @@ async def _process_verify_identity(req: VerifyIdentityRequest, caller: dict) ->
                 msg["From"] = from_addr
                 msg["To"] = email
                 try:
-                    await asyncio.to_thread(_send_email, settings, msg)
+                    await asyncio.to_thread(send_email, msg)
                 except Exception as e:
                     raise HTTPException(500, f"failed to send email: {e}")
             else:
@@ -334,6 +333,7 @@ async def _attest_file(file_bytes: bytes, filename: str, content_type: str | Non
                        wallet_signature: str | None) -> dict:
     validate_upload(file_bytes, content_type)
     file_tlsh = compute_tlsh(file_bytes)
+    file_phash = compute_phash(file_bytes)
     file_clip = compute_clip_embedding(file_bytes, content_type)
     verify_output = await run_verifier(file_bytes, filename, settings)
     content_hash_hex = verify_output.get("content_hash")
@@ -345,7 +345,7 @@ async def _attest_file(file_bytes: bytes, filename: str, content_type: str | Non
     result = await _submit_attestation(
         settings=settings, content_hash_hex=content_hash_hex,
         verify_output=verify_output, tlsh_hash=file_tlsh,
-        clip_embedding=file_clip, content_type="file",
+        clip_embedding=file_clip, phash=file_phash, content_type="file",
         mime_type=content_type, content_size=len(file_bytes),
         stored=should_store, wallet_pubkey=wallet_pubkey,
         wallet_message=wallet_message, wallet_signature=wallet_signature,
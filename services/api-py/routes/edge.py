@@ -1,7 +1,7 @@
 import asyncio
 import secrets
 
-from fastapi import APIRouter, Depends, HTTPException
+from fastapi import APIRouter, Depends, Header, HTTPException
 from pydantic import BaseModel
 from nacl.signing import VerifyKey
 from nacl.exceptions import BadSignatureError
@@ -9,6 +9,11 @@ import base58
 
 from auth import require_api_key
 from config import Settings
+import idempotency
+from inflight import attestation_in_flight
+from metrics import ATTEST_REQUESTS
+from ratelimit import limit_by_api_key, limit_by_ip
+from revocation import is_revoked
 from versioning import VERIFIER_VERSION, compute_trust_bundle_hash
 from solana_tx import (
     ATTESTATION_SEED,
@@ -18,11 +23,50 @@ from solana_tx import (
     find_pda,
 )
 from solana_read import lookup_attestation
+from webhook_delivery import notify_attestation_created
 import db
 from solders.pubkey import Pubkey
 
 router = APIRouter()
 
+# Challenges live in Postgres so a nonce issued by one worker can be
+# redeemed on another
+CHALLENGE_EXPIRY_SECS = 5 * 60
+
+
+async def require_edge_node(
+    customer: dict = Depends(require_api_key),
+    x_challenge_nonce: str | None = Header(None),
+    x_challenge_sig: str | None = Header(None),
+    x_node_pubkey: str | None = Header(None),
+) -> dict:
+    """API key plus a signed challenge from the node's registered keypair.
+
+    The challenge is optional unless EDGE_REQUIRE_CHALLENGE is set, but is
+    always checked when a client sends it.
+    """
+    if is_revoked(customer["api_key"]):
+        raise HTTPException(401, "API key revoked")
+
+    sent = x_challenge_nonce or x_challenge_sig or x_node_pubkey
+    if not sent and not Settings().edge_require_challenge:
+        return customer
+    if not (x_challenge_nonce and x_challenge_sig and x_node_pubkey):
+        raise HTTPException(401, "X-Challenge-Nonce, X-Challenge-Sig and X-Node-Pubkey are required")
+
+    if not await db.consume_edge_challenge(x_challenge_nonce, CHALLENGE_EXPIRY_SECS):
+        raise HTTPException(401, "unknown or expired challenge")
+    if x_node_pubkey != customer.get("wallet_pubkey"):
+        raise HTTPException(401, "node pubkey does not match the registered key")
+
+    try:
+        pubkey_bytes = base58.b58decode(x_node_pubkey)
+        sig_bytes = base58.b58decode(x_challenge_sig)
+        VerifyKey(pubkey_bytes).verify(f"R3L: challenge {x_challenge_nonce}".encode(), sig_bytes)
+    except (BadSignatureError, ValueError):
+        raise HTTPException(401, "invalid challenge signature")
+    return customer
+
 
 class EdgeAttestRequest(BaseModel):
     content_hash: str
@@ -33,8 +77,12 @@ class EdgeAttestRequest(BaseModel):
     issuer: str = ""
     common_name: str = ""
     software_agent: str = ""
+    software_agent_canonical: str = ""  # verifier's canonical product name for software_agent
     signing_time: str = ""
     cert_fingerprint: str = ""
+    manifest_hash: str = ""     # SHA-256 of the C2PA manifest store JSON
+    assertions_digest: str = "" # SHA-256 of the sorted assertion labels
+    trust_bundle_hash: str = "" # SHA-256 of the edge verifier's trust list PEMs
     wallet_signature: str = ""  # base58 Ed25519 sig for on-chain verification
     tlsh_hash: str = ""         # edge-computed TLSH hash
     clip_embedding: list[float] = []  # edge-computed CLIP embedding (512-dim)
@@ -81,8 +129,27 @@ async def register(req: RegisterRequest):
     return {"api_key": customer["api_key"], "pubkey": req.pubkey, "name": customer["name"]}
 
 
-@router.post("/attest")
-async def edge_attest(req: EdgeAttestRequest, customer: dict = Depends(require_api_key)):
+@router.get("/challenge", dependencies=[Depends(limit_by_ip)])
+async def challenge():
+    nonce = secrets.token_hex(32)
+    await db.insert_edge_challenge(nonce, CHALLENGE_EXPIRY_SECS)
+    return {"nonce": nonce}
+
+
+@router.post("/attest", dependencies=[Depends(limit_by_api_key)])
+async def edge_attest(
+    req: EdgeAttestRequest,
+    customer: dict = Depends(require_edge_node),
+    idempotency_key: str | None = Header(None),
+):
+    key = idempotency.store_key(customer["api_key"], idempotency_key)
+    if replayed := idempotency.replay(key):
+        return replayed
+    with attestation_in_flight(req.content_hash):
+        return idempotency.remember(key, await _edge_attest(req, customer))
+
+
+async def _edge_attest(req: EdgeAttestRequest, customer: dict) -> dict:
     settings = Settings()
 
     # 1. Validate content hash
@@ -109,7 +176,7 @@ async def edge_attest(req: EdgeAttestRequest, customer: dict = Depends(require_a
         }
 
     # 3. Resolve wallet from customer record (only if signature provided)
-    wallet_bytes = b"\x00" * 32
+    wallets: list[bytes] = []
     wallet_pubkey = None
     ed25519_ix = None
     customer_wallet = customer.get("wallet_pubkey")
@@ -128,17 +195,17 @@ async def edge_attest(req: EdgeAttestRequest, customer: dict = Depends(require_a
         except BadSignatureError:
             raise HTTPException(400, "invalid wallet signature")
 
-        wallet_bytes = pk_bytes
+        wallets = [pk_bytes]
         wallet_pubkey = customer_wallet
         ed25519_ix = create_ed25519_instruction(pk_bytes, sig_bytes, wallet_message.encode())
 
     # Privacy mode: keep identity in Postgres but zero it out for Solana
     if customer.get("privacy_mode", False):
-        wallet_bytes = b"\x00" * 32
+        wallets = []
         ed25519_ix = None
 
     # 4. Compute versioning
-    trust_hash = compute_trust_bundle_hash(settings.trust_dir)
+    trust_hash = req.trust_bundle_hash or compute_trust_bundle_hash(settings.trust_dir)
 
     # 5. Encode unified instruction (single tx)
     pda, _ = find_pda([ATTESTATION_SEED, content_hash_bytes], program_id)
@@ -154,9 +221,12 @@ async def edge_attest(req: EdgeAttestRequest, customer: dict = Depends(require_a
         software_agent=req.software_agent,
         signing_time=req.signing_time,
         cert_fingerprint=req.cert_fingerprint,
-        wallet=wallet_bytes,
+        wallets=wallets,
         verifier_version=VERIFIER_VERSION,
         trust_bundle_hash=trust_hash,
+        manifest_hash=req.manifest_hash,
+        assertions_digest=req.assertions_digest,
+        software_agent_canonical=req.software_agent_canonical,
     )
 
     extra_ixs = [ed25519_ix] if ed25519_ix else None
@@ -169,6 +239,7 @@ async def edge_attest(req: EdgeAttestRequest, customer: dict = Depends(require_a
         pda,
         200_000,
         extra_ixs,
+        priority_fee_strategy=settings.priority_fee_strategy,
     )
 
     # 6. Single DB insert (include org info if caller is an org key)
@@ -186,12 +257,15 @@ async def edge_attest(req: EdgeAttestRequest, customer: dict = Depends(require_a
         issuer=req.issuer,
         common_name=req.common_name,
         software_agent=req.software_agent,
+        software_agent_canonical=req.software_agent_canonical or None,
         signing_time=req.signing_time,
         cert_fingerprint=req.cert_fingerprint,
         wallet_pubkey=wallet_pubkey,
         submitted_by=customer.get("name") or org_domain,
         verifier_version=VERIFIER_VERSION,
         trust_bundle_hash=trust_hash,
+        manifest_hash=req.manifest_hash or None,
+        assertions_digest=req.assertions_digest or None,
         tlsh_hash=req.tlsh_hash or None,
         clip_embedding=req.clip_embedding or None,
         org_id=org_id,
@@ -201,6 +275,8 @@ async def edge_attest(req: EdgeAttestRequest, customer: dict = Depends(require_a
         mime_type=req.mime_type or None,
         content_size=req.content_size or None,
     )
+    ATTEST_REQUESTS.labels("trusted_verifier").inc()
+    notify_attestation_created(req.content_hash, req.trust_list_match)
 
     result = {
         "signature": sig,
@@ -0,0 +1,168 @@
+import asyncio
+
+import strawberry
+from strawberry.dataloader import DataLoader
+from strawberry.fastapi import GraphQLRouter
+
+from config import Settings
+import db
+from routes.attestation import attestation_response
+from solana_read import lookup_attestations
+
+MAX_PAGE_SIZE = 200
+
+
+# ── Types ───────────────────────────────────────────────────────────
+
+@strawberry.type
+class Attestation:
+    content_hash: str
+    has_c2pa: bool
+    trust_list_match: str
+    validation_state: str
+    digital_source_type: str
+    issuer: str
+    common_name: str
+    software_agent: str
+    signing_time: str
+    cert_fingerprint: str
+    submitted_by: str
+    timestamp: int
+    proof_type: str
+    email_domain: str | None = None
+    wallet_pubkey: str | None = None
+    verifier_version: str | None = None
+    trust_bundle_hash: str | None = None
+    manifest_hash: str | None = None
+    assertions_digest: str | None = None
+
+    @classmethod
+    def from_response(cls, att: dict) -> "Attestation":
+        return cls(
+            content_hash=att["content_hash"],
+            has_c2pa=bool(att.get("has_c2pa")),
+            trust_list_match=att.get("trust_list_match") or "",
+            validation_state=att.get("validation_state") or "",
+            digital_source_type=att.get("digital_source_type") or "",
+            issuer=att.get("issuer") or "",
+            common_name=att.get("common_name") or "",
+            software_agent=att.get("software_agent") or "",
+            signing_time=att.get("signing_time") or "",
+            cert_fingerprint=att.get("cert_fingerprint") or "",
+            submitted_by=att.get("submitted_by") or "",
+            timestamp=att.get("timestamp") or 0,
+            proof_type=att.get("proof_type") or "",
+            email_domain=att.get("email_domain"),
+            wallet_pubkey=att.get("wallet_pubkey"),
+            verifier_version=att.get("verifier_version"),
+            trust_bundle_hash=att.get("trust_bundle_hash"),
+            manifest_hash=att.get("manifest_hash"),
+            assertions_digest=att.get("assertions_digest"),
+        )
+
+
+@strawberry.type
+class PageInfo:
+    has_next_page: bool
+    end_cursor: str | None
+
+
+@strawberry.type
+class AttestationEdge:
+    cursor: str
+    node: Attestation
+
+
+@strawberry.type
+class AttestationConnection:
+    edges: list[AttestationEdge]
+    page_info: PageInfo
+    total_count: int
+
+
+# ── Loaders ─────────────────────────────────────────────────────────
+
+async def load_attestations(hashes: list[str]) -> list[Attestation | None]:
+    """Resolve a batch of content hashes: DB first, then one getMultipleAccounts call."""
+    found: dict[str, dict] = {}
+    hidden: set[str] = set()
+    for h, row in (await db.get_attestations(hashes)).items():
+        if row.get("private", False):
+            hidden.add(h)
+        else:
+            found[h] = attestation_response(row)
+
+    missing = [h for h in hashes if h not in found and h not in hidden]
+    if missing:
+        settings = Settings()
+        on_chain = await asyncio.to_thread(
+            lookup_attestations, settings.solana_rpc_url, settings.program_id, missing
+        )
+        for h, att in zip(missing, on_chain):
+            if att:
+                found[h] = att
+
+    return [Attestation.from_response(found[h]) if h in found else None for h in hashes]
+
+
+async def get_context() -> dict:
+    return {"attestation_loader": DataLoader(load_fn=load_attestations)}
+
+
+async def _list_public() -> list[dict]:
+    rows = await db.list_attestations()
+    return [attestation_response(r) for r in rows]
+
+
+# ── Schema ──────────────────────────────────────────────────────────
+
+@strawberry.type
+class Query:
+    @strawberry.field
+    async def attestation(self, info: strawberry.Info, hash: str) -> Attestation | None:
+        return await info.context["attestation_loader"].load(hash)
+
+    @strawberry.field
+    async def attestations(self, limit: int | None = 50, after: str | None = None) -> AttestationConnection:
+        items = await _list_public()
+        start = 0
+        if after:
+            start = next((i + 1 for i, a in enumerate(items) if a["content_hash"] == after), len(items))
+        limit = max(1, min(limit or 50, MAX_PAGE_SIZE))
+        page = items[start:start + limit]
+        edges = [AttestationEdge(cursor=a["content_hash"], node=Attestation.from_response(a)) for a in page]
+        return AttestationConnection(
+            edges=edges,
+            page_info=PageInfo(
+                has_next_page=start + limit < len(items),
+                end_cursor=edges[-1].cursor if edges else None,
+            ),
+            total_count=len(items),
+        )
+
+    @strawberry.field
+    async def search(
+        self,
+        issuer: str | None = None,
+        trust_level: str | None = None,
+        after: int | None = None,
+        before: int | None = None,
+    ) -> list[Attestation]:
+        results = []
+        for a in await _list_public():
+            if issuer and issuer.lower() not in a["issuer"].lower():
+                continue
+            if trust_level and a["trust_list_match"] != trust_level:
+                continue
+            if after is not None and a["timestamp"] < after:
+                continue
+            if before is not None and a["timestamp"] > before:
+                continue
+            results.append(Attestation.from_response(a))
+        return results
+
+
+schema = strawberry.Schema(query=Query)
+
+router = GraphQLRouter(schema, context_getter=get_context, graphql_ide=None)
+graphiql_router = GraphQLRouter(schema, context_getter=get_context, graphql_ide="graphiql")
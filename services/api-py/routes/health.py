@@ -0,0 +1,146 @@
+import logging
+import os
+import time
+
+import httpx
+from fastapi import APIRouter
+from fastapi.responses import JSONResponse
+
+from config import Settings
+from rpc import rpc_client
+from versioning import VERIFIER_VERSION
+import db
+
+log = logging.getLogger(__name__)
+
+router = APIRouter()
+
+RPC_TIMEOUT_SECS = 3.0
+
+# GET /api/health: RPC probe timeout, latency above which the API reports
+# "degraded", and how long one result is reused
+HEALTH_RPC_TIMEOUT_SECS = 2.0
+HEALTH_DEGRADED_LATENCY_SECS = 1.0
+HEALTH_CACHE_TTL = 10
+
+# (checked_at, (http status, body))
+_health_cache: tuple[float, tuple[int, dict]] | None = None
+
+
+def _count_dir_certs(dirpath: str) -> int:
+    """Certificates in the .pem files of `dirpath` (0 if it doesn't exist)."""
+    if not os.path.isdir(dirpath):
+        return 0
+    count = 0
+    for fname in os.listdir(dirpath):
+        if not fname.endswith(".pem"):
+            continue
+        with open(os.path.join(dirpath, fname), "rb") as f:
+            count += f.read().count(b"-----BEGIN CERTIFICATE-----")
+    return count
+
+
+def _count_trust_certs(trust_dir: str) -> int:
+    return sum(_count_dir_certs(os.path.join(trust_dir, subdir)) for subdir in ["official", "curated"])
+
+
+def _check_trust(settings: Settings) -> str | None:
+    n = _count_trust_certs(settings.trust_dir)
+    if n == 0:
+        return f"no trust anchors found in {settings.trust_dir}"
+    return None
+
+
+async def _check_solana_url(url: str, timeout: float = RPC_TIMEOUT_SECS) -> str | None:
+    try:
+        async with httpx.AsyncClient(timeout=timeout) as client:
+            resp = await client.post(url, json={
+                "jsonrpc": "2.0", "id": 1, "method": "getHealth",
+            })
+        body = resp.json()
+    except Exception as e:
+        return f"solana rpc unreachable: {e}"
+    if body.get("result") != "ok":
+        return f"solana rpc unhealthy: {body.get('error', body)}"
+    return None
+
+
+async def _check_solana(settings: Settings) -> str | None:
+    """Ready if the primary RPC node or any fallback is healthy."""
+    err = None
+    for url in rpc_client(settings.solana_rpc_url).urls:
+        err = await _check_solana_url(url)
+        if err is None:
+            return None
+        log.info("solana rpc %s not healthy: %s", url, err)
+    return err
+
+
+async def _check_db() -> str | None:
+    if not await db.ping():
+        return "database unavailable"
+    return None
+
+
+async def _health() -> tuple[int, dict]:
+    settings = Settings()
+
+    start = time.monotonic()
+    rpc_err = await _check_solana_url(settings.solana_rpc_url, HEALTH_RPC_TIMEOUT_SECS)
+    latency = time.monotonic() - start
+    solana_rpc = {"url": settings.solana_rpc_url, "latency_ms": round(latency * 1000), "ok": rpc_err is None}
+    if rpc_err:
+        solana_rpc["error"] = rpc_err
+
+    official = _count_dir_certs(os.path.join(settings.trust_dir, "official"))
+    curated = _count_dir_certs(os.path.join(settings.trust_dir, "curated"))
+    trust_dir = {"path": settings.trust_dir, "official_anchors": official, "curated_anchors": curated}
+
+    if rpc_err:
+        status = "down"
+    elif latency > HEALTH_DEGRADED_LATENCY_SECS or official + curated == 0:
+        status = "degraded"
+    else:
+        status = "ok"
+    body = {"status": status, "solana_rpc": solana_rpc, "trust_dir": trust_dir, "version": VERIFIER_VERSION}
+    return (503 if status == "down" else 200), body
+
+
+@router.get("")
+async def health():
+    """Dependency summary: Solana RPC reachability and latency, trust
+    anchors on disk. 503 when the RPC node is unreachable. Results are
+    reused for HEALTH_CACHE_TTL seconds."""
+    global _health_cache
+    now = time.monotonic()
+    if _health_cache is None or now - _health_cache[0] >= HEALTH_CACHE_TTL:
+        _health_cache = (now, await _health())
+    status_code, body = _health_cache[1]
+    return JSONResponse(status_code=status_code, content=body)
+
+
+@router.get("/live")
+async def live():
+    """Liveness: the process is up and serving requests."""
+    return {"status": "ok"}
+
+
+@router.get("/ready")
+async def ready():
+    """Readiness: trust anchors loaded, Solana RPC and database reachable."""
+    settings = Settings()
+    checks = {
+        "trust": _check_trust(settings),
+        "solana": await _check_solana(settings),
+        "database": await _check_db(),
+    }
+    for name, err in checks.items():
+        if err:
+            log.warning("readiness check %s failed: %s", name, err)
+        else:
+            log.info("readiness check %s ok", name)
+
+    failures = {name: err for name, err in checks.items() if err}
+    if failures:
+        return JSONResponse(status_code=503, content={"status": "not ready", "failures": failures})
+    return {"status": "ready"}
@@ -0,0 +1,18 @@
+import json
+import os
+
+from fastapi import APIRouter, HTTPException
+
+from config import Settings
+
+router = APIRouter()
+
+
+@router.get("/idl")
+async def get_idl():
+    """Anchor IDL of the provenance_attestation program, for client SDK generation."""
+    settings = Settings()
+    if not os.path.isfile(settings.idl_path):
+        raise HTTPException(404, "IDL not built (run `make publish-idl` or `anchor build`)")
+    with open(settings.idl_path) as f:
+        return json.load(f)
@@ -1,7 +1,6 @@
 import asyncio
 import random
 import secrets
-import smtplib
 import string
 from dataclasses import dataclass, field
 from datetime import datetime, timedelta
@@ -13,6 +12,7 @@ from pydantic import BaseModel
 import db
 from auth import require_api_key, require_org_admin
 from config import Settings
+from mailer import send_email
 from did import get_all_dids_for_org
 
 router = APIRouter()
@@ -52,6 +52,85 @@ def _generate_code() -> str:
     return "".join(random.choices(string.digits, k=6))
 
 
+# ── DNS helpers ─────────────────────────────────────────────────────
+
+DNS_TOKEN_PREFIX = "r3l-verify="
+DNS_CHALLENGE_LABEL = "_r3l-verify"
+
+
+async def _issue_dns_token(
+    domain: str, existing: dict | None, name: str | None, admin_email: str | None,
+) -> str:
+    """Create the org (or reset an unverified one) with a fresh DNS token."""
+    dns_token = DNS_TOKEN_PREFIX + secrets.token_hex(16)
+    if not existing:
+        await db.insert_organization(
+            domain=domain,
+            name=name,
+            verification_method="dns",
+            dns_token=dns_token,
+            admin_email=admin_email,
+        )
+        return dns_token
+
+    async with db.get_session() as session:
+        from sqlalchemy import select
+        from models import Organization
+        stmt = select(Organization).where(Organization.domain == domain)
+        row = (await session.execute(stmt)).scalar_one_or_none()
+        if row:
+            row.dns_token = dns_token
+            row.verification_method = "dns"
+            if admin_email:
+                row.admin_email = admin_email
+            if name:
+                row.name = name
+            await session.commit()
+    return dns_token
+
+
+async def _txt_records(name: str) -> list[str]:
+    try:
+        import dns.resolver
+        answers = await asyncio.to_thread(
+            lambda: dns.resolver.resolve(name, "TXT")
+        )
+    except Exception:
+        return []
+    return [s.decode() for rdata in answers for s in rdata.strings]
+
+
+async def _complete_dns_verification(org: dict) -> dict:
+    """Look for the org's token on the domain (or its _r3l-verify record) and verify it."""
+    domain = org["domain"]
+    token = org["dns_token"]
+    txt_records = await _txt_records(f"{DNS_CHALLENGE_LABEL}.{domain}")
+    if token not in txt_records and token.removeprefix(DNS_TOKEN_PREFIX) not in txt_records:
+        txt_records += await _txt_records(domain)
+        if token not in txt_records:
+            raise HTTPException(
+                400,
+                f"TXT record not found. Expected: {token}. Found: {txt_records}",
+            )
+
+    verified_org = await db.verify_organization(domain)
+    api_key = _generate_api_key()
+    await db.insert_org_api_key(
+        org_id=verified_org["id"],
+        api_key=api_key,
+        email=org.get("admin_email"),
+        role="admin",
+    )
+
+    return {
+        "status": "verified",
+        "domain": domain,
+        "verification_method": "dns",
+        "api_key": api_key,
+        "role": "admin",
+    }
+
+
 # ── Request models ──────────────────────────────────────────────────
 
 class RegisterRequest(BaseModel):
@@ -65,6 +144,12 @@ class VerifyDnsRequest(BaseModel):
     domain: str
 
 
+class StartDnsRequest(BaseModel):
+    domain: str
+    admin_email: str | None = None
+    name: str | None = None
+
+
 class VerifyEmailRequest(BaseModel):
     email: str
     code: str
@@ -155,7 +240,7 @@ async def register(req: RegisterRequest):
             msg["To"] = req.admin_email
 
             try:
-                await asyncio.to_thread(_send_email, settings, msg)
+                await asyncio.to_thread(send_email, msg)
             except Exception as e:
                 raise HTTPException(500, f"failed to send email: {e}")
         else:
@@ -164,34 +249,7 @@ async def register(req: RegisterRequest):
         return resp
 
     if req.method == "dns":
-        dns_token = "r3l-verify=" + secrets.token_hex(16)
-        if existing:
-            # Update existing unverified org
-            org = existing
-        else:
-            org = await db.insert_organization(
-                domain=domain,
-                name=req.name,
-                verification_method="dns",
-                dns_token=dns_token,
-                admin_email=req.admin_email,
-            )
-        # For existing orgs, update dns_token in-place
-        if existing:
-            async with db.get_session() as session:
-                from sqlalchemy import select
-                from models import Organization
-                stmt = select(Organization).where(Organization.domain == domain)
-                row = (await session.execute(stmt)).scalar_one_or_none()
-                if row:
-                    row.dns_token = dns_token
-                    row.verification_method = "dns"
-                    if req.admin_email:
-                        row.admin_email = req.admin_email
-                    if req.name:
-                        row.name = req.name
-                    await session.commit()
-
+        dns_token = await _issue_dns_token(domain, existing, req.name, req.admin_email)
         return {
             "status": "pending",
             "method": "dns",
@@ -251,7 +309,7 @@ async def register(req: RegisterRequest):
             msg["To"] = req.admin_email
 
             try:
-                await asyncio.to_thread(_send_email, settings, msg)
+                await asyncio.to_thread(send_email, msg)
             except Exception as e:
                 raise HTTPException(500, f"failed to send email: {e}")
         else:
@@ -264,12 +322,6 @@ async def register(req: RegisterRequest):
         raise HTTPException(400, "method must be 'dns' or 'email'")
 
 
-def _send_email(settings: Settings, msg: MIMEText):
-    with smtplib.SMTP_SSL(settings.smtp_host, 465) as server:
-        server.login(settings.smtp_user, settings.smtp_pass)
-        server.send_message(msg)
-
-
 # ── POST /api/org/verify/dns ────────────────────────────────────────
 
 @router.post("/verify/dns")
@@ -283,39 +335,61 @@ async def verify_dns(req: VerifyDnsRequest):
     if not org["dns_token"]:
         raise HTTPException(400, "no DNS verification pending for this domain")
 
-    # DNS TXT lookup
-    try:
-        import dns.resolver
-        answers = await asyncio.to_thread(
-            lambda: dns.resolver.resolve(domain, "TXT")
-        )
-        txt_records = []
-        for rdata in answers:
-            for txt_string in rdata.strings:
-                txt_records.append(txt_string.decode())
-    except Exception:
-        txt_records = []
+    return await _complete_dns_verification(org)
 
-    if org["dns_token"] not in txt_records:
-        raise HTTPException(
-            400,
-            f"TXT record not found. Expected: {org['dns_token']}. Found: {txt_records}",
-        )
 
-    verified_org = await db.verify_organization(domain)
-    api_key = _generate_api_key()
-    await db.insert_org_api_key(
-        org_id=verified_org["id"],
-        api_key=api_key,
-        email=org.get("admin_email"),
-        role="admin",
-    )
+# ── POST /api/org/start-dns ─────────────────────────────────────────
 
+@router.post("/start-dns")
+async def start_dns(req: StartDnsRequest):
+    """DNS-only alternative to register(method="email") for pipelines that can't receive mail."""
+    domain = req.domain.lower().strip()
+    if not domain or "." not in domain:
+        raise HTTPException(400, "invalid domain")
+    if domain in BLOCKED_DOMAINS:
+        raise HTTPException(400, f"{domain} is a public email provider and cannot be registered as an organization")
+
+    existing = await db.get_organization_by_domain(domain)
+    if existing and existing["verified"]:
+        raise HTTPException(409, "organization already verified")
+
+    dns_token = await _issue_dns_token(domain, existing, req.name, req.admin_email)
+    token = dns_token.removeprefix(DNS_TOKEN_PREFIX)
+    record = f"{DNS_CHALLENGE_LABEL}.{domain}"
     return {
-        "status": "verified",
+        "status": "pending",
+        "method": "dns",
         "domain": domain,
-        "api_key": api_key,
-        "role": "admin",
+        "record": record,
+        "txt_value": token,
+        "token": token,
+        "instruction": f"Add a TXT record to {record} with value: {token}",
+    }
+
+
+# ── GET /api/org/verify-dns/{token} ─────────────────────────────────
+
+@router.get("/verify-dns/{token}")
+async def verify_dns_token(token: str):
+    org = await db.get_organization_by_dns_token(DNS_TOKEN_PREFIX + token)
+    if not org:
+        raise HTTPException(404, "unknown DNS verification token")
+    if org["verified"]:
+        raise HTTPException(409, "already verified")
+    return await _complete_dns_verification(org)
+
+
+# ── GET /api/org/status/{domain} ────────────────────────────────────
+
+@router.get("/status/{domain}")
+async def status(domain: str):
+    org = await db.get_organization_by_domain(domain.lower().strip())
+    if not org:
+        raise HTTPException(404, "organization not found")
+    return {
+        "domain": org["domain"],
+        "verified": org["verified"],
+        "verification_method": org["verification_method"],
     }
 
 
@@ -366,6 +440,7 @@ async def verify_email(req: VerifyEmailRequest):
     return {
         "status": "verified",
         "domain": domain,
+        "verification_method": "email",
         "api_key": api_key,
         "role": "admin",
     }
@@ -420,7 +495,7 @@ async def resend_code(req: ResendRequest):
         msg["To"] = req.admin_email
 
         try:
-            await asyncio.to_thread(_send_email, settings, msg)
+            await asyncio.to_thread(send_email, msg)
         except Exception as e:
             raise HTTPException(500, f"failed to send email: {e}")
     else:
@@ -1,56 +1,95 @@
 import asyncio
 import json
 import os
+import re
 import tempfile
 
-from fastapi import APIRouter, File, HTTPException, UploadFile
+from fastapi import APIRouter, Depends, HTTPException, Query, UploadFile
+from fastapi.responses import StreamingResponse
 
 from config import Settings
-from routes.verify import run_verifier
+from metrics import PROOF_GENERATION
+from prove_progress import FINAL_STAGES, publish, subscribe
+from routes.verify import run_verifier_file, save_upload, upload_file
 
 router = APIRouter()
 
+# prove exits with this code when execution exceeds --max-cycles
+EXIT_TOO_MANY_CYCLES = 2
+
+CONTENT_HASH_RE = re.compile(r"[0-9a-fA-F]{64}")
+CYCLES_RE = re.compile(r"executed in (\d+) cycles")
+# prove's stderr lines that start a stage, checked by prefix
+STAGE_LINES = (
+    ("generating Groth16 proof", "proving"),
+    ("verifying proof", "verifying"),
+)
+
+
+def progress_event(line: str) -> dict | None:
+    """Progress event for one line of prover stderr, if it marks a stage."""
+    if m := CYCLES_RE.match(line):
+        return {"stage": "executing", "cycles": int(m.group(1))}
+    for prefix, stage in STAGE_LINES:
+        if line.startswith(prefix):
+            return {"stage": stage}
+    return None
+
+
+async def read_stderr(stream: asyncio.StreamReader, content_hash: str) -> bytes:
+    """Drain prover stderr, publishing progress as stages start."""
+    lines = []
+    while line := await stream.readline():
+        lines.append(line)
+        if event := progress_event(line.decode(errors="replace").strip()):
+            publish(content_hash, event)
+    return b"".join(lines)
+
 
 @router.post("/prove")
-async def prove(file: UploadFile = File(...)):
+async def prove(file: UploadFile = Depends(upload_file)):
     settings = Settings()
-    file_bytes = await file.read()
     filename = file.filename or "upload"
 
-    # 1. Verify
-    verify_output = await run_verifier(file_bytes, filename, settings)
-
-    # 2. Write file to temp (for prover)
-    ext = os.path.splitext(filename)[1] if filename else ""
-    media_tmp = tempfile.NamedTemporaryFile(suffix=ext, delete=False)
-    media_tmp.write(file_bytes)
-    media_tmp.close()
+    # 1. Stream upload to temp (shared by verifier and prover)
+    media_path, content_hash = await save_upload(file)
 
-    # 3. Create sidecar temp file
+    # 2. Create sidecar temp file
     sidecar_tmp = tempfile.NamedTemporaryFile(suffix=".json", delete=False)
     sidecar_tmp.close()
 
     try:
+        # 3. Verify
+        verify_output = await run_verifier_file(media_path, filename, content_hash, settings)
+
         # 4. Run prover binary
         prover_bin = os.path.join(settings.prover_dir, "target/release/prove")
         args = [
             prover_bin,
-            "--media", media_tmp.name,
+            "--media", media_path,
             "--trust-dir", settings.trust_dir,
             "--json-out", sidecar_tmp.name,
+            "--max-cycles", str(settings.max_prove_cycles),
         ]
         use_mock = settings.prover_mock != "false"
         if use_mock:
             args.append("--mock")
 
-        proc = await asyncio.create_subprocess_exec(
-            *args,
-            stdout=asyncio.subprocess.PIPE,
-            stderr=asyncio.subprocess.PIPE,
-            cwd=settings.prover_dir,
-        )
-        stdout, stderr = await proc.communicate()
+        publish(content_hash, {"stage": "executing"})
+        with PROOF_GENERATION.time():
+            proc = await asyncio.create_subprocess_exec(
+                *args,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE,
+                cwd=settings.prover_dir,
+            )
+            stdout, stderr = await asyncio.gather(
+                proc.stdout.read(), read_stderr(proc.stderr, content_hash)
+            )
+            await proc.wait()
 
+        if proc.returncode == EXIT_TOO_MANY_CYCLES:
+            raise HTTPException(422, f"proof exceeds {settings.max_prove_cycles} cycles")
         if proc.returncode != 0:
             raise HTTPException(
                 500,
@@ -61,11 +100,41 @@ async def prove(file: UploadFile = File(...)):
         with open(sidecar_tmp.name) as f:
             sidecar = json.load(f)
 
+        publish(content_hash, {"stage": "done"})
         return {
             "proof": sidecar.get("proof", ""),
             "public_outputs": sidecar.get("public_values", ""),
             "verify_output": verify_output,
         }
+    except HTTPException as e:
+        publish(content_hash, {"stage": "failed", "error": e.detail})
+        raise
+    except BaseException:
+        publish(content_hash, {"stage": "failed", "error": "internal error"})
+        raise
     finally:
-        os.unlink(media_tmp.name)
+        os.unlink(media_path)
         os.unlink(sidecar_tmp.name)
+
+
+@router.get("/prove/stream")
+async def prove_stream(content_hash: str = Query(...)):
+    """Server-sent progress events for a POST /api/prove of the file with
+    this SHA-256. Open it before posting the file; the stream ends after
+    the `done` or `failed` event."""
+    if not CONTENT_HASH_RE.fullmatch(content_hash):
+        raise HTTPException(400, "content_hash must be 64 hex characters")
+
+    async def events():
+        with subscribe(content_hash) as queue:
+            while True:
+                event = await queue.get()
+                yield f"data: {json.dumps(event)}\n\n"
+                if event["stage"] in FINAL_STAGES:
+                    return
+
+    return StreamingResponse(
+        events(),
+        media_type="text/event-stream",
+        headers={"Cache-Control": "no-cache"},
+    )
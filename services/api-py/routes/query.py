@@ -84,6 +84,8 @@ def _format_response(att: dict) -> dict:
             "submitted_by": att.get("submitted_by") or None,
             "verifier_version": att.get("verifier_version") or None,
             "trust_bundle_hash": att.get("trust_bundle_hash") or None,
+            "manifest_hash": att.get("manifest_hash") or None,
+            "assertions_digest": att.get("assertions_digest") or None,
         },
     }
 
@@ -1,8 +1,8 @@
 import hashlib
 
-from fastapi import APIRouter, File, HTTPException, UploadFile
+from fastapi import APIRouter, Depends, HTTPException, UploadFile
 
-from routes.verify import validate_upload
+from routes.verify import upload_file, validate_upload
 from similarity import compute_tlsh, compute_clip_embedding, tlsh_distance
 import db
 
@@ -52,7 +52,7 @@ def _sort_matches(matches: list[dict]) -> list[dict]:
 
 
 @router.post("")
-async def search_similar_by_file(file: UploadFile = File(...)):
+async def search_similar_by_file(file: UploadFile = Depends(upload_file)):
     """Upload a file and find similar attested content."""
     file_bytes = await file.read()
     validate_upload(file_bytes, file.content_type)
@@ -0,0 +1,65 @@
+import asyncio
+import time
+
+from fastapi import APIRouter, HTTPException
+from fastapi.responses import JSONResponse
+
+from config import Settings
+from solana_read import fetch_all_attestations
+
+router = APIRouter()
+
+STATS_CACHE_TTL = 300  # seconds
+
+# (computed_at, stats); per process, like the graph cache
+_stats_cache: tuple[float, dict] | None = None
+
+
+def aggregate(attestations: list[dict]) -> dict:
+    """Summary counts over deserialized Attestation accounts."""
+    by_trust_level = {"official": 0, "curated": 0, "untrusted": 0}
+    by_proof_type = {"zk_groth16": 0, "trusted_verifier": 0}
+    identity = {"email": 0, "wallet": 0}
+    has_c2pa = 0
+    for att in attestations:
+        level = att.get("trust_list_match")
+        by_trust_level[level if level in ("official", "curated") else "untrusted"] += 1
+        proof_type = att.get("proof_type") or ""
+        by_proof_type[proof_type] = by_proof_type.get(proof_type, 0) + 1
+        if att.get("email_domain"):
+            identity["email"] += 1
+        if att.get("wallet_pubkeys"):
+            identity["wallet"] += 1
+        if att.get("has_c2pa"):
+            has_c2pa += 1
+
+    total = len(attestations)
+    return {
+        "total": total,
+        "by_trust_level": by_trust_level,
+        "by_proof_type": by_proof_type,
+        "identity": identity,
+        "has_c2pa_percent": round(100 * has_c2pa / total, 2) if total else 0.0,
+        "last_attested_at": max((a["timestamp"] for a in attestations), default=None),
+    }
+
+
+@router.get("/stats")
+async def stats():
+    """Aggregate counts over all on-chain attestations, recomputed at most
+    every STATS_CACHE_TTL seconds (`Cache-Status: HIT` when served cached)."""
+    global _stats_cache
+    now = time.time()
+    if _stats_cache and now - _stats_cache[0] < STATS_CACHE_TTL:
+        return JSONResponse(_stats_cache[1], headers={"Cache-Status": "HIT"})
+
+    settings = Settings()
+    try:
+        attestations = await asyncio.to_thread(
+            fetch_all_attestations, settings.solana_rpc_url, settings.program_id
+        )
+    except Exception as e:
+        raise HTTPException(502, f"solana rpc error: {e}")
+    result = aggregate(attestations)
+    _stats_cache = (now, result)
+    return JSONResponse(result, headers={"Cache-Status": "MISS"})
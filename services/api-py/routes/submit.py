@@ -1,18 +1,34 @@
 import asyncio
+import logging
+import os
+import re
 
 from fastapi import APIRouter, HTTPException
 from pydantic import BaseModel
 
 from config import Settings
+from metrics import ATTEST_REQUESTS
 from solana_tx import (
     ATTESTATION_SEED,
+    NONCE_SEED,
     build_and_send_tx,
     encode_proof_data,
     find_pda,
 )
+from solders.instruction import AccountMeta
 from solders.pubkey import Pubkey
+from webhook_delivery import notify_attestation_created
 
 router = APIRouter()
+log = logging.getLogger(__name__)
+
+PREVERIFY_TIMEOUT = 60  # seconds
+
+# Same bounds as the program's MAX_PROOF_LEN / MAX_PUBLIC_INPUTS_LEN (constants.rs)
+MAX_PROOF_LEN = 2048
+MAX_PUBLIC_INPUTS_LEN = 512
+
+CONTENT_HASH_RE = re.compile(r"[0-9a-fA-F]{64}")
 
 
 class SubmitRequest(BaseModel):
@@ -21,19 +37,66 @@ class SubmitRequest(BaseModel):
     public_inputs: str
 
 
+async def preverify_proof(proof_bytes: bytes, public_inputs_bytes: bytes, settings: Settings) -> bool:
+    """Run the same Groth16 check as the on-chain program before paying for a tx."""
+    verify_bin = os.path.join(settings.prover_dir, "target/release/verify_proof")
+    try:
+        proc = await asyncio.create_subprocess_exec(
+            verify_bin,
+            "--proof", proof_bytes.hex(),
+            "--public-inputs", public_inputs_bytes.hex(),
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE,
+            cwd=settings.prover_dir,
+        )
+    except FileNotFoundError:
+        log.error("verify_proof binary not found at %s", verify_bin)
+        raise HTTPException(503, "proof pre-verification unavailable: verify_proof is not built")
+    try:
+        _, stderr = await asyncio.wait_for(proc.communicate(), timeout=PREVERIFY_TIMEOUT)
+    except asyncio.TimeoutError:
+        proc.kill()
+        raise HTTPException(504, "proof pre-verification timed out")
+
+    ok = proc.returncode == 0
+    log.info(
+        "proof pre-verification: proof=%d bytes public_inputs=%d bytes ok=%s",
+        len(proof_bytes), len(public_inputs_bytes), ok,
+    )
+    if not ok:
+        log.info("verify_proof stderr: %s", stderr.decode().strip())
+    return ok
+
+
+def decode_hex_field(name: str, value: str, max_len: int) -> bytes:
+    """Decode a hex request field, rejecting oversized input before decoding it."""
+    if len(value) > 2 * max_len:
+        raise HTTPException(400, f"{name} exceeds {max_len} bytes ({len(value) // 2} bytes given)")
+    try:
+        return bytes.fromhex(value)
+    except ValueError:
+        raise HTTPException(400, f"{name} is not valid hex")
+
+
 @router.post("/submit")
 async def submit(req: SubmitRequest):
     settings = Settings()
 
+    if not CONTENT_HASH_RE.fullmatch(req.content_hash):
+        raise HTTPException(400, "content_hash must be 64 hex characters")
     content_hash_bytes = bytes.fromhex(req.content_hash)
-    if len(content_hash_bytes) != 32:
-        raise HTTPException(400, "content_hash must be 32 bytes")
 
-    proof_bytes = bytes.fromhex(req.proof)
-    public_inputs_bytes = bytes.fromhex(req.public_inputs)
+    proof_bytes = decode_hex_field("proof", req.proof, MAX_PROOF_LEN)
+    public_inputs_bytes = decode_hex_field("public_inputs", req.public_inputs, MAX_PUBLIC_INPUTS_LEN)
+
+    # Mock proofs are only accepted by skip-verification program builds
+    if settings.prover_mock == "false":
+        if not await preverify_proof(proof_bytes, public_inputs_bytes, settings):
+            raise HTTPException(400, "proof verification failed")
 
     program_id = Pubkey.from_string(settings.program_id)
     pda, _ = find_pda([ATTESTATION_SEED, content_hash_bytes], program_id)
+    nonce_pda, _ = find_pda([NONCE_SEED, content_hash_bytes], program_id)
 
     ix_data = encode_proof_data(proof_bytes, public_inputs_bytes, content_hash_bytes)
 
@@ -45,7 +108,12 @@ async def submit(req: SubmitRequest):
         ix_data,
         pda,
         400_000,
+        priority_fee_strategy=settings.priority_fee_strategy,
+        extra_accounts=[AccountMeta(nonce_pda, is_signer=False, is_writable=True)],
     )
+    ATTEST_REQUESTS.labels("zk_groth16").inc()
+    # The trust level is committed inside the proof's public outputs, not sent alongside
+    notify_attestation_created(req.content_hash, None)
 
     return {
         "signature": sig,
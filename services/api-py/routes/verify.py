@@ -1,84 +1,417 @@
 import asyncio
 import hashlib
+import io
+import ipaddress
 import json
 import os
+import re
+import socket
 import tempfile
+from urllib.parse import urlparse
 
-from fastapi import APIRouter, File, HTTPException, UploadFile
+import httpx
+import zxingcpp
+from fastapi import APIRouter, Depends, HTTPException, Query, Request, UploadFile
+from PIL import Image
+from pydantic import BaseModel
+from starlette.datastructures import UploadFile as FormFile
 
 from config import Settings
+from routes.attestation import lookup
 
 router = APIRouter()
 
 MAX_FILE_SIZE = 50 * 1024 * 1024  # 50 MB
 ALLOWED_MIME_PREFIXES = ("image/", "video/", "audio/", "application/pdf", "text/")
 VERIFIER_TIMEOUT = 60  # seconds
+FINGERPRINT_ALGS = ("sha1", "sha256", "sha384")
+QR_IMAGE_TYPES = ("image/jpeg", "image/png")
+# A content hash anywhere in the QR text: bare, or inside a lookup URL
+CONTENT_HASH_RE = re.compile(r"(?<![0-9a-fA-F])[0-9a-fA-F]{64}(?![0-9a-fA-F])")
 
 
-def validate_upload(file_bytes: bytes, content_type: str | None = None):
-    if len(file_bytes) > MAX_FILE_SIZE:
-        raise HTTPException(413, f"file too large: {len(file_bytes)} bytes (max {MAX_FILE_SIZE})")
+UPLOAD_CHUNK_SIZE = 1024 * 1024  # 1 MB
+# Multipart field names tried in order before falling back to the first file
+UPLOAD_FIELD_NAMES = ("file", "upload", "media")
+# /verify/batch reads every file under these field names, in request order
+BATCH_FIELD_NAMES = ("file[]", "file")
+MAX_BATCH_FILES = 10
+# /verify-url redirect hops, each re-checked by check_public_url
+MAX_URL_REDIRECTS = 5
+
+
+async def upload_file(request: Request) -> UploadFile:
+    """The uploaded file, whatever its multipart field is called.
+
+    Only one file is processed; any others in the request are ignored.
+    """
+    form = await request.form()
+    files = [(name, value) for name, value in form.multi_items() if isinstance(value, FormFile)]
+    if not files:
+        raise HTTPException(422, "multipart body has no file field")
+    for wanted in UPLOAD_FIELD_NAMES:
+        for name, value in files:
+            if name == wanted:
+                return value
+    return files[0][1]
+
+
+def validate_content_type(content_type: str | None):
     if content_type:
         ct = content_type.lower()
         if not any(ct.startswith(p) for p in ALLOWED_MIME_PREFIXES):
             raise HTTPException(415, f"unsupported media type: {ct}")
 
 
+def validate_upload(file_bytes: bytes, content_type: str | None = None):
+    if len(file_bytes) > MAX_FILE_SIZE:
+        raise HTTPException(413, f"file too large: {len(file_bytes)} bytes (max {MAX_FILE_SIZE})")
+    validate_content_type(content_type)
+
+
+async def save_upload(file: UploadFile) -> tuple[str, str]:
+    """Stream an upload to a temp file chunk by chunk, enforcing MAX_FILE_SIZE.
+
+    Returns (path, sha256 hex). The caller must unlink the path.
+    """
+    ext = os.path.splitext(file.filename or "")[1]
+    tmp = tempfile.NamedTemporaryFile(suffix=ext, delete=False)
+    hasher = hashlib.sha256()
+    size = 0
+    try:
+        while chunk := await file.read(UPLOAD_CHUNK_SIZE):
+            size += len(chunk)
+            if size > MAX_FILE_SIZE:
+                raise HTTPException(413, f"file too large (max {MAX_FILE_SIZE} bytes)")
+            hasher.update(chunk)
+            tmp.write(chunk)
+        tmp.close()
+    except BaseException:
+        tmp.close()
+        os.unlink(tmp.name)
+        raise
+    return tmp.name, hasher.hexdigest()
+
+
 async def run_verifier(file_bytes: bytes, filename: str, settings: Settings) -> dict:
     ext = os.path.splitext(filename)[1] if filename else ""
     tmp = tempfile.NamedTemporaryFile(suffix=ext, delete=False)
     try:
         tmp.write(file_bytes)
         tmp.close()
+        return await run_verifier_file(tmp.name, filename, hashlib.sha256(file_bytes).hexdigest(), settings)
+    finally:
+        os.unlink(tmp.name)
+
+
+async def exec_verifier(settings: Settings, *args: str) -> tuple[int, bytes]:
+    """Run the verifier binary with `args`; returns (exit code, stdout)."""
+    proc = await asyncio.create_subprocess_exec(
+        settings.verifier_bin, *args,
+        stdout=asyncio.subprocess.PIPE,
+        stderr=asyncio.subprocess.PIPE,
+        env={**os.environ, "TRUST_DIR": settings.trust_dir},
+    )
+    try:
+        stdout, _ = await asyncio.wait_for(proc.communicate(), timeout=VERIFIER_TIMEOUT)
+    except asyncio.TimeoutError:
+        proc.kill()
+        raise HTTPException(504, "verifier timed out")
+    return proc.returncode, stdout
+
+
+def unsigned_output(filename: str, content_hash: str | None, error: str | None = None) -> dict:
+    """Verifier output shape for a file with no usable C2PA data."""
+    return {
+        "path": filename,
+        "content_hash": content_hash,
+        "has_c2pa": False,
+        "trust_list_match": None,
+        "validation_state": None,
+        "validation_state_code": None,
+        "validation_error_count": None,
+        "validation_codes": None,
+        "title": None,
+        "format": None,
+        "format_detected": None,
+        "format_claimed": None,
+        "format_mismatch": False,
+        "digital_source_type": None,
+        "claim_generator": None,
+        "software_agent": None,
+        "software_agent_canonical": None,
+        "issuer": None,
+        "issuer_normalized": None,
+        "common_name": None,
+        "signing_time": None,
+        "sig_algorithm": None,
+        "actions": None,
+        "ingredients": None,
+        "has_ingredients": False,
+        "ingredient_count": 0,
+        "manifest_store": None,
+        "manifest_hash": None,
+        "trust_bundle_hash": None,
+        "assertion_labels": None,
+        "assertions_digest": None,
+        "exif_make": None,
+        "exif_model": None,
+        "exif_gps_lat": None,
+        "exif_gps_lon": None,
+        "location_country": None,
+        "location_region": None,
+        "location_city": None,
+        "location_lat": None,
+        "location_lon": None,
+        "manifest_label": None,
+        "active_manifest_label": None,
+        "container_brand": None,
+        "compatible_brands": None,
+        "cert_fingerprint": None,
+        "fingerprint_alg": None,
+        "cert_chain_depth": None,
+        "ocsp_status": None,
+        "cert_revoked": False,
+        "parse_fallback_used": False,
+        "error": error,
+    }
+
+
+async def run_verifier_file(
+    path: str,
+    filename: str,
+    content_hash: str,
+    settings: Settings,
+    fingerprint_alg: str = "sha256",
+) -> dict:
+    """Run the verifier on a file already on disk. `content_hash` is used
+    for the unsigned fallback when the verifier rejects the file."""
+    returncode, stdout = await exec_verifier(settings, "--fingerprint-alg", fingerprint_alg, path)
+
+    if returncode != 0:
+        # Verifier can't handle this file type (e.g. PDF without C2PA support).
+        # Return an unsigned result with the content hash computed in Python.
+        return unsigned_output(filename, content_hash)
+
+    return json.loads(stdout.decode())
 
-        proc = await asyncio.create_subprocess_exec(
-            settings.verifier_bin, tmp.name,
-            stdout=asyncio.subprocess.PIPE,
-            stderr=asyncio.subprocess.PIPE,
-            env={**os.environ, "TRUST_DIR": settings.trust_dir},
+
+@router.post("/verify")
+async def verify(
+    file: UploadFile = Depends(upload_file),
+    fingerprint_alg: str = Query("sha256"),
+):
+    settings = Settings()
+    fingerprint_alg = fingerprint_alg.lower()
+    if fingerprint_alg not in FINGERPRINT_ALGS:
+        raise HTTPException(400, f"fingerprint_alg must be one of {', '.join(FINGERPRINT_ALGS)}")
+    validate_content_type(file.content_type)
+    path, content_hash = await save_upload(file)
+    try:
+        return await run_verifier_file(
+            path, file.filename or "upload", content_hash, settings, fingerprint_alg
         )
-        try:
-            stdout, stderr = await asyncio.wait_for(proc.communicate(), timeout=VERIFIER_TIMEOUT)
-        except asyncio.TimeoutError:
-            proc.kill()
-            raise HTTPException(504, "verifier timed out")
-
-        if proc.returncode != 0:
-            # Verifier can't handle this file type (e.g. PDF without C2PA support).
-            # Return an unsigned result with the content hash computed in Python.
-            content_hash = hashlib.sha256(file_bytes).hexdigest()
-            return {
-                "path": filename,
-                "content_hash": content_hash,
-                "has_c2pa": False,
-                "trust_list_match": None,
-                "validation_state": None,
-                "validation_error_count": None,
-                "validation_codes": None,
-                "title": None,
-                "format": None,
-                "digital_source_type": None,
-                "claim_generator": None,
-                "software_agent": None,
-                "issuer": None,
-                "common_name": None,
-                "signing_time": None,
-                "sig_algorithm": None,
-                "actions": None,
-                "ingredients": None,
-                "manifest_store": None,
-                "error": None,
-            }
-
-        return json.loads(stdout.decode())
     finally:
+        os.unlink(path)
+
+
+async def verify_one(file: UploadFile, settings: Settings) -> dict:
+    """Verifier output for one batch file; failures become its `error`."""
+    filename = file.filename or "upload"
+    try:
+        validate_content_type(file.content_type)
+        path, content_hash = await save_upload(file)
+    except HTTPException as e:
+        return unsigned_output(filename, None, e.detail)
+    try:
+        return await run_verifier_file(path, filename, content_hash, settings)
+    except HTTPException as e:
+        return unsigned_output(filename, content_hash, e.detail)
+    except ValueError:
+        return unsigned_output(filename, content_hash, "verifier returned invalid output")
+    finally:
+        os.unlink(path)
+
+
+@router.post("/verify/batch")
+async def verify_batch(request: Request):
+    """Verify up to MAX_BATCH_FILES uploads concurrently. Results are in
+    upload order, and a file that can't be verified gets an `error`
+    instead of failing the batch."""
+    settings = Settings()
+    form = await request.form()
+    files = [
+        value for name, value in form.multi_items()
+        if name in BATCH_FIELD_NAMES and isinstance(value, FormFile)
+    ]
+    if not files:
+        raise HTTPException(422, "multipart body has no file[] fields")
+    if len(files) > MAX_BATCH_FILES:
+        raise HTTPException(400, f"too many files: {len(files)} (max {MAX_BATCH_FILES})")
+    return await asyncio.gather(*(verify_one(f, settings) for f in files))
+
+
+class VerifyUrlRequest(BaseModel):
+    url: str                      # https:// only
+    max_bytes: int | None = None  # download cap, at most MAX_FILE_SIZE
+
+
+async def check_public_url(url: str):
+    """Reject `url` unless it is https and every address its host resolves
+    to is public, so /verify-url can't be pointed at loopback, private,
+    link-local (cloud metadata) or other internal addresses."""
+    parsed = urlparse(url)
+    if parsed.scheme != "https" or not parsed.hostname:
+        raise HTTPException(400, "url must be an https:// URL")
+    try:
+        infos = await asyncio.get_running_loop().getaddrinfo(
+            parsed.hostname, parsed.port or 443, type=socket.SOCK_STREAM
+        )
+    except socket.gaierror:
+        raise HTTPException(422, f"could not resolve {parsed.hostname}")
+    for *_, sockaddr in infos:
+        addr = ipaddress.ip_address(sockaddr[0])
+        if not addr.is_global or addr.is_multicast:
+            raise HTTPException(400, "url must resolve to a public address")
+
+
+async def download_url(url: str, max_bytes: int) -> tuple[str, str]:
+    """Stream `url` to a temp file keeping its extension, enforcing
+    `max_bytes`. Redirects are followed by hand so each hop goes through
+    check_public_url. Returns (path, sha256 hex). The caller must unlink
+    the path."""
+    ext = os.path.splitext(urlparse(url).path)[1]
+    tmp = tempfile.NamedTemporaryFile(suffix=ext, delete=False)
+    hasher = hashlib.sha256()
+    size = 0
+    try:
+        async with httpx.AsyncClient(follow_redirects=False, timeout=30) as client:
+            for _ in range(MAX_URL_REDIRECTS + 1):
+                await check_public_url(url)
+                async with client.stream("GET", url, headers={"User-Agent": "R3L-Verifier/1.0"}) as resp:
+                    if resp.is_redirect:
+                        url = str(resp.url.join(resp.headers["location"]))
+                        continue
+                    if resp.is_error:
+                        raise HTTPException(422, f"URL returned {resp.status_code}")
+                    async for chunk in resp.aiter_bytes(UPLOAD_CHUNK_SIZE):
+                        size += len(chunk)
+                        if size > max_bytes:
+                            raise HTTPException(413, f"file too large (max {max_bytes} bytes)")
+                        hasher.update(chunk)
+                        tmp.write(chunk)
+                    break
+            else:
+                raise HTTPException(422, f"more than {MAX_URL_REDIRECTS} redirects")
+        tmp.close()
+    except httpx.HTTPError as e:
+        tmp.close()
+        os.unlink(tmp.name)
+        raise HTTPException(502, f"failed to fetch URL: {e}")
+    except BaseException:
+        tmp.close()
         os.unlink(tmp.name)
+        raise
+    return tmp.name, hasher.hexdigest()
 
 
-@router.post("/verify")
-async def verify(file: UploadFile = File(...)):
+@router.post("/verify-url")
+async def verify_url(req: VerifyUrlRequest):
+    """Verify media already hosted at a public HTTPS URL. The URL is
+    echoed back as `source_url`."""
     settings = Settings()
-    file_bytes = await file.read()
-    validate_upload(file_bytes, file.content_type)
-    result = await run_verifier(file_bytes, file.filename or "upload", settings)
+    if not req.url.startswith("https://"):
+        raise HTTPException(400, "url must start with https://")
+    max_bytes = min(req.max_bytes or MAX_FILE_SIZE, MAX_FILE_SIZE)
+    path, content_hash = await download_url(req.url, max_bytes)
+    try:
+        filename = os.path.basename(urlparse(req.url).path) or "download"
+        result = await run_verifier_file(path, filename, content_hash, settings)
+    finally:
+        os.unlink(path)
+    result["source_url"] = req.url
     return result
+
+
+class VerifyJumbfRequest(BaseModel):
+    jumbf: str          # hex-encoded C2PA manifest store, no container file
+    content_hash: str   # SHA-256 hex of the asset the manifest belongs to
+
+
+@router.post("/verify-jumbf")
+async def verify_jumbf(
+    req: VerifyJumbfRequest,
+    fingerprint_alg: str = Query("sha256"),
+):
+    """Verify a bare manifest store. The asset isn't available, so
+    `content_hash` is reported as given rather than checked."""
+    settings = Settings()
+    fingerprint_alg = fingerprint_alg.lower()
+    if fingerprint_alg not in FINGERPRINT_ALGS:
+        raise HTTPException(400, f"fingerprint_alg must be one of {', '.join(FINGERPRINT_ALGS)}")
+    try:
+        jumbf = bytes.fromhex(req.jumbf)
+        content_hash = bytes.fromhex(req.content_hash)
+    except ValueError:
+        raise HTTPException(400, "jumbf and content_hash must be hex")
+    if len(content_hash) != 32:
+        raise HTTPException(400, "content hash must be 32 bytes")
+    if len(jumbf) > MAX_FILE_SIZE:
+        raise HTTPException(413, f"jumbf too large: {len(jumbf)} bytes (max {MAX_FILE_SIZE})")
+
+    tmp = tempfile.NamedTemporaryFile(suffix=".c2pa", delete=False)
+    try:
+        tmp.write(jumbf)
+        tmp.close()
+        returncode, stdout = await exec_verifier(
+            settings,
+            "--fingerprint-alg", fingerprint_alg,
+            "--jumbf", tmp.name,
+            "--content-hash", content_hash.hex(),
+        )
+    finally:
+        os.unlink(tmp.name)
+
+    if returncode != 0:
+        try:
+            error = json.loads(stdout.decode()).get("error")
+        except ValueError:
+            error = None
+        raise HTTPException(400, error or "verifier failed")
+    return json.loads(stdout.decode())
+
+
+def decode_qr(image_bytes: bytes) -> str | None:
+    """Text of the first QR code in an image, or None if there isn't one."""
+    try:
+        image = Image.open(io.BytesIO(image_bytes))
+        image.load()
+    except (OSError, Image.DecompressionBombError):
+        raise HTTPException(400, "could not read image")
+    result = zxingcpp.read_barcode(image, formats=zxingcpp.BarcodeFormat.QRCode)
+    return result.text if result else None
+
+
+@router.post("/verify-qr")
+async def verify_qr(file: UploadFile = Depends(upload_file)):
+    """Look up the attestation whose content hash is printed as a QR code,
+    e.g. on a print or its packaging."""
+    if file.content_type not in QR_IMAGE_TYPES:
+        raise HTTPException(415, f"unsupported media type: {file.content_type} (JPEG or PNG)")
+    image_bytes = await file.read()
+    validate_upload(image_bytes)
+
+    decoded = await asyncio.to_thread(decode_qr, image_bytes)
+    if decoded is None:
+        raise HTTPException(422, "no QR code found in image")
+
+    match = CONTENT_HASH_RE.search(decoded)
+    content_hash = match.group(0).lower() if match else None
+    attestation = None
+    if content_hash:
+        try:
+            attestation = await lookup(content_hash)
+        except HTTPException as e:
+            if e.status_code != 404:
+                raise
+    return {"qr_decoded": decoded, "content_hash": content_hash, "attestation": attestation}
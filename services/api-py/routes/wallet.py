@@ -74,6 +74,7 @@ async def attest_wallet(req: WalletAttestRequest):
         ix_data,
         pda,
         200_000,
+        priority_fee_strategy=settings.priority_fee_strategy,
     )
 
     # 6. Write to Postgres
@@ -0,0 +1,46 @@
+from urllib.parse import urlparse
+
+from fastapi import APIRouter, Depends, HTTPException
+from pydantic import BaseModel
+
+from auth import require_admin_key
+import db
+
+router = APIRouter(dependencies=[Depends(require_admin_key)])
+
+
+class RegisterWebhookRequest(BaseModel):
+    url: str
+    secret: str | None = None
+
+
+def _public(webhook: dict) -> dict:
+    """Webhook as returned by the API; the secret is never echoed back."""
+    return {
+        "id": webhook["id"],
+        "url": webhook["url"],
+        "signed": bool(webhook.get("secret")),
+        "created_at": webhook["created_at"],
+    }
+
+
+@router.post("")
+async def register_webhook(req: RegisterWebhookRequest):
+    """Register a URL to receive `attestation.created` events."""
+    parsed = urlparse(req.url)
+    if parsed.scheme not in ("http", "https") or not parsed.netloc:
+        raise HTTPException(400, "url must be an http(s) URL")
+    webhook = await db.insert_webhook(url=req.url, secret=req.secret or None)
+    return _public(webhook)
+
+
+@router.get("")
+async def list_webhooks():
+    return [_public(w) for w in await db.list_webhooks()]
+
+
+@router.delete("/{webhook_id}")
+async def delete_webhook(webhook_id: int):
+    if not await db.delete_webhook(webhook_id):
+        raise HTTPException(404, "webhook not found")
+    return {"status": "deleted", "id": webhook_id}
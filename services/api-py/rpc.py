@@ -0,0 +1,114 @@
+"""Solana RPC access with fallback nodes.
+
+Every call tries SOLANA_RPC_URL first, then each SOLANA_RPC_FALLBACK_URLS
+entry in order. Only transport failures (connection errors, timeouts,
+HTTP 5xx) move on to the next URL; a JSON-RPC error comes from a node
+that is up and is raised as-is.
+
+A URL that fails CIRCUIT_FAILURES times in a row is skipped for
+CIRCUIT_RESET_SECS, so requests don't each wait out its timeout while a
+node is down.
+"""
+
+import logging
+import threading
+import time
+from typing import Callable, TypeVar
+
+import httpx
+from solana.exceptions import SolanaRpcException
+from solana.rpc.api import Client as SolanaClient
+
+from config import Settings
+
+log = logging.getLogger(__name__)
+
+CIRCUIT_FAILURES = 3
+CIRCUIT_RESET_SECS = 60
+RPC_TIMEOUT_SECS = 10
+
+# solana-py wraps httpx errors (incl. raise_for_status) in SolanaRpcException
+TRANSPORT_ERRORS = (SolanaRpcException, httpx.HTTPError, OSError)
+
+T = TypeVar("T")
+
+
+class _Endpoint:
+    def __init__(self, url: str):
+        self.url = url
+        self.client = SolanaClient(url, timeout=RPC_TIMEOUT_SECS)
+        self.failures = 0
+        self.skip_until = 0.0
+
+
+class ResilientRpcClient:
+    def __init__(self, urls: list[str]):
+        # dict.fromkeys drops duplicates but keeps the primary first
+        self._endpoints = [_Endpoint(url) for url in dict.fromkeys(urls)]
+        self._lock = threading.Lock()
+
+    @property
+    def urls(self) -> list[str]:
+        return [ep.url for ep in self._endpoints]
+
+    def call(self, fn: Callable[[SolanaClient], T]) -> T:
+        """Run `fn` against the first reachable node, e.g.
+        `rpc.call(lambda c: c.get_account_info(pda))`."""
+        return self._run(lambda ep: fn(ep.client))
+
+    def post(self, payload: dict, timeout: float = RPC_TIMEOUT_SECS) -> dict:
+        """Raw JSON-RPC request, for methods solana-py doesn't wrap."""
+        def send(ep: _Endpoint) -> dict:
+            resp = httpx.post(ep.url, json=payload, timeout=timeout)
+            resp.raise_for_status()
+            return resp.json()
+        return self._run(send)
+
+    def _available(self) -> list[_Endpoint]:
+        now = time.monotonic()
+        with self._lock:
+            available = [ep for ep in self._endpoints if ep.skip_until <= now]
+        # Every circuit open: try them all rather than fail without asking
+        return available or self._endpoints
+
+    def _run(self, fn: Callable[[_Endpoint], T]) -> T:
+        endpoints = self._available()
+        for i, ep in enumerate(endpoints):
+            try:
+                result = fn(ep)
+            except TRANSPORT_ERRORS as e:
+                self._record_failure(ep)
+                if i + 1 == len(endpoints):
+                    raise
+                log.warning("solana rpc %s failed (%s), switching to %s", ep.url, e, endpoints[i + 1].url)
+                continue
+            self._record_success(ep)
+            return result
+        raise AssertionError("unreachable")
+
+    def _record_failure(self, ep: _Endpoint):
+        with self._lock:
+            ep.failures += 1
+            if ep.failures >= CIRCUIT_FAILURES:
+                ep.failures = 0
+                ep.skip_until = time.monotonic() + CIRCUIT_RESET_SECS
+                log.warning("solana rpc %s failed %d times in a row, skipping for %ds",
+                            ep.url, CIRCUIT_FAILURES, CIRCUIT_RESET_SECS)
+
+    def _record_success(self, ep: _Endpoint):
+        with self._lock:
+            ep.failures = 0
+
+
+_clients: dict[str, ResilientRpcClient] = {}
+_clients_lock = threading.Lock()
+
+
+def rpc_client(rpc_url: str) -> ResilientRpcClient:
+    """Shared client for `rpc_url` backed by the configured fallbacks."""
+    with _clients_lock:
+        client = _clients.get(rpc_url)
+        if client is None:
+            fallbacks = [u.strip() for u in Settings().solana_rpc_fallback_urls.split(",") if u.strip()]
+            client = _clients[rpc_url] = ResilientRpcClient([rpc_url, *fallbacks])
+        return client
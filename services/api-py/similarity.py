@@ -1,7 +1,7 @@
-"""TLSH + MobileCLIP2-S0 similarity computation.
+"""TLSH + pHash + MobileCLIP2-S0 similarity computation.
 
 Call init_similarity() once at startup to load the CLIP model.
-Then use compute_tlsh() / compute_clip_embedding() per-file.
+Then use compute_tlsh() / compute_phash() / compute_clip_embedding() per-file.
 
 Supports cross-modal embeddings:
   - Images: encode_image() via PIL
@@ -17,11 +17,14 @@ import subprocess
 import tempfile
 from io import BytesIO
 
+import numpy as np
 import tlsh
 import torch
 import open_clip
 from PIL import Image
 
+import db
+
 log = logging.getLogger(__name__)
 
 _model = None
@@ -29,6 +32,9 @@ _preprocess = None
 _tokenizer = None
 _device = "cpu"
 
+# In-memory (content_hash, phash) index for /api/deduplicate
+_phash_index: list[tuple[bytes, int]] = []
+
 # Content types that should use text extraction → encode_text()
 _TEXT_CONTENT_TYPES = ("application/pdf", "text/")
 
@@ -269,3 +275,69 @@ def tlsh_distance(h1: str, h2: str) -> int:
     Returns 0 for identical, <100 for near-duplicate, >300 for unrelated.
     """
     return tlsh.diff(h1, h2)
+
+
+# ── pHash ─────────────────────────────────────────────────────────
+
+_PHASH_SIZE = 32  # grayscale resize before DCT
+_PHASH_LOW = 8    # low-frequency block kept → 64 bits
+
+
+def _dct_matrix(n: int) -> np.ndarray:
+    k = np.arange(n)
+    m = np.cos(np.pi * (2 * k[None, :] + 1) * k[:, None] / (2 * n))
+    m[0] /= np.sqrt(2)
+    return m * np.sqrt(2 / n)
+
+
+_DCT = _dct_matrix(_PHASH_SIZE)
+
+
+def compute_phash(file_bytes: bytes) -> str | None:
+    """Compute a 64-bit DCT perceptual hash for an image.
+
+    Returns 16 hex chars, or None if the file isn't a decodable image.
+    """
+    try:
+        img = Image.open(BytesIO(file_bytes)).convert("L")
+    except Exception:
+        return None
+    img = img.resize((_PHASH_SIZE, _PHASH_SIZE), Image.Resampling.LANCZOS)
+    pixels = np.asarray(img, dtype=np.float64)
+    low = (_DCT @ pixels @ _DCT.T)[:_PHASH_LOW, :_PHASH_LOW].flatten()
+    # Median excludes the DC term, which dwarfs the rest
+    bits = low > np.median(low[1:])
+    value = 0
+    for b in bits:
+        value = (value << 1) | int(b)
+    return f"{value:016x}"
+
+
+def phash_distance(a: int, b: int) -> int:
+    """Hamming distance between two 64-bit pHashes."""
+    return (a ^ b).bit_count()
+
+
+def add_to_phash_index(content_hash_hex: str, phash_hex: str):
+    _phash_index.append((bytes.fromhex(content_hash_hex), int(phash_hex, 16)))
+
+
+async def load_phash_index():
+    """Populate the in-memory pHash index from the DB. Call once at startup."""
+    rows = await db.get_all_phashes()
+    _phash_index.clear()
+    for content_hash, phash in rows:
+        add_to_phash_index(content_hash, phash)
+    log.info("pHash index loaded (%d entries)", len(_phash_index))
+
+
+def search_phash(phash_hex: str, threshold: int) -> list[tuple[str, int]]:
+    """Return (content_hash_hex, distance) within threshold, nearest first."""
+    query = int(phash_hex, 16)
+    hits = []
+    for content_hash, value in _phash_index:
+        dist = phash_distance(query, value)
+        if dist <= threshold:
+            hits.append((content_hash.hex(), dist))
+    hits.sort(key=lambda h: h[1])
+    return hits
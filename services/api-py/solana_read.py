@@ -1,14 +1,19 @@
 import struct
 
 from solders.pubkey import Pubkey
-from solana.rpc.api import Client as SolanaClient
+import base58
+from solana.rpc.types import MemcmpOpts
 
-from solana_tx import ATTESTATION_SEED, find_pda
+from metrics import RPC_LATENCY
+from rpc import rpc_client
+from solana_tx import ATTESTATION_SEED, account_discriminator, find_pda
 
-# ── Account discriminator ──────────────────────────────────────────
-ATTESTATION_DISC = bytes([152, 125, 183, 86, 36, 146, 121, 73])
+# ── Account discriminators ─────────────────────────────────────────
+ATTESTATION_DISC = account_discriminator("Attestation")
+REVOKED_API_KEY_DISC = account_discriminator("RevokedApiKey")
 
-ZERO_PUBKEY = b"\x00" * 32
+# Attestation.has_c2pa: after the discriminator and content_hash
+HAS_C2PA_OFFSET = 8 + 32
 
 
 def _read_borsh_string(data: bytes, off: int) -> tuple[str, int]:
@@ -45,6 +50,40 @@ def deserialize_attestation(data: bytes) -> dict | None:
         wallet_sig = data[off:off + 64]; off += 64
         verifier_version, off = _read_borsh_string(data, off)
         trust_bundle_hash, off = _read_borsh_string(data, off)
+        # Accounts created before manifest_hash end here unless reallocated
+        manifest_hash = ""
+        if off + 4 <= len(data):
+            manifest_hash, off = _read_borsh_string(data, off)
+        assertions_digest = ""
+        if off + 4 <= len(data):
+            assertions_digest, off = _read_borsh_string(data, off)
+        expiry_timestamp = 0
+        renewal_count = 0
+        if off + 12 <= len(data):
+            expiry_timestamp = struct.unpack_from("<q", data, off)[0]; off += 8
+            renewal_count = struct.unpack_from("<I", data, off)[0]; off += 4
+        revoked = False
+        revocation_reason = ""
+        if off + 5 <= len(data):
+            revoked = bool(data[off]); off += 1
+            revocation_reason, off = _read_borsh_string(data, off)
+        last_updated = 0
+        if off + 8 <= len(data):
+            last_updated = struct.unpack_from("<q", data, off)[0]; off += 8
+        # Co-signers after the first, which is in wallet/wallet_sig
+        wallets = [wallet] if any(wallet) else []
+        wallet_sigs = [wallet_sig] if any(wallet_sig) else []
+        if off + 4 <= len(data):
+            n_wallets = struct.unpack_from("<I", data, off)[0]; off += 4
+            wallets += [data[off + 32 * i:off + 32 * (i + 1)] for i in range(n_wallets)]
+            off += 32 * n_wallets
+        if off + 4 <= len(data):
+            n_sigs = struct.unpack_from("<I", data, off)[0]; off += 4
+            wallet_sigs += [data[off + 64 * i:off + 64 * (i + 1)] for i in range(n_sigs)]
+            off += 64 * n_sigs
+        software_agent_canonical = ""
+        if off + 4 <= len(data):
+            software_agent_canonical, off = _read_borsh_string(data, off)
 
         result = {
             "content_hash": content_hash.hex(),
@@ -60,18 +99,35 @@ def deserialize_attestation(data: bytes) -> dict | None:
             "submitted_by": str(Pubkey.from_bytes(submitted_by)),
             "timestamp": timestamp,
             "proof_type": proof_type,
+            "revoked": revoked,
+            "revocation_reason": revocation_reason or None,
         }
 
         if email_domain:
             result["email_domain"] = email_domain
-        if wallet != ZERO_PUBKEY:
-            result["wallet_pubkey"] = str(Pubkey.from_bytes(wallet))
-        if wallet_sig != b"\x00" * 64:
-            result["wallet_sig"] = wallet_sig.hex()
+        if wallets:
+            result["wallet_pubkeys"] = [str(Pubkey.from_bytes(w)) for w in wallets]
+            # First signer, for clients that expect a single wallet
+            result["wallet_pubkey"] = result["wallet_pubkeys"][0]
+        if wallet_sigs:
+            result["wallet_sigs"] = [sig.hex() for sig in wallet_sigs]
+            result["wallet_sig"] = result["wallet_sigs"][0]
         if verifier_version:
             result["verifier_version"] = verifier_version
         if trust_bundle_hash:
             result["trust_bundle_hash"] = trust_bundle_hash
+        if manifest_hash:
+            result["manifest_hash"] = manifest_hash
+        if assertions_digest:
+            result["assertions_digest"] = assertions_digest
+        if software_agent_canonical:
+            result["software_agent_canonical"] = software_agent_canonical
+        if expiry_timestamp:
+            result["expiry_timestamp"] = expiry_timestamp
+        if renewal_count:
+            result["renewal_count"] = renewal_count
+        if last_updated:
+            result["last_updated"] = last_updated
 
         return result
     except Exception:
@@ -89,8 +145,8 @@ def lookup_attestation(rpc_url: str, program_id_str: str, content_hash_hex: str)
     program_id = Pubkey.from_string(program_id_str)
     pda, _ = find_pda([ATTESTATION_SEED, content_hash_bytes], program_id)
 
-    client = SolanaClient(rpc_url)
-    resp = client.get_account_info(pda)
+    with RPC_LATENCY.labels("get_account").time():
+        resp = rpc_client(rpc_url).call(lambda c: c.get_account_info(pda))
     if resp.value is None:
         return None
 
@@ -98,36 +154,82 @@ def lookup_attestation(rpc_url: str, program_id_str: str, content_hash_hex: str)
     return deserialize_attestation(data)
 
 
-def list_all_attestations(rpc_url: str, program_id_str: str) -> list[dict]:
+def lookup_attestations(rpc_url: str, program_id_str: str, content_hashes_hex: list[str]) -> list[dict | None]:
+    """Batch variant of lookup_attestation using getMultipleAccounts (100 per call)."""
     program_id = Pubkey.from_string(program_id_str)
-    client = SolanaClient(rpc_url)
-    items = []
+    results: list[dict | None] = [None] * len(content_hashes_hex)
+
+    pdas = []
+    indices = []
+    for i, h in enumerate(content_hashes_hex):
+        try:
+            content_hash_bytes = bytes.fromhex(h)
+        except ValueError:
+            continue
+        if len(content_hash_bytes) != 32:
+            continue
+        pda, _ = find_pda([ATTESTATION_SEED, content_hash_bytes], program_id)
+        pdas.append(pda)
+        indices.append(i)
+
+    rpc = rpc_client(rpc_url)
+    for start in range(0, len(pdas), 100):
+        with RPC_LATENCY.labels("get_account").time():
+            resp = rpc.call(lambda c: c.get_multiple_accounts(pdas[start:start + 100]))
+        for i, account in zip(indices[start:start + 100], resp.value):
+            if account is not None:
+                results[i] = deserialize_attestation(account.data)
+
+    return results
+
+
+def fetch_all_attestations(rpc_url: str, program_id_str: str, has_c2pa: bool | None = None) -> list[dict]:
+    """Every Attestation account, fully deserialized, in RPC order. RPC errors propagate."""
+    program_id = Pubkey.from_string(program_id_str)
+
+    # Fixed-position fields filter server-side; strings need deserializing first
+    filters = [MemcmpOpts(offset=0, bytes=base58.b58encode(ATTESTATION_DISC).decode())]
+    if has_c2pa is not None:
+        filters.append(MemcmpOpts(offset=HAS_C2PA_OFFSET, bytes=base58.b58encode(bytes([has_c2pa])).decode()))
+
+    with RPC_LATENCY.labels("get_account").time():
+        resp = rpc_client(rpc_url).call(lambda c: c.get_program_accounts(program_id, filters=filters))
+    accounts = (deserialize_attestation(keyed.account.data) for keyed in resp.value)
+    return [att for att in accounts if att]
+
 
+def list_all_attestations(rpc_url: str, program_id_str: str, has_c2pa: bool | None = None) -> list[dict]:
+    items = []
     try:
-        resp = client.get_program_accounts(program_id)
-        for keyed in resp.value:
-            data = keyed.account.data
-            if len(data) < 8:
-                continue
-
-            att = deserialize_attestation(data)
-            if att:
-                item = {
-                    "content_hash": att["content_hash"],
-                    "proof_type": att["proof_type"],
-                    "timestamp": att["timestamp"],
-                }
-                if att.get("issuer"):
-                    item["issuer"] = att["issuer"]
-                if att.get("trust_list_match"):
-                    item["trust_list_match"] = att["trust_list_match"]
-                if att.get("email_domain"):
-                    item["email_domain"] = att["email_domain"]
-                if att.get("wallet_pubkey"):
-                    item["wallet_pubkey"] = att["wallet_pubkey"]
-                items.append(item)
+        for att in fetch_all_attestations(rpc_url, program_id_str, has_c2pa):
+            item = {
+                "content_hash": att["content_hash"],
+                "proof_type": att["proof_type"],
+                "timestamp": att["timestamp"],
+            }
+            if att.get("issuer"):
+                item["issuer"] = att["issuer"]
+            if att.get("trust_list_match"):
+                item["trust_list_match"] = att["trust_list_match"]
+            if att.get("email_domain"):
+                item["email_domain"] = att["email_domain"]
+            if att.get("wallet_pubkey"):
+                item["wallet_pubkey"] = att["wallet_pubkey"]
+            items.append(item)
     except Exception:
         pass
 
     items.sort(key=lambda x: x["timestamp"], reverse=True)
     return items
+
+
+def list_revoked_api_keys(rpc_url: str, program_id_str: str) -> set[bytes]:
+    """SHA-256 hashes of every API key with a RevokedApiKey account.
+
+    Unlike the lookups above, RPC errors propagate so a failed refresh
+    doesn't look like an empty revocation list.
+    """
+    program_id = Pubkey.from_string(program_id_str)
+    filters = [MemcmpOpts(offset=0, bytes=base58.b58encode(REVOKED_API_KEY_DISC).decode())]
+    resp = rpc_client(rpc_url).call(lambda c: c.get_program_accounts(program_id, filters=filters))
+    return {bytes(keyed.account.data[8:40]) for keyed in resp.value if len(keyed.account.data) >= 40}
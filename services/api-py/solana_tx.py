@@ -1,26 +1,49 @@
+import hashlib
 import json
+import logging
 import struct
+from enum import Enum
 
-from solders.compute_budget import set_compute_unit_limit
+from solders.compute_budget import set_compute_unit_limit, set_compute_unit_price
 from solders.hash import Hash
 from solders.instruction import AccountMeta, Instruction
 from solders.keypair import Keypair
 from solders.message import Message
 from solders.pubkey import Pubkey
 from solders.transaction import Transaction
-from solana.rpc.api import Client as SolanaClient
 from solana.rpc.commitment import Confirmed
 
+from metrics import RPC_LATENCY
+from rpc import rpc_client
+
+log = logging.getLogger(__name__)
+
 SYSTEM_PROGRAM_ID = Pubkey.from_string("11111111111111111111111111111111")
 ED25519_PROGRAM_ID = Pubkey.from_string("Ed25519SigVerify111111111111111111111111111")
 INSTRUCTIONS_SYSVAR_ID = Pubkey.from_string("Sysvar1nstructions1111111111111111111111111")
 
-# ── Instruction discriminators ──────────────────────────────────────
-SUBMIT_ATTESTATION_DISC = bytes([238, 220, 255, 105, 183, 211, 40, 83])
-SUBMIT_PROOF_DISC = bytes([54, 241, 46, 84, 4, 212, 46, 94])
+# ── Discriminators ──────────────────────────────────────────────────
+
+def discriminator_for(instruction: str) -> bytes:
+    """Anchor instruction discriminator: sha256("global:<instruction>")[:8]."""
+    return hashlib.sha256(f"global:{instruction}".encode()).digest()[:8]
+
+
+def account_discriminator(account: str) -> bytes:
+    """Anchor account discriminator: sha256("account:<Account>")[:8]."""
+    return hashlib.sha256(f"account:{account}".encode()).digest()[:8]
+
+
+SUBMIT_ATTESTATION_DISC = discriminator_for("submit_attestation")
+SUBMIT_PROOF_DISC = discriminator_for("submit_proof")
+REVOKE_API_KEY_DISC = discriminator_for("revoke_api_key")
+REVOKE_ATTESTATION_DISC = discriminator_for("revoke_attestation")
 
 # ── PDA seeds ───────────────────────────────────────────────────────
 ATTESTATION_SEED = b"attestation"
+NONCE_SEED = b"nonce"
+REVOKED_SEED = b"revoked"
+EVENT_AUTHORITY_SEED = b"__event_authority"  # Anchor #[event_cpi] signer
 
 
 def borsh_string(s: str) -> bytes:
@@ -32,6 +55,10 @@ def borsh_vec(data: bytes) -> bytes:
     return struct.pack("<I", len(data)) + data
 
 
+def borsh_pubkey_vec(pubkeys: list[bytes]) -> bytes:
+    return struct.pack("<I", len(pubkeys)) + b"".join(pubkeys)
+
+
 def load_keypair(path: str) -> Keypair:
     with open(path) as f:
         secret = json.load(f)
@@ -101,9 +128,12 @@ def encode_attestation_data(
     cert_fingerprint: str,
     email_domain: str = "",
     email_hash: bytes = b"\x00" * 32,
-    wallet: bytes = b"\x00" * 32,
+    wallets: list[bytes] | None = None,
     verifier_version: str = "",
     trust_bundle_hash: str = "",
+    manifest_hash: str = "",
+    assertions_digest: str = "",
+    software_agent_canonical: str = "",
 ) -> bytes:
     data = bytearray(SUBMIT_ATTESTATION_DISC)
     data += content_hash
@@ -115,9 +145,12 @@ def encode_attestation_data(
     ]:
         data += borsh_string(s)
     data += email_hash
-    data += wallet
+    data += borsh_pubkey_vec(wallets or [])
     data += borsh_string(verifier_version)
     data += borsh_string(trust_bundle_hash)
+    data += borsh_string(manifest_hash)
+    data += borsh_string(assertions_digest)
+    data += borsh_string(software_agent_canonical)
     return bytes(data)
 
 
@@ -127,9 +160,12 @@ def encode_proof_data(
     content_hash: bytes,
     email_domain: str = "",
     email_hash: bytes = b"\x00" * 32,
-    wallet: bytes = b"\x00" * 32,
+    wallets: list[bytes] | None = None,
     verifier_version: str = "",
     trust_bundle_hash: str = "",
+    manifest_hash: str = "",
+    assertions_digest: str = "",
+    software_agent_canonical: str = "",
 ) -> bytes:
     data = bytearray(SUBMIT_PROOF_DISC)
     data += borsh_vec(proof_bytes)
@@ -137,12 +173,78 @@ def encode_proof_data(
     data += content_hash
     data += borsh_string(email_domain)
     data += email_hash
-    data += wallet
+    data += borsh_pubkey_vec(wallets or [])
     data += borsh_string(verifier_version)
     data += borsh_string(trust_bundle_hash)
+    data += borsh_string(manifest_hash)
+    data += borsh_string(assertions_digest)
+    data += borsh_string(software_agent_canonical)
     return bytes(data)
 
 
+def encode_revoke_api_key_data(api_key_hash: bytes) -> bytes:
+    return REVOKE_API_KEY_DISC + api_key_hash
+
+
+def encode_revoke_attestation_data(content_hash: bytes, reason: str) -> bytes:
+    return REVOKE_ATTESTATION_DISC + content_hash + borsh_string(reason)
+
+
+# ── Priority fees ───────────────────────────────────────────────────
+
+class PriorityFeeStrategy(Enum):
+    MIN = "min"
+    P50 = "p50"
+    P75 = "p75"
+    MAX = "max"
+    CUSTOM = "custom"
+
+
+_FEE_PERCENTILES = {
+    PriorityFeeStrategy.MIN: 0,
+    PriorityFeeStrategy.P50: 50,
+    PriorityFeeStrategy.P75: 75,
+    PriorityFeeStrategy.MAX: 100,
+}
+
+
+def parse_fee_strategy(value: str) -> tuple[PriorityFeeStrategy, int]:
+    """Parse PRIORITY_FEE_STRATEGY: min|p50|p75|max, or a fixed micro-lamport price."""
+    value = value.strip().lower()
+    if value.isdigit():
+        return PriorityFeeStrategy.CUSTOM, int(value)
+    try:
+        return PriorityFeeStrategy(value), 0
+    except ValueError:
+        raise ValueError(f"invalid priority fee strategy: {value!r}") from None
+
+
+def get_priority_fee(rpc_url: str, pda: Pubkey, strategy: str) -> int:
+    """Compute-unit price (micro-lamports) for the configured strategy.
+
+    Percentile strategies sample getRecentPrioritizationFees for the
+    attestation PDA; an RPC failure falls back to 0 rather than blocking.
+    """
+    kind, custom = parse_fee_strategy(strategy)
+    if kind is PriorityFeeStrategy.CUSTOM:
+        return custom
+
+    try:
+        resp = rpc_client(rpc_url).post({
+            "jsonrpc": "2.0", "id": 1,
+            "method": "getRecentPrioritizationFees",
+            "params": [[str(pda)]],
+        }, timeout=5)
+        fees = sorted(f["prioritizationFee"] for f in resp["result"])
+    except Exception as e:
+        log.warning("getRecentPrioritizationFees failed, using 0: %s", e)
+        return 0
+    if not fees:
+        return 0
+    idx = round(_FEE_PERCENTILES[kind] / 100 * (len(fees) - 1))
+    return fees[idx]
+
+
 # ── Transaction builder ─────────────────────────────────────────────
 
 def build_and_send_tx(
@@ -153,9 +255,17 @@ def build_and_send_tx(
     pda: Pubkey,
     compute_units: int = 200_000,
     extra_ixs: list[Instruction] | None = None,
+    priority_fee_strategy: str = "p50",
+    extra_accounts: list[AccountMeta] | None = None,
 ) -> tuple[str, str]:
-    """Build, sign, and send a Solana transaction. Returns (signature, pda_str)."""
-    client = SolanaClient(rpc_url)
+    """Build, sign, and send a Solana transaction. Returns (signature, pda_str).
+
+    `extra_accounts` are appended after the four accounts every instruction
+    starts with (attestation, payer, system program, instructions sysvar).
+    The event authority and program account that `#[event_cpi]` instructions
+    expect come last; instructions without events ignore them.
+    """
+    rpc = rpc_client(rpc_url)
     payer = load_keypair(keypair_path)
     program_id = Pubkey.from_string(program_id_str)
 
@@ -165,17 +275,26 @@ def build_and_send_tx(
         AccountMeta(SYSTEM_PROGRAM_ID, is_signer=False, is_writable=False),
         AccountMeta(INSTRUCTIONS_SYSVAR_ID, is_signer=False, is_writable=False),
     ]
+    accounts += extra_accounts or []
+    event_authority, _ = find_pda([EVENT_AUTHORITY_SEED], program_id)
+    accounts += [
+        AccountMeta(event_authority, is_signer=False, is_writable=False),
+        AccountMeta(program_id, is_signer=False, is_writable=False),
+    ]
 
     ix = Instruction(program_id, ix_data, accounts)
     compute_ix = set_compute_unit_limit(compute_units)
+    fee = get_priority_fee(rpc_url, pda, priority_fee_strategy)
+    fee_ix = set_compute_unit_price(fee)
+    log.info("priority fee %d micro-lamports/CU (strategy=%s, pda=%s)", fee, priority_fee_strategy, pda)
 
     # Order: compute budget → extra instructions (Ed25519) → program instruction
-    all_ixs = [compute_ix]
+    all_ixs = [compute_ix, fee_ix]
     if extra_ixs:
         all_ixs.extend(extra_ixs)
     all_ixs.append(ix)
 
-    blockhash_resp = client.get_latest_blockhash()
+    blockhash_resp = rpc.call(lambda c: c.get_latest_blockhash())
     blockhash = blockhash_resp.value.blockhash
 
     msg = Message.new_with_blockhash(
@@ -186,9 +305,10 @@ def build_and_send_tx(
     tx = Transaction.new_unsigned(msg)
     tx.sign([payer], blockhash)
 
-    result = client.send_transaction(tx)
+    with RPC_LATENCY.labels("send_tx").time():
+        result = rpc.call(lambda c: c.send_transaction(tx))
     sig = str(result.value)
 
-    client.confirm_transaction(result.value, commitment=Confirmed)
+    rpc.call(lambda c: c.confirm_transaction(result.value, commitment=Confirmed))
 
     return sig, str(pda)
@@ -0,0 +1,64 @@
+"""Shared fixtures. Run from services/api-py:
+
+    pip install -r requirements-dev.txt
+    pytest
+"""
+
+import itertools
+
+import httpx
+import pytest
+from fastapi import FastAPI
+from sqlalchemy.ext.asyncio import async_sessionmaker, create_async_engine
+
+import db
+from models import Attestation, Base
+
+_hashes = itertools.count(1)
+
+
+@pytest.fixture
+async def sqlite_db(tmp_path):
+    """Point the db module at a fresh SQLite file for one test.
+
+    init_db is Postgres-only (pgvector, ALTER ... IF NOT EXISTS), so the
+    tables are created straight from the models.
+    """
+    engine = create_async_engine(f"sqlite+aiosqlite:///{tmp_path / 'r3l.db'}")
+    async with engine.begin() as conn:
+        await conn.run_sync(Base.metadata.create_all)
+    db._engine = engine
+    db._session_factory = async_sessionmaker(engine, expire_on_commit=False)
+    yield
+    await db.close_db()
+
+
+@pytest.fixture
+def add_attestation(sqlite_db):
+    """Insert an attestation row. Unset fields get test defaults; returns
+    the content hash."""
+
+    async def add(**fields) -> str:
+        fields.setdefault("content_hash", f"{next(_hashes):064x}")
+        fields.setdefault("proof_type", "trusted_verifier")
+        fields.setdefault("created_at", 1_700_000_000)
+        async with db.get_session() as session:
+            session.add(Attestation(**fields))
+            await session.commit()
+        return fields["content_hash"]
+
+    return add
+
+
+@pytest.fixture
+async def api(sqlite_db):
+    """Client for the /api/attestations routes, without main.py's startup
+    (Solana, similarity index, storage). Runs the app on the test's event
+    loop, which the SQLite engine is bound to."""
+    from routes import attestation
+
+    app = FastAPI()
+    app.include_router(attestation.router, prefix="/api")
+    transport = httpx.ASGITransport(app=app)
+    async with httpx.AsyncClient(transport=transport, base_url="http://test") as client:
+        yield client
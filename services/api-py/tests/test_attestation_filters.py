@@ -0,0 +1,86 @@
+"""GET /api/attestations submitter, from/to and kind filters, alone and combined."""
+
+import pytest
+
+WALLET = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"
+NODE = "edge-1"
+T0 = 1_700_000_000
+
+
+@pytest.fixture
+async def seeded(add_attestation) -> dict[str, str]:
+    """Attestations spread over submitters, times and kinds, by name."""
+    return {
+        "wallet_c2pa": await add_attestation(wallet_pubkey=WALLET, has_c2pa=True, created_at=T0),
+        "node_email": await add_attestation(submitted_by=NODE, email_domain="example.com", created_at=T0 + 10),
+        "node_c2pa": await add_attestation(submitted_by=NODE, has_c2pa=True, created_at=T0 + 20),
+        "anon": await add_attestation(has_c2pa=False, created_at=T0 + 30),
+    }
+
+
+async def matching(api, seeded: dict[str, str], **params) -> set[str]:
+    """Names of the seeded attestations GET /api/attestations returns."""
+    resp = await api.get("/api/attestations", params=params)
+    assert resp.status_code == 200
+    by_hash = {h: name for name, h in seeded.items()}
+    body = resp.json()
+    assert body["total"] == len(body["items"])
+    return {by_hash[i["content_hash"]] for i in body["items"]}
+
+
+async def test_submitter_matches_wallet(api, seeded):
+    assert await matching(api, seeded, submitter=WALLET) == {"wallet_c2pa"}
+
+
+async def test_submitter_matches_edge_node(api, seeded):
+    assert await matching(api, seeded, submitter=NODE) == {"node_email", "node_c2pa"}
+
+
+async def test_unknown_submitter(api, seeded):
+    assert await matching(api, seeded, submitter="nobody") == set()
+
+
+async def test_from_is_inclusive(api, seeded):
+    assert await matching(api, seeded, **{"from": T0 + 20}) == {"node_c2pa", "anon"}
+
+
+async def test_to_is_inclusive(api, seeded):
+    assert await matching(api, seeded, to=T0 + 10) == {"wallet_c2pa", "node_email"}
+
+
+async def test_from_and_to(api, seeded):
+    assert await matching(api, seeded, **{"from": T0 + 10, "to": T0 + 20}) == {"node_email", "node_c2pa"}
+
+
+@pytest.mark.parametrize(
+    "kind, expected",
+    [
+        ("c2pa", {"wallet_c2pa", "node_c2pa"}),
+        ("identity", {"node_email"}),
+        ("wallet", {"wallet_c2pa"}),
+    ],
+)
+async def test_kind(api, seeded, kind, expected):
+    assert await matching(api, seeded, kind=kind) == expected
+
+
+async def test_unknown_kind_is_rejected(api, seeded):
+    resp = await api.get("/api/attestations", params={"kind": "video"})
+
+    assert resp.status_code == 400
+
+
+async def test_submitter_and_kind(api, seeded):
+    assert await matching(api, seeded, submitter=NODE, kind="c2pa") == {"node_c2pa"}
+
+
+async def test_submitter_and_range(api, seeded):
+    assert await matching(api, seeded, submitter=NODE, to=T0 + 10) == {"node_email"}
+
+
+async def test_all_filters(api, seeded):
+    params = {"submitter": NODE, "kind": "identity", "from": T0, "to": T0 + 15}
+    assert await matching(api, seeded, **params) == {"node_email"}
+
+    params["kind"] = "c2pa"
+    assert await matching(api, seeded, **params) == set()
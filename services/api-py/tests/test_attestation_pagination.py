@@ -0,0 +1,83 @@
+"""GET /api/attestations paging: LIMIT/OFFSET and COUNT(*) run in SQL."""
+
+import db
+from routes.attestation import DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE
+
+
+async def add_many(add_attestation, n: int) -> list[str]:
+    """`n` attestations, one second apart; returned newest first."""
+    hashes = [await add_attestation(created_at=1_700_000_000 + i) for i in range(n)]
+    return hashes[::-1]
+
+
+async def test_pages_split_results_at_limit(add_attestation, api):
+    newest_first = await add_many(add_attestation, 5)
+
+    first = (await api.get("/api/attestations", params={"limit": 2})).json()
+    second = (await api.get("/api/attestations", params={"limit": 2, "page": 2})).json()
+    last = (await api.get("/api/attestations", params={"limit": 2, "page": 3})).json()
+
+    assert [i["content_hash"] for i in first["items"]] == newest_first[0:2]
+    assert [i["content_hash"] for i in second["items"]] == newest_first[2:4]
+    assert [i["content_hash"] for i in last["items"]] == newest_first[4:5]
+    assert first["total"] == 5
+    assert first["pages"] == 3
+
+
+async def test_page_past_end_is_empty(add_attestation, api):
+    await add_many(add_attestation, 3)
+
+    resp = await api.get("/api/attestations", params={"limit": 2, "page": 3})
+
+    assert resp.status_code == 200
+    assert resp.json()["items"] == []
+    assert resp.json()["total"] == 3
+    assert resp.headers["X-Total-Count"] == "3"
+
+
+async def test_exact_multiple_of_limit_has_no_extra_page(add_attestation, api):
+    await add_many(add_attestation, 4)
+
+    body = (await api.get("/api/attestations", params={"limit": 2})).json()
+
+    assert body["pages"] == 2
+
+
+async def test_limit_is_clamped(add_attestation, api):
+    await add_many(add_attestation, MAX_PAGE_SIZE + 1)
+
+    body = (await api.get("/api/attestations", params={"limit": MAX_PAGE_SIZE * 5})).json()
+
+    assert body["limit"] == MAX_PAGE_SIZE
+    assert len(body["items"]) == MAX_PAGE_SIZE
+    assert body["total"] == MAX_PAGE_SIZE + 1
+
+
+async def test_default_limit(add_attestation, api):
+    await add_many(add_attestation, DEFAULT_PAGE_SIZE + 1)
+
+    body = (await api.get("/api/attestations")).json()
+
+    assert body["limit"] == DEFAULT_PAGE_SIZE
+    assert len(body["items"]) == DEFAULT_PAGE_SIZE
+
+
+async def test_limit_below_one_is_rejected(api):
+    assert (await api.get("/api/attestations", params={"limit": 0})).status_code == 422
+
+
+async def test_empty_table(api):
+    resp = await api.get("/api/attestations")
+
+    assert resp.status_code == 200
+    assert resp.json() == {"items": [], "total": 0, "page": 1, "limit": DEFAULT_PAGE_SIZE, "pages": 0}
+    assert resp.headers["X-Total-Count"] == "0"
+
+
+async def test_count_matches_filters_not_page(add_attestation):
+    for i in range(4):
+        await add_attestation(has_c2pa=i % 2 == 0)
+
+    assert await db.count_attestations(has_c2pa=True) == 2
+    assert len(await db.list_attestations(limit=1, has_c2pa=True)) == 1
+    assert await db.list_attestations(limit=10, offset=10) == []
@@ -0,0 +1,92 @@
+"""CompressionMiddleware: JSON /api/ responses over MIN_SIZE are compressed."""
+
+import gzip
+import json
+
+from compression import MIN_SIZE, CompressionMiddleware
+
+SMALL = {"ok": True}
+LARGE = {"items": ["x" * 64] * (MIN_SIZE // 64 + 1)}
+
+
+def json_app(payload: dict, content_type: bytes = b"application/json"):
+    """ASGI app answering every request with `payload`, in two body chunks."""
+    body = json.dumps(payload).encode()
+
+    async def app(scope, receive, send):
+        await send({
+            "type": "http.response.start",
+            "status": 200,
+            "headers": [(b"content-type", content_type), (b"content-length", str(len(body)).encode())],
+        })
+        half = len(body) // 2
+        await send({"type": "http.response.body", "body": body[:half], "more_body": True})
+        await send({"type": "http.response.body", "body": body[half:]})
+
+    return app
+
+
+async def get(app, path: str = "/api/things", accept_encoding: str | None = "gzip") -> tuple[dict, bytes]:
+    """Run one GET through the middleware; returns (headers, body)."""
+    headers = [(b"accept-encoding", accept_encoding.encode())] if accept_encoding is not None else []
+    scope = {"type": "http", "method": "GET", "path": path, "headers": headers}
+    messages = []
+
+    async def receive():
+        return {"type": "http.request", "body": b""}
+
+    async def send(message):
+        messages.append(message)
+
+    await CompressionMiddleware(app)(scope, receive, send)
+    start, *bodies = messages
+    assert start["type"] == "http.response.start"
+    return (
+        {k.decode().lower(): v.decode() for k, v in start["headers"]},
+        b"".join(m.get("body", b"") for m in bodies),
+    )
+
+
+async def test_large_json_is_gzipped():
+    headers, body = await get(json_app(LARGE))
+
+    assert headers["content-encoding"] == "gzip"
+    assert headers["content-length"] == str(len(body))
+    assert headers["vary"] == "Accept-Encoding"
+    assert json.loads(gzip.decompress(body)) == LARGE
+
+
+async def test_small_json_is_not_compressed():
+    headers, body = await get(json_app(SMALL))
+
+    assert len(json.dumps(SMALL)) < MIN_SIZE
+    assert "content-encoding" not in headers
+    assert headers["vary"] == "Accept-Encoding"
+    assert json.loads(body) == SMALL
+
+
+async def test_without_accept_encoding_passes_through():
+    headers, body = await get(json_app(LARGE), accept_encoding=None)
+
+    assert "content-encoding" not in headers
+    assert json.loads(body) == LARGE
+
+
+async def test_non_json_passes_through():
+    headers, body = await get(json_app(LARGE, content_type=b"text/csv"))
+
+    assert "content-encoding" not in headers
+    assert json.loads(body) == LARGE
+
+
+async def test_outside_api_prefix_passes_through():
+    headers, _ = await get(json_app(LARGE), path="/assets/app.json")
+
+    assert "content-encoding" not in headers
+
+
+async def test_prefers_highest_weighted_encoding():
+    headers, body = await get(json_app(LARGE), accept_encoding="br;q=0.5, gzip;q=0.9")
+
+    assert headers["content-encoding"] == "gzip"
+    assert json.loads(gzip.decompress(body)) == LARGE
@@ -0,0 +1,89 @@
+"""POST /api/edge/attest replays a repeated Idempotency-Key without a second transaction."""
+
+import httpx
+import pytest
+from fastapi import FastAPI
+
+import idempotency
+from ratelimit import limit_by_api_key
+from routes import edge
+
+CUSTOMER = {"api_key": "r3l_test", "name": "edge-1", "type": "individual"}
+BODY = {"content_hash": "ab" * 32, "has_c2pa": True, "trust_bundle_hash": "00" * 32}
+
+
+@pytest.fixture
+def sent(monkeypatch) -> list[bytes]:
+    """Instruction data of each Solana transaction sent, in place of the RPC."""
+    txs = []
+
+    def build_and_send_tx(rpc_url, keypair_path, program_id, ix_data, pda, *args, **kwargs):
+        txs.append(ix_data)
+        return f"sig{len(txs)}", str(pda)
+
+    async def insert_attestation(**fields):
+        pass
+
+    monkeypatch.setattr(edge, "build_and_send_tx", build_and_send_tx)
+    monkeypatch.setattr(edge, "lookup_attestation", lambda rpc_url, program_id, content_hash: None)
+    monkeypatch.setattr(edge.db, "insert_attestation", insert_attestation)
+    monkeypatch.setattr(edge, "notify_attestation_created", lambda content_hash, trust_list_match: None)
+    idempotency._store.cache_clear()
+    return txs
+
+
+@pytest.fixture
+async def edge_api():
+    """Client for the /api/edge routes with auth and rate limiting stubbed out."""
+    app = FastAPI()
+    app.include_router(edge.router, prefix="/api/edge")
+    app.dependency_overrides[edge.require_edge_node] = lambda: dict(CUSTOMER)
+    app.dependency_overrides[limit_by_api_key] = lambda: None
+    transport = httpx.ASGITransport(app=app)
+    async with httpx.AsyncClient(transport=transport, base_url="http://test") as client:
+        yield client
+
+
+async def attest(client, key: str | None):
+    headers = {"Idempotency-Key": key} if key else {}
+    return await client.post("/api/edge/attest", json=BODY, headers=headers)
+
+
+async def test_repeated_key_sends_one_transaction(edge_api, sent):
+    first = await attest(edge_api, "retry-1")
+    second = await attest(edge_api, "retry-1")
+
+    assert first.status_code == second.status_code == 200
+    assert len(sent) == 1
+    assert second.json() == first.json()
+    assert first.json()["signature"] == "sig1"
+    assert idempotency.REPLAYED_HEADER not in first.headers
+    assert second.headers[idempotency.REPLAYED_HEADER] == "true"
+
+
+async def test_distinct_keys_are_not_replayed(edge_api, sent):
+    await attest(edge_api, "retry-1")
+    second = await attest(edge_api, "retry-2")
+
+    assert len(sent) == 2
+    assert idempotency.REPLAYED_HEADER not in second.headers
+
+
+async def test_no_key_is_not_replayed(edge_api, sent):
+    await attest(edge_api, None)
+    await attest(edge_api, None)
+
+    assert len(sent) == 2
+
+
+async def test_failed_request_is_not_remembered(edge_api, sent):
+    bad = await edge_api.post(
+        "/api/edge/attest", json={**BODY, "content_hash": "xyz"}, headers={"Idempotency-Key": "retry-1"}
+    )
+    assert bad.status_code == 400
+
+    retried = await attest(edge_api, "retry-1")
+
+    assert retried.status_code == 200
+    assert len(sent) == 1
+    assert idempotency.REPLAYED_HEADER not in retried.headers
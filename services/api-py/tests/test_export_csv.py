@@ -0,0 +1,77 @@
+"""POST /api/attestations/export.csv: header row, rows, and gzip."""
+
+import csv
+import gzip
+import io
+
+import pytest
+
+from routes import attestation
+from routes.attestation import EXPORT_COLUMNS
+
+ACCOUNTS = [
+    {
+        "content_hash": "bb" * 32,
+        "has_c2pa": False,
+        "timestamp": 1_700_000_100,
+        "proof_type": "trusted_verifier",
+        "submitted_by": "edge-1",
+    },
+    {
+        "content_hash": "aa" * 32,
+        "has_c2pa": True,
+        "timestamp": 1_700_000_000,
+        "proof_type": "zk_groth16",
+        "trust_list_match": "official",
+        "issuer": "Adobe",
+    },
+]
+
+
+@pytest.fixture(autouse=True)
+def on_chain(monkeypatch):
+    """Serve ACCOUNTS in place of the RPC scan."""
+    monkeypatch.setattr(attestation, "fetch_all_attestations", lambda rpc_url, program_id: ACCOUNTS)
+
+
+def rows(text: str) -> list[list[str]]:
+    return list(csv.reader(io.StringIO(text)))
+
+
+async def test_header_row_without_gzip(api):
+    resp = await api.post("/api/attestations/export.csv", headers={"Accept-Encoding": "identity"})
+
+    assert resp.status_code == 200
+    assert "Content-Encoding" not in resp.headers
+    assert resp.headers["Content-Type"] == "text/csv; charset=utf-8"
+    assert rows(resp.text)[0] == EXPORT_COLUMNS
+
+
+async def test_header_row_with_gzip(api):
+    async with api.stream(
+        "POST", "/api/attestations/export.csv", headers={"Accept-Encoding": "gzip"}
+    ) as resp:
+        raw = b"".join([chunk async for chunk in resp.aiter_raw()])
+
+    assert resp.headers["Content-Encoding"] == "gzip"
+    assert rows(gzip.decompress(raw).decode())[0] == EXPORT_COLUMNS
+
+
+async def test_rows_oldest_first(api):
+    resp = await api.post("/api/attestations/export.csv", headers={"Accept-Encoding": "identity"})
+
+    header, *body = rows(resp.text)
+    records = [dict(zip(header, row)) for row in body]
+    assert [r["content_hash"] for r in records] == ["aa" * 32, "bb" * 32]
+    assert records[0]["has_c2pa"] == "true"
+    assert records[0]["timestamp_utc"] == "2023-11-14T22:13:20Z"
+    assert records[1]["submitted_by"] == "edge-1"
+    assert records[1]["trust_list_match"] == ""
+
+
+async def test_empty_export_is_just_the_header(api, monkeypatch):
+    monkeypatch.setattr(attestation, "fetch_all_attestations", lambda rpc_url, program_id: [])
+
+    resp = await api.post("/api/attestations/export.csv", headers={"Accept-Encoding": "identity"})
+
+    assert rows(resp.text) == [EXPORT_COLUMNS]
@@ -0,0 +1,72 @@
+"""limit_by_api_key: a tight loop of requests on one key ends in 429 with Retry-After."""
+
+from types import SimpleNamespace
+
+import httpx
+import pytest
+from fastapi import Depends, FastAPI
+
+import ratelimit
+from ratelimit import TokenBucket, limit_by_api_key
+
+RPS = 2.0
+
+
+@pytest.fixture
+async def limited(monkeypatch):
+    """Client for a route behind limit_by_api_key, with a fresh bucket."""
+    bucket = TokenBucket(rate=RPS, burst=RPS)
+    monkeypatch.setattr(ratelimit, "api_key_limiter", lambda: bucket)
+
+    app = FastAPI()
+
+    @app.get("/limited", dependencies=[Depends(limit_by_api_key)])
+    async def limited_route():
+        return {"ok": True}
+
+    transport = httpx.ASGITransport(app=app)
+    async with httpx.AsyncClient(transport=transport, base_url="http://test") as client:
+        yield client
+
+
+async def until_limited(client, api_key: str, max_requests: int = 100) -> tuple[int, httpx.Response]:
+    """Requests sent with `api_key` until one is refused, and the refusal."""
+    for sent in range(1, max_requests + 1):
+        resp = await client.get("/limited", headers={"X-Api-Key": api_key})
+        if resp.status_code != 200:
+            return sent, resp
+    pytest.fail(f"no 429 after {max_requests} requests")
+
+
+async def test_tight_loop_gets_429_with_retry_after(limited):
+    sent, resp = await until_limited(limited, "r3l_a")
+
+    assert resp.status_code == 429
+    assert sent == RPS + 1
+    assert int(resp.headers["Retry-After"]) >= 1
+
+
+async def test_keys_have_separate_buckets(limited):
+    await until_limited(limited, "r3l_a")
+
+    resp = await limited.get("/limited", headers={"X-Api-Key": "r3l_b"})
+
+    assert resp.status_code == 200
+
+
+async def test_requests_without_a_key_are_not_limited(limited):
+    for _ in range(int(RPS) * 5):
+        resp = await limited.get("/limited")
+        assert resp.status_code == 200
+
+
+def test_bucket_refills_over_time(monkeypatch):
+    now = [1000.0]
+    monkeypatch.setattr(ratelimit, "time", SimpleNamespace(monotonic=lambda: now[0]))
+    bucket = TokenBucket(rate=RPS, burst=RPS)
+
+    assert [bucket.acquire("k") for _ in range(int(RPS))] == [0.0] * int(RPS)
+    assert bucket.acquire("k") == pytest.approx(1 / RPS)
+
+    now[0] += 1 / RPS
+    assert bucket.acquire("k") == 0.0
@@ -0,0 +1,53 @@
+"""routes.stats.aggregate over deserialized Attestation accounts."""
+
+from routes.stats import aggregate
+
+
+def account(**fields) -> dict:
+    fields.setdefault("timestamp", 1_700_000_000)
+    fields.setdefault("proof_type", "trusted_verifier")
+    return fields
+
+
+def test_empty():
+    assert aggregate([]) == {
+        "total": 0,
+        "by_trust_level": {"official": 0, "curated": 0, "untrusted": 0},
+        "by_proof_type": {"zk_groth16": 0, "trusted_verifier": 0},
+        "identity": {"email": 0, "wallet": 0},
+        "has_c2pa_percent": 0.0,
+        "last_attested_at": None,
+    }
+
+
+def test_counts():
+    stats = aggregate([
+        account(trust_list_match="official", has_c2pa=True, proof_type="zk_groth16"),
+        account(trust_list_match="curated", has_c2pa=True, email_domain="example.com"),
+        account(trust_list_match="", wallet_pubkeys=["9xQe"], timestamp=1_700_000_500),
+    ])
+
+    assert stats["total"] == 3
+    assert stats["by_trust_level"] == {"official": 1, "curated": 1, "untrusted": 1}
+    assert stats["by_proof_type"] == {"zk_groth16": 1, "trusted_verifier": 2}
+    assert stats["identity"] == {"email": 1, "wallet": 1}
+    assert stats["has_c2pa_percent"] == 66.67
+    assert stats["last_attested_at"] == 1_700_000_500
+
+
+def test_unrecognised_trust_level_is_untrusted():
+    stats = aggregate([account(), account(trust_list_match="unknown")])
+
+    assert stats["by_trust_level"] == {"official": 0, "curated": 0, "untrusted": 2}
+
+
+def test_unlisted_proof_type_gets_its_own_count():
+    stats = aggregate([account(proof_type="sp1_plonk"), account(proof_type=None)])
+
+    assert stats["by_proof_type"] == {"zk_groth16": 0, "trusted_verifier": 0, "sp1_plonk": 1, "": 1}
+
+
+def test_empty_wallet_list_is_not_a_wallet_identity():
+    stats = aggregate([account(wallet_pubkeys=[], email_domain="")])
+
+    assert stats["identity"] == {"email": 0, "wallet": 0}
@@ -0,0 +1,118 @@
+"""POST /api/verify/batch: results in upload order, failures kept per file."""
+
+import asyncio
+import hashlib
+import json
+
+import httpx
+import pytest
+from fastapi import FastAPI, HTTPException
+
+from routes import verify
+
+# Stub verifier behaviour, keyed by file contents
+SLOW = b"slow"
+TIMES_OUT = b"times out"
+GARBAGE = b"garbage"
+REJECTED = b"rejected"
+
+
+@pytest.fixture(autouse=True)
+def verifier(monkeypatch) -> list[str]:
+    """Replace the verifier binary; returns the paths it was run on."""
+    runs = []
+
+    async def exec_verifier(settings, *args):
+        path = args[-1]
+        runs.append(path)
+        with open(path, "rb") as f:
+            data = f.read()
+        if data == SLOW:
+            # finishes after the files uploaded behind it
+            await asyncio.sleep(0.05)
+        if data == TIMES_OUT:
+            raise HTTPException(504, "verifier timed out")
+        if data == GARBAGE:
+            return 0, b"not json"
+        if data == REJECTED:
+            return 1, b""
+        return 0, json.dumps({"path": path, "has_c2pa": True, "title": data.decode()}).encode()
+
+    monkeypatch.setattr(verify, "exec_verifier", exec_verifier)
+    return runs
+
+
+@pytest.fixture
+async def api():
+    app = FastAPI()
+    app.include_router(verify.router, prefix="/api")
+    transport = httpx.ASGITransport(app=app)
+    async with httpx.AsyncClient(transport=transport, base_url="http://test") as client:
+        yield client
+
+
+def upload(name: str, data: bytes, content_type: str = "image/png"):
+    return ("file[]", (name, data, content_type))
+
+
+async def batch(api, *files) -> list[dict]:
+    resp = await api.post("/api/verify/batch", files=list(files))
+    assert resp.status_code == 200
+    return resp.json()
+
+
+async def test_results_in_upload_order(api):
+    results = await batch(api, upload("a.png", SLOW), upload("b.png", b"b"), upload("c.png", b"c"))
+
+    assert [r["title"] for r in results] == ["slow", "b", "c"]
+    assert all(r["has_c2pa"] for r in results)
+
+
+async def test_failing_file_does_not_block_the_others(api, verifier):
+    results = await batch(
+        api,
+        upload("a.png", b"a"),
+        upload("timeout.png", TIMES_OUT),
+        upload("garbage.png", GARBAGE),
+        upload("c.png", b"c"),
+    )
+
+    assert len(verifier) == 4
+    assert [r["path"] for r in results[1:3]] == ["timeout.png", "garbage.png"]
+    assert results[1]["error"] == "verifier timed out"
+    assert results[1]["content_hash"] == hashlib.sha256(TIMES_OUT).hexdigest()
+    assert results[2]["error"] == "verifier returned invalid output"
+    assert [results[0]["title"], results[3]["title"]] == ["a", "c"]
+
+
+async def test_unsupported_type_is_not_run(api, verifier):
+    results = await batch(api, upload("doc.zip", b"zip", "application/zip"), upload("b.png", b"b"))
+
+    assert len(verifier) == 1
+    assert results[0]["has_c2pa"] is False
+    assert results[0]["content_hash"] is None
+    assert results[0]["error"].startswith("unsupported media type")
+    assert results[1]["title"] == "b"
+
+
+async def test_verifier_rejection_is_unsigned_without_error(api):
+    [result] = await batch(api, upload("r.png", REJECTED))
+
+    assert result["has_c2pa"] is False
+    assert result["error"] is None
+    assert result["content_hash"] == hashlib.sha256(REJECTED).hexdigest()
+
+
+async def test_too_many_files(api, verifier):
+    files = [upload(f"{i}.png", b"x") for i in range(verify.MAX_BATCH_FILES + 1)]
+
+    resp = await api.post("/api/verify/batch", files=files)
+
+    assert resp.status_code == 400
+    assert verifier == []
+
+
+async def test_no_files(api):
+    resp = await api.post("/api/verify/batch", files=[("other", ("a.png", b"a", "image/png"))])
+
+    assert resp.status_code == 422
@@ -0,0 +1,54 @@
+"""/verify-url target checks (SSRF)."""
+
+import asyncio
+import socket
+
+import pytest
+from fastapi import HTTPException
+
+from routes.verify import check_public_url
+
+
+@pytest.fixture
+def resolve_to(monkeypatch):
+    """Make every hostname resolve to the given addresses."""
+
+    def set_addrs(*addrs: str):
+        async def fake_getaddrinfo(host, port, **kwargs):
+            return [
+                (socket.AF_INET6 if ":" in a else socket.AF_INET, socket.SOCK_STREAM, 6, "", (a, port))
+                for a in addrs
+            ]
+
+        monkeypatch.setattr(asyncio.get_running_loop(), "getaddrinfo", fake_getaddrinfo)
+
+    return set_addrs
+
+
+async def test_public_https_url_passes(resolve_to):
+    resolve_to("93.184.216.34")
+    await check_public_url("https://example.com/photo.jpg")
+
+
+@pytest.mark.parametrize(
+    "addr",
+    ["127.0.0.1", "10.1.2.3", "192.168.0.10", "169.254.169.254", "::1", "fe80::1", "::ffff:127.0.0.1"],
+)
+async def test_internal_addresses_rejected(resolve_to, addr):
+    resolve_to(addr)
+    with pytest.raises(HTTPException) as e:
+        await check_public_url("https://internal.example/")
+    assert e.value.status_code == 400
+
+
+async def test_any_internal_address_rejects(resolve_to):
+    resolve_to("93.184.216.34", "10.0.0.5")
+    with pytest.raises(HTTPException):
+        await check_public_url("https://mixed.example/")
+
+
+@pytest.mark.parametrize("url", ["http://example.com/a.jpg", "file:///etc/passwd", "https:///nohost"])
+async def test_non_https_rejected(url):
+    with pytest.raises(HTTPException) as e:
+        await check_public_url(url)
+    assert e.value.status_code == 400
@@ -0,0 +1,84 @@
+"""Webhook delivery for attestation events.
+
+Registered webhooks (the `webhooks` table, managed through
+/api/webhooks) receive a JSON POST for every new attestation:
+
+    {"event": "attestation.created", "content_hash": "...",
+     "trust_list_match": "...", "timestamp": 1705320000}
+
+If the webhook has a secret, `X-R3L-Signature: sha256=<hex>` carries the
+HMAC-SHA256 of the raw body under that secret. Delivery runs in the
+background after the attestation response is sent; a failed POST (network
+error or non-2xx) is retried DELIVERY_RETRIES times with exponential
+backoff, then dropped and logged.
+"""
+
+import asyncio
+import hashlib
+import hmac
+import json
+import logging
+import time
+
+import httpx
+
+import db
+
+log = logging.getLogger(__name__)
+
+DELIVERY_TIMEOUT_SECS = 10.0
+DELIVERY_RETRIES = 3
+RETRY_BASE_DELAY_SECS = 1.0  # doubled after each failed attempt
+
+SIGNATURE_HEADER = "X-R3L-Signature"
+
+# Keeps delivery tasks referenced until they finish
+_tasks: set[asyncio.Task] = set()
+
+
+def sign(secret: str, body: bytes) -> str:
+    return "sha256=" + hmac.new(secret.encode(), body, hashlib.sha256).hexdigest()
+
+
+async def _deliver(client: httpx.AsyncClient, webhook: dict, body: bytes):
+    headers = {"Content-Type": "application/json"}
+    if webhook.get("secret"):
+        headers[SIGNATURE_HEADER] = sign(webhook["secret"], body)
+
+    delay = RETRY_BASE_DELAY_SECS
+    for attempt in range(DELIVERY_RETRIES + 1):
+        try:
+            resp = await client.post(webhook["url"], content=body, headers=headers)
+            if resp.is_success:
+                return
+            error = f"HTTP {resp.status_code}"
+        except httpx.HTTPError as e:
+            error = str(e) or type(e).__name__
+        if attempt < DELIVERY_RETRIES:
+            log.info("webhook %s delivery failed (%s), retrying in %.0fs", webhook["id"], error, delay)
+            await asyncio.sleep(delay)
+            delay *= 2
+        else:
+            log.warning("webhook %s delivery failed after %d attempts: %s", webhook["id"], attempt + 1, error)
+
+
+async def _notify(payload: dict):
+    webhooks = await db.list_webhooks()
+    if not webhooks:
+        return
+    body = json.dumps(payload).encode()
+    async with httpx.AsyncClient(timeout=DELIVERY_TIMEOUT_SECS) as client:
+        await asyncio.gather(*(_deliver(client, w, body) for w in webhooks))
+
+
+def notify_attestation_created(content_hash: str, trust_list_match: str | None):
+    """Queue `attestation.created` deliveries to every registered webhook."""
+    payload = {
+        "event": "attestation.created",
+        "content_hash": content_hash,
+        "trust_list_match": trust_list_match or None,
+        "timestamp": int(time.time()),
+    }
+    task = asyncio.create_task(_notify(payload))
+    _tasks.add(task)
+    task.add_done_callback(_tasks.discard)
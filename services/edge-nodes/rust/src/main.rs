@@ -1,16 +1,31 @@
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
-use clap::{Parser, Subcommand};
-use ed25519_dalek::{Signer, SigningKey};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use notify::{EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use prover_shared::ContentHash;
+use rand::Rng;
+use rayon::prelude::*;
 use sha2::{Digest, Sha256};
 
+/// Official C2PA trust list published by the Content Authenticity Initiative
+const OFFICIAL_TRUST_URL: &str = "https://opensource.contentauthenticity.org/trust-list.pem";
+/// Largest page the API serves from /api/attestations
+const LIST_PAGE_SIZE: usize = 200;
+
 /// R3L Edge Node CLI — verify files locally, attest on-chain.
 #[derive(Parser)]
 #[command(name = "r3l-edge", version)]
 struct Cli {
+    /// Print results (and errors) as JSON on stdout
+    #[arg(long, global = true)]
+    json: bool,
     #[command(subcommand)]
     command: Cmd,
 }
@@ -48,6 +63,81 @@ enum Cmd {
         /// Path to trust directory
         #[arg(long, default_value = "data/trust")]
         trust_dir: String,
+        #[command(flatten)]
+        retry: RetryConfig,
+    },
+    /// Verify and attest every matching file in a directory
+    Batch {
+        /// Directory to scan (not recursive)
+        dir: PathBuf,
+        /// Only files with these extensions, e.g. png,jpg
+        #[arg(long, value_delimiter = ',')]
+        extension: Vec<String>,
+        /// Files processed in parallel
+        #[arg(long, default_value_t = 4)]
+        workers: usize,
+        /// Skip files the API already has an attestation for
+        #[arg(long)]
+        skip_existing: bool,
+        /// Verify only; submit nothing
+        #[arg(long)]
+        dry_run: bool,
+        /// Path to Ed25519 keypair JSON
+        #[arg(long, default_value = "edge-keypair.json")]
+        keypair: PathBuf,
+        /// API base URL
+        #[arg(long, env = "R3L_API_URL", default_value = "http://localhost:3001")]
+        api: String,
+        /// API key
+        #[arg(long, env = "R3L_API_KEY")]
+        api_key: String,
+        /// Path to verifier binary
+        #[arg(long, default_value = "verifier")]
+        verifier: String,
+        /// Path to trust directory
+        #[arg(long, default_value = "data/trust")]
+        trust_dir: String,
+    },
+    /// Attest new files as they appear in a directory, until SIGINT/SIGTERM
+    Watch {
+        /// Directory to watch (not recursive)
+        dir: PathBuf,
+        /// Only files with these extensions, e.g. png,jpg
+        #[arg(long, value_delimiter = ',')]
+        extension: Vec<String>,
+        /// Files attested in parallel
+        #[arg(long, default_value_t = 2)]
+        workers: usize,
+        /// Retries per file after a failed attestation, with exponential backoff
+        #[arg(long, default_value_t = 3)]
+        max_retries: u32,
+        /// Seconds between directory scans if native file events are unavailable
+        #[arg(long, default_value_t = 2)]
+        poll_interval: u64,
+        /// Path to Ed25519 keypair JSON
+        #[arg(long, default_value = "edge-keypair.json")]
+        keypair: PathBuf,
+        /// API base URL
+        #[arg(long, env = "R3L_API_URL", default_value = "http://localhost:3001")]
+        api: String,
+        /// API key
+        #[arg(long, env = "R3L_API_KEY")]
+        api_key: String,
+        /// Path to verifier binary
+        #[arg(long, default_value = "verifier")]
+        verifier: String,
+        /// Path to trust directory
+        #[arg(long, default_value = "data/trust")]
+        trust_dir: String,
+    },
+    /// Manage the r3l.toml config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigCmd,
+    },
+    /// Print a shell completion script
+    Completions {
+        shell: clap_complete::Shell,
     },
     /// Hash a file (SHA-256)
     Hash {
@@ -57,19 +147,242 @@ enum Cmd {
     /// Query structured trust verdict
     Query {
         /// Content hash (hex)
-        hash: String,
+        hash: ContentHash,
         /// API base URL
         #[arg(long, env = "R3L_API_URL", default_value = "http://localhost:3001")]
         api: String,
+        /// Seconds a cached response stays fresh
+        #[arg(long, default_value_t = 300)]
+        cache_ttl: i64,
+        /// Ignore the cache and fetch from the API
+        #[arg(long)]
+        force_refresh: bool,
     },
     /// Look up raw attestation data
     Lookup {
         /// Content hash (hex)
-        hash: String,
+        hash: ContentHash,
         /// API base URL
         #[arg(long, env = "R3L_API_URL", default_value = "http://localhost:3001")]
         api: String,
+        /// Seconds a cached response stays fresh
+        #[arg(long, default_value_t = 300)]
+        cache_ttl: i64,
+        /// Ignore the cache and fetch from the API
+        #[arg(long)]
+        force_refresh: bool,
     },
+    /// List attestations as a table
+    List {
+        /// API base URL
+        #[arg(long, env = "R3L_API_URL", default_value = "http://localhost:3001")]
+        api: String,
+        /// Show only the N most recent
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Keep only rows where FIELD equals VALUE, e.g. trust_list_match=official
+        #[arg(long, value_name = "FIELD=VALUE")]
+        filter: Option<String>,
+        /// Output format: table or json
+        #[arg(long, default_value = "table", value_parser = ["table", "json"])]
+        output: String,
+    },
+    /// Inspect or purge the local lookup/query cache
+    Cache {
+        /// Delete all cached entries
+        #[arg(long)]
+        clear: bool,
+    },
+    /// End-to-end self-test: attest a synthetic file and look it up
+    Test {
+        /// API base URL
+        #[arg(long, env = "R3L_API_URL", default_value = "http://localhost:3001")]
+        api: String,
+        /// API key
+        #[arg(long, env = "R3L_API_KEY")]
+        api_key: String,
+        /// Close the test attestation afterwards
+        #[arg(long)]
+        cleanup: bool,
+        /// Path to Ed25519 keypair JSON
+        #[arg(long, default_value = "edge-keypair.json")]
+        keypair: PathBuf,
+        /// Path to verifier binary
+        #[arg(long, default_value = "verifier")]
+        verifier: String,
+        /// Path to trust directory
+        #[arg(long, default_value = "data/trust")]
+        trust_dir: String,
+    },
+    /// Import a Solana CLI keypair (64-byte JSON array) as the node keypair
+    ImportKey {
+        /// Solana CLI keypair file, e.g. ~/.config/solana/id.json
+        solana_keypair: PathBuf,
+        /// Where to write the edge node keypair
+        #[arg(long, default_value = "edge-keypair.json")]
+        output: PathBuf,
+        /// Overwrite an existing keypair at --output
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the node keypair for use in other wallets
+    ExportKey {
+        /// solana (64-byte JSON), phantom (base58) or hex
+        #[arg(long, default_value = "solana", value_parser = ["solana", "phantom", "hex"])]
+        format: String,
+        /// Path to Ed25519 keypair JSON
+        #[arg(long, default_value = "edge-keypair.json")]
+        keypair: PathBuf,
+    },
+    /// Download trust anchor lists into the trust directory
+    SyncTrust {
+        /// Path to trust directory
+        #[arg(long, default_value = "data/trust")]
+        trust_dir: String,
+        /// sha256sum-style manifest of expected list hashes (signature at <url>.sig)
+        #[arg(long)]
+        manifest_url: Option<String>,
+        /// Require a valid Ed25519 signature on the manifest
+        #[arg(long, requires_all = ["manifest_url", "manifest_pubkey"])]
+        verify_sig: bool,
+        /// Base58 Ed25519 pubkey that signs the manifest
+        #[arg(long)]
+        manifest_pubkey: Option<String>,
+        /// Official trust list URL
+        #[arg(long, default_value = OFFICIAL_TRUST_URL)]
+        official_url: String,
+        /// Curated trust list URL (skipped if unset)
+        #[arg(long)]
+        curated_url: Option<String>,
+        /// Re-sync periodically: @hourly, @daily, @weekly, or an interval like 30m / 6h
+        #[arg(long)]
+        schedule: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCmd {
+    /// Write a commented default config to $XDG_CONFIG_HOME/r3l/r3l.toml
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+// ── Config file ──────────────────────────────────────────────────
+
+const CONFIG_FILE: &str = "r3l.toml";
+
+const DEFAULT_CONFIG: &str = r#"# r3l-edge settings. Command-line flags and environment variables
+# (R3L_API_URL, R3L_API_KEY) take precedence over these values.
+
+# R3L API base URL
+api = "http://localhost:3001"
+
+# Ed25519 node keypair (JSON), as written by `r3l-edge register`
+keypair = "edge-keypair.json"
+
+# Verifier binary, a path or a name on $PATH
+verifier = "verifier"
+
+# Trust anchor directory with official/ and curated/ PEMs (see `r3l-edge sync-trust`)
+trust_dir = "data/trust"
+
+# API key printed by `r3l-edge register`
+# api_key = "..."
+"#;
+
+/// Defaults for the flags shared by most subcommands, from r3l.toml.
+#[derive(serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    api: String,
+    keypair: PathBuf,
+    verifier: String,
+    trust_dir: String,
+    api_key: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            api: "http://localhost:3001".into(),
+            keypair: "edge-keypair.json".into(),
+            verifier: "verifier".into(),
+            trust_dir: "data/trust".into(),
+            api_key: None,
+        }
+    }
+}
+
+impl Config {
+    /// Value for the flag with clap id `id`, if the config sets one.
+    fn value_for(&self, id: &str) -> Option<String> {
+        match id {
+            "api" => Some(self.api.clone()),
+            "keypair" => Some(self.keypair.display().to_string()),
+            "verifier" => Some(self.verifier.clone()),
+            "trust_dir" => Some(self.trust_dir.clone()),
+            "api_key" => self.api_key.clone(),
+            _ => None,
+        }
+    }
+}
+
+/// $XDG_CONFIG_HOME/r3l/r3l.toml, falling back to ~/.config per the XDG spec.
+fn xdg_config_path() -> Result<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var("HOME").context("HOME is not set")?).join(".config"),
+    };
+    Ok(base.join("r3l").join(CONFIG_FILE))
+}
+
+/// ./r3l.toml if present, else the XDG config file; None if neither exists.
+fn find_config() -> Option<PathBuf> {
+    let local = PathBuf::from(CONFIG_FILE);
+    if local.is_file() {
+        return Some(local);
+    }
+    xdg_config_path().ok().filter(|p| p.is_file())
+}
+
+fn load_config(path: &Path) -> Result<Config> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading config: {}", path.display()))?;
+    toml::from_str(&text).with_context(|| format!("parsing config: {}", path.display()))
+}
+
+/// Parse the command line with `config` values as the defaults for the
+/// shared flags, so flags and environment variables still override them.
+fn parse_cli(config: &Config) -> Cli {
+    parse_cli_from(config, std::env::args_os())
+}
+
+/// [`parse_cli`] over `args` rather than the process's own.
+fn parse_cli_from(config: &Config, args: impl IntoIterator<Item = std::ffi::OsString>) -> Cli {
+    let command = Cli::command().mut_subcommands(|sub| {
+        sub.mut_args(|arg| match config.value_for(arg.get_id().as_str()) {
+            // clap needs 'static defaults; this runs once per process
+            Some(value) => arg.default_value(&*Box::leak(value.into_boxed_str())).required(false),
+            None => arg,
+        })
+    });
+    let matches = command.get_matches_from(args);
+    Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+}
+
+fn cmd_config_init(force: bool) -> Result<()> {
+    let path = xdg_config_path()?;
+    if path.exists() && !force {
+        bail!("{} already exists (use --force to overwrite)", path.display());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    fs::write(&path, DEFAULT_CONFIG).with_context(|| format!("writing config: {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    Ok(())
 }
 
 // ── Keypair helpers ──────────────────────────────────────────────
@@ -99,6 +412,21 @@ fn generate_keypair(path: &PathBuf) -> Result<SigningKey> {
     Ok(key)
 }
 
+/// Read a 64-byte keypair JSON and check the public half matches the secret.
+fn read_keypair_bytes(path: &PathBuf) -> Result<[u8; 64]> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("reading keypair: {}", path.display()))?;
+    let bytes: Vec<u8> = serde_json::from_str::<Vec<u8>>(&data)
+        .context("parsing keypair JSON (expected an array of 64 numbers)")?;
+    let bytes: [u8; 64] = bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| anyhow::anyhow!("keypair must be 64 bytes, got {}", b.len()))?;
+    if SigningKey::from_keypair_bytes(&bytes).is_err() {
+        bail!("public key (last 32 bytes) does not match the private key (first 32 bytes)");
+    }
+    Ok(bytes)
+}
+
 fn pubkey_b58(key: &SigningKey) -> String {
     bs58::encode(key.verifying_key().as_bytes()).into_string()
 }
@@ -108,45 +436,443 @@ fn sign_b58(key: &SigningKey, msg: &str) -> String {
     bs58::encode(sig.to_bytes()).into_string()
 }
 
+// ── JSON output ──────────────────────────────────────────────────
+
+/// `--json` output of `attest`
+#[derive(serde::Serialize)]
+struct AttestResult {
+    content_hash: String,
+    attestation_pda: String,
+    signature: Option<String>,
+    wallet_pubkey: Option<String>,
+    existing: bool,
+}
+
+/// `--json` output of `register`
+#[derive(serde::Serialize)]
+struct RegisterResult {
+    pubkey: String,
+    name: String,
+    api_key: String,
+}
+
+/// `--json` output of `hash`
+#[derive(serde::Serialize)]
+struct HashResult {
+    content_hash: ContentHash,
+    path: String,
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+/// Stable `code` for a `--json` error: `http_<status>` for API errors,
+/// otherwise the kind of failure.
+fn error_code(err: &anyhow::Error) -> String {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<HttpError>() {
+            return format!("http_{}", e.status.as_u16());
+        }
+        if cause.is::<reqwest::Error>() {
+            return "network".into();
+        }
+        if cause.is::<std::io::Error>() {
+            return "io".into();
+        }
+        if cause.is::<serde_json::Error>() {
+            return "invalid_json".into();
+        }
+    }
+    "error".into()
+}
+
 // ── HTTP helpers ─────────────────────────────────────────────────
 
+/// Non-success response from the API
+#[derive(Debug)]
+struct HttpError {
+    status: reqwest::StatusCode,
+    body: String,
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP {}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Retries for `post_json` / `get_json` after a transport error, 429 or
+/// 5xx. Other 4xx responses fail at once.
+#[derive(clap::Args, Clone, Copy)]
+struct RetryConfig {
+    /// Retries after a network error, 429 or 5xx response
+    #[arg(long = "max-retries", default_value_t = 3)]
+    max_attempts: u32,
+    /// Delay before the first retry, in milliseconds
+    #[arg(long = "retry-delay-ms", default_value_t = 500)]
+    initial_delay_ms: u64,
+    /// Factor the delay grows by after each retry
+    #[arg(long = "retry-multiplier", default_value_t = 2.0)]
+    multiplier: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self { max_attempts: 3, initial_delay_ms: 500, multiplier: 2.0 }
+    }
+}
+
+/// Set from `attest` flags; every other command uses the defaults.
+static RETRY: OnceLock<RetryConfig> = OnceLock::new();
+
+/// Send the request `build` makes, retrying per [`RETRY`] with ±10% jitter
+/// on the delay. `build` runs once per attempt, and an error from it ends
+/// the retries. Returns the body of the first successful response.
+fn send_with_retry(
+    method: &str,
+    url: &str,
+    build: impl Fn() -> Result<reqwest::blocking::RequestBuilder>,
+) -> Result<String> {
+    let retry = RETRY.get().copied().unwrap_or_default();
+    let mut delay_ms = retry.initial_delay_ms as f64;
+    let mut attempt = 0;
+    loop {
+        let err = match build()?.send() {
+            Ok(resp) => {
+                let status = resp.status();
+                let text = resp.text().context("reading response body")?;
+                if status.is_success() {
+                    return Ok(text);
+                }
+                let err = anyhow::Error::new(HttpError { status, body: text });
+                if status != reqwest::StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                    return Err(err);
+                }
+                err
+            }
+            Err(e) => anyhow::Error::new(e).context(format!("HTTP {method} failed")),
+        };
+        if attempt >= retry.max_attempts {
+            return Err(err);
+        }
+        attempt += 1;
+        let delay = Duration::from_millis((delay_ms * rand::thread_rng().gen_range(0.9..=1.1)) as u64);
+        eprintln!("{method} {url}: {err:#}; retry {attempt}/{} in {}ms", retry.max_attempts, delay.as_millis());
+        std::thread::sleep(delay);
+        delay_ms *= retry.multiplier;
+    }
+}
+
 fn post_json(url: &str, body: &serde_json::Value, headers: &[(&str, &str)]) -> Result<serde_json::Value> {
+    post_json_with(url, body, || Ok(headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()))
+}
+
+/// Like [`post_json`], taking headers from `headers` on every attempt, so a
+/// retry can carry ones that are only good once.
+fn post_json_with(
+    url: &str,
+    body: &serde_json::Value,
+    headers: impl Fn() -> Result<Vec<(String, String)>>,
+) -> Result<serde_json::Value> {
+    let client = reqwest::blocking::Client::new();
+    let text = send_with_retry("POST", url, || {
+        let mut req = client.post(url).json(body);
+        for (k, v) in headers()? {
+            req = req.header(k, v);
+        }
+        Ok(req)
+    })?;
+    serde_json::from_str(&text).context("parsing response JSON")
+}
+
+fn delete_json(url: &str, headers: &[(&str, &str)]) -> Result<serde_json::Value> {
     let client = reqwest::blocking::Client::new();
-    let mut req = client.post(url).json(body);
+    let mut req = client.delete(url);
     for (k, v) in headers {
         req = req.header(*k, *v);
     }
-    let resp = req.send().context("HTTP POST failed")?;
+    let resp = req.send().context("HTTP DELETE failed")?;
     let status = resp.status();
     let text = resp.text().context("reading response body")?;
     if !status.is_success() {
-        bail!("HTTP {}: {}", status, text);
+        return Err(HttpError { status, body: text }.into());
     }
     serde_json::from_str(&text).context("parsing response JSON")
 }
 
 fn get_json(url: &str) -> Result<serde_json::Value> {
+    let client = reqwest::blocking::Client::new();
+    let text = send_with_retry("GET", url, || Ok(client.get(url)))?;
+    serde_json::from_str(&text).context("parsing response JSON")
+}
+
+fn get_bytes(url: &str) -> Result<Vec<u8>> {
     let resp = reqwest::blocking::get(url).context("HTTP GET failed")?;
     let status = resp.status();
-    let text = resp.text().context("reading response body")?;
     if !status.is_success() {
-        bail!("HTTP {}: {}", status, text);
+        bail!("HTTP {} from {}", status, url);
+    }
+    Ok(resp.bytes().context("reading response body")?.to_vec())
+}
+
+/// Fetch a one-time challenge and sign it, returning the auth headers
+/// (X-Challenge-Nonce, X-Challenge-Sig, X-Node-Pubkey).
+fn challenge_headers(api: &str, key: &SigningKey) -> Result<[(&'static str, String); 3]> {
+    let resp = get_json(&format!("{api}/api/edge/challenge"))?;
+    let nonce = resp["nonce"].as_str().context("no nonce in challenge response")?;
+    let sig = sign_b58(key, &format!("R3L: challenge {nonce}"));
+    Ok([
+        ("X-Challenge-Nonce", nonce.to_string()),
+        ("X-Challenge-Sig", sig),
+        ("X-Node-Pubkey", pubkey_b58(key)),
+    ])
+}
+
+// ── Response cache ───────────────────────────────────────────────
+
+fn cache_path() -> Result<PathBuf> {
+    let home = std::env::var("HOME").context("HOME is not set")?;
+    Ok(PathBuf::from(home).join(".config/r3l-edge/cache.db"))
+}
+
+fn open_cache() -> Result<rusqlite::Connection> {
+    let path = cache_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    let conn = rusqlite::Connection::open(&path)
+        .with_context(|| format!("opening cache: {}", path.display()))?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS cache (hash TEXT PRIMARY KEY, response JSON NOT NULL, fetched_at INTEGER NOT NULL)",
+        [],
+    )?;
+    Ok(conn)
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// GET `url` through the cache. `key` distinguishes lookup vs query responses
+/// for the same content hash. Returns the response and whether it was cached.
+fn cached_get_json(key: &str, url: &str, ttl: i64, force_refresh: bool) -> Result<(serde_json::Value, bool)> {
+    // A broken cache shouldn't block lookups — fall back to the network
+    let conn = match open_cache() {
+        Ok(c) => Some(c),
+        Err(e) => {
+            eprintln!("Cache unavailable: {e:#}");
+            None
+        }
+    };
+
+    if let (Some(conn), false) = (&conn, force_refresh) {
+        let row: Option<String> = conn
+            .query_row(
+                "SELECT response FROM cache WHERE hash = ?1 AND fetched_at > ?2",
+                rusqlite::params![key, unix_now() - ttl],
+                |r| r.get(0),
+            )
+            .ok();
+        if let Some(text) = row {
+            if let Ok(resp) = serde_json::from_str(&text) {
+                return Ok((resp, true));
+            }
+        }
+    }
+
+    let resp = get_json(url)?;
+    if let Some(conn) = &conn {
+        conn.execute(
+            "INSERT OR REPLACE INTO cache (hash, response, fetched_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![key, resp.to_string(), unix_now()],
+        )?;
+    }
+    Ok((resp, false))
+}
+
+fn print_response(resp: &serde_json::Value, cached: bool) -> Result<()> {
+    let text = serde_json::to_string_pretty(resp)?;
+    if cached {
+        println!("[cached] {text}");
+    } else {
+        println!("{text}");
+    }
+    Ok(())
+}
+
+fn cmd_cache(clear: bool) -> Result<()> {
+    let conn = open_cache()?;
+    if clear {
+        let n = conn.execute("DELETE FROM cache", [])?;
+        println!("Cleared {n} cached entr{}", if n == 1 { "y" } else { "ies" });
+    } else {
+        let n: i64 = conn.query_row("SELECT COUNT(*) FROM cache", [], |r| r.get(0))?;
+        println!("{n} cached entr{} in {}", if n == 1 { "y" } else { "ies" }, cache_path()?.display());
+    }
+    Ok(())
+}
+
+// ── List ─────────────────────────────────────────────────────────
+
+/// Truncate to `max` chars, marking the cut with an ellipsis.
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() > max {
+        format!("{}…", s.chars().take(max).collect::<String>())
+    } else {
+        s.to_string()
     }
-    serde_json::from_str(&text).context("parsing response JSON")
+}
+
+/// Format unix seconds as "YYYY-MM-DD HH:MM" UTC.
+fn format_unix(ts: i64) -> String {
+    // Civil-from-days (Howard Hinnant), valid for the proleptic Gregorian calendar
+    let days = ts.div_euclid(86_400);
+    let secs = ts.rem_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02} {:02}:{:02}", secs / 3600, secs % 3600 / 60)
+}
+
+/// String form of a JSON field for `--filter` comparisons.
+fn field_str(v: &serde_json::Value) -> String {
+    match v {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn cmd_list(api: String, limit: Option<usize>, filter: Option<String>, output: String) -> Result<()> {
+    let mut rows = Vec::new();
+    let mut page = 1;
+    loop {
+        let resp = get_json(&format!(
+            "{api}/api/attestations?page={page}&limit={LIST_PAGE_SIZE}"
+        ))?;
+        let items = resp["items"]
+            .as_array()
+            .context("expected a page of attestations")?;
+        rows.extend(items.iter().cloned());
+        if page >= resp["pages"].as_u64().unwrap_or(0) {
+            break;
+        }
+        page += 1;
+    }
+
+    if let Some(filter) = &filter {
+        let (field, value) = filter
+            .split_once('=')
+            .with_context(|| format!("invalid filter {filter:?} (expected FIELD=VALUE)"))?;
+        rows.retain(|row| field_str(&row[field]) == value);
+    }
+    // The API returns newest first; sort anyway so --limit keeps the most recent
+    rows.sort_by_key(|row| std::cmp::Reverse(row["timestamp"].as_i64().unwrap_or(0)));
+    if let Some(limit) = limit {
+        rows.truncate(limit);
+    }
+
+    if output == "json" {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+
+    let mut table = comfy_table::Table::new();
+    table
+        .load_preset(comfy_table::presets::UTF8_FULL)
+        .set_header(vec!["Hash", "Type", "Trust", "C2PA", "Issuer", "Timestamp"]);
+    for row in &rows {
+        let text = |key: &str| row[key].as_str().unwrap_or("").to_string();
+        table.add_row(vec![
+            truncate(&text("content_hash"), 16),
+            text("proof_type"),
+            text("trust_list_match"),
+            match row["has_c2pa"].as_bool() {
+                Some(true) => "yes".to_string(),
+                Some(false) => "no".to_string(),
+                None => String::new(),
+            },
+            truncate(&text("issuer"), 20),
+            row["timestamp"].as_i64().map(format_unix).unwrap_or_default(),
+        ]);
+    }
+    println!("{table}");
+    println!("{} attestation{}", rows.len(), if rows.len() == 1 { "" } else { "s" });
+    Ok(())
 }
 
 // ── Hash ─────────────────────────────────────────────────────────
 
-fn hash_file(path: &PathBuf) -> Result<String> {
+fn hash_file(path: &Path) -> Result<ContentHash> {
     let data = fs::read(path)
         .with_context(|| format!("reading file: {}", path.display()))?;
-    let hash = Sha256::digest(&data);
-    Ok(hex::encode(hash))
+    Ok(ContentHash::from(<[u8; 32]>::from(Sha256::digest(&data))))
+}
+
+// ── Synthetic test image ─────────────────────────────────────────
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &b in data {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Build a 1x1 white PNG with a random `tEXt` nonce so every run attests a
+/// new content hash. It carries no C2PA manifest (that needs a signing cert),
+/// so it exercises the verify → attest → lookup path as an unsigned file.
+fn synthetic_png() -> Vec<u8> {
+    let nonce: [u8; 16] = rand::random();
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    // IHDR: 1x1, 8-bit greyscale
+    let mut ihdr = Vec::new();
+    ihdr.extend_from_slice(&1u32.to_be_bytes());
+    ihdr.extend_from_slice(&1u32.to_be_bytes());
+    ihdr.extend_from_slice(&[8, 0, 0, 0, 0]);
+    png_chunk(&mut png, b"IHDR", &ihdr);
+
+    let text = format!("Comment\0r3l-edge self-test {}", hex::encode(nonce));
+    png_chunk(&mut png, b"tEXt", text.as_bytes());
+
+    // IDAT: zlib stream with one stored block holding the scanline
+    // [filter=0, pixel=0xFF]; adler32 of those two bytes is 0x01010100.
+    let idat = [0x78, 0x01, 0x01, 0x02, 0x00, 0xFD, 0xFF, 0x00, 0xFF, 0x01, 0x01, 0x01, 0x00];
+    png_chunk(&mut png, b"IDAT", &idat);
+
+    png_chunk(&mut png, b"IEND", &[]);
+    png
 }
 
 // ── Commands ─────────────────────────────────────────────────────
 
-fn cmd_register(name: Option<String>, keypair: PathBuf, api: String) -> Result<()> {
+fn cmd_register(name: Option<String>, keypair: PathBuf, api: String, json: bool) -> Result<()> {
     let key = if keypair.exists() {
         eprintln!("Using existing keypair: {}", keypair.display());
         load_keypair(&keypair)?
@@ -166,6 +892,13 @@ fn cmd_register(name: Option<String>, keypair: PathBuf, api: String) -> Result<(
 
     let resp = post_json(&format!("{api}/api/edge/register"), &body, &[])?;
 
+    if json {
+        return print_json(&RegisterResult {
+            pubkey: resp["pubkey"].as_str().unwrap_or("").to_string(),
+            name: resp["name"].as_str().unwrap_or("").to_string(),
+            api_key: resp["api_key"].as_str().unwrap_or("").to_string(),
+        });
+    }
     println!("\nRegistered successfully!");
     println!("  Pubkey:  {}", resp["pubkey"].as_str().unwrap_or(""));
     println!("  Name:    {}", resp["name"].as_str().unwrap_or(""));
@@ -175,59 +908,75 @@ fn cmd_register(name: Option<String>, keypair: PathBuf, api: String) -> Result<(
     Ok(())
 }
 
-fn cmd_attest(
-    file: PathBuf,
+fn cmd_import_key(solana_keypair: PathBuf, output: PathBuf, force: bool) -> Result<()> {
+    let bytes = read_keypair_bytes(&solana_keypair)?;
+    if output.exists() && !force {
+        bail!("{} already exists (use --force to overwrite)", output.display());
+    }
+    fs::write(&output, serde_json::to_string(&bytes.to_vec())?)
+        .with_context(|| format!("writing keypair: {}", output.display()))?;
+
+    let key = SigningKey::from_bytes(bytes[..32].try_into()?);
+    println!("Imported {} -> {}", solana_keypair.display(), output.display());
+    println!("  Pubkey: {}", pubkey_b58(&key));
+    Ok(())
+}
+
+fn cmd_export_key(format: String, keypair: PathBuf) -> Result<()> {
+    let bytes = read_keypair_bytes(&keypair)?;
+    match format.as_str() {
+        "solana" => println!("{}", serde_json::to_string(&bytes.to_vec())?),
+        "phantom" => println!("{}", bs58::encode(bytes).into_string()),
+        _ => println!("{}", hex::encode(bytes)),
+    }
+    Ok(())
+}
+
+/// Where and how this node verifies and submits attestations.
+struct EdgeNode {
     keypair: PathBuf,
     api: String,
     api_key: String,
     verifier: String,
     trust_dir: String,
-) -> Result<()> {
+}
+
+/// Run the verifier on `file` and parse its JSON output.
+fn verify_file(file: &Path, node: &EdgeNode) -> Result<serde_json::Value> {
     if !file.exists() {
         bail!("File not found: {}", file.display());
     }
 
-    // 1. Run verifier
-    eprintln!("Verifying: {}", file.display());
-    let mut cmd = Command::new(&verifier);
-    if !trust_dir.is_empty() && std::path::Path::new(&trust_dir).is_dir() {
-        cmd.arg("--trust-dir").arg(&trust_dir);
+    let mut cmd = Command::new(&node.verifier);
+    if !node.trust_dir.is_empty() && Path::new(&node.trust_dir).is_dir() {
+        cmd.env("TRUST_DIR", &node.trust_dir);
     }
-    cmd.arg(&file);
+    cmd.arg(file);
 
-    let output = cmd.output().with_context(|| format!("running verifier: {verifier}"))?;
+    let output = cmd.output().with_context(|| format!("running verifier: {}", node.verifier))?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         bail!("Verifier failed: {stderr}");
     }
+    serde_json::from_slice(&output.stdout).context("parsing verifier JSON output")
+}
 
-    let verify_output: serde_json::Value = serde_json::from_slice(&output.stdout)
-        .context("parsing verifier JSON output")?;
-
-    let content_hash = verify_output["content_hash"]
-        .as_str()
-        .context("no content_hash in verifier output")?;
-
-    eprintln!("Content hash: {content_hash}");
-    eprintln!("C2PA: {}", verify_output["has_c2pa"].as_bool().unwrap_or(false));
-
-    // 1b. Compute TLSH hash for similarity search
-    let file_bytes = fs::read(&file)
+/// TLSH digest of a file for similarity search; empty if the file is too
+/// small or uniform to hash.
+fn tlsh_file(file: &Path) -> Result<String> {
+    let file_bytes = fs::read(file)
         .with_context(|| format!("reading file for TLSH: {}", file.display()))?;
-    let tlsh_hash = {
-        let mut builder = tlsh2::TlshDefaultBuilder::new();
-        builder.update(&file_bytes);
-        builder.build()
-            .map(|h| h.hash().to_string())
-            .unwrap_or_default()
-    };
-    if !tlsh_hash.is_empty() {
-        eprintln!("TLSH: {tlsh_hash}");
-    }
+    let mut builder = tlsh2::TlshDefaultBuilder::new();
+    builder.update(&file_bytes);
+    Ok(builder.build()
+        .map(|h| h.hash().to_string())
+        .unwrap_or_default())
+}
 
-    // 2. Build attestation body
+/// `/api/edge/attest` body for a verified file.
+fn attest_body(verify_output: &serde_json::Value, tlsh_hash: String) -> serde_json::Value {
     let mut body = serde_json::json!({
-        "content_hash": content_hash,
+        "content_hash": verify_output["content_hash"].as_str().unwrap_or(""),
         "has_c2pa": verify_output["has_c2pa"].as_bool().unwrap_or(false),
         "trust_list_match": verify_output["trust_list_match"].as_str().unwrap_or(""),
         "validation_state": verify_output["validation_state"].as_str().unwrap_or(""),
@@ -235,32 +984,79 @@ fn cmd_attest(
         "issuer": verify_output["issuer"].as_str().unwrap_or(""),
         "common_name": verify_output["common_name"].as_str().unwrap_or(""),
         "software_agent": verify_output["software_agent"].as_str().unwrap_or(""),
+        "software_agent_canonical": verify_output["software_agent_canonical"].as_str().unwrap_or(""),
         "signing_time": verify_output["signing_time"].as_str().unwrap_or(""),
+        "cert_fingerprint": verify_output["cert_fingerprint"].as_str().unwrap_or(""),
+        "manifest_hash": verify_output["manifest_hash"].as_str().unwrap_or(""),
+        "assertions_digest": verify_output["assertions_digest"].as_str().unwrap_or(""),
+        "trust_bundle_hash": verify_output["trust_bundle_hash"].as_str().unwrap_or(""),
     });
-
-    // 2b. Add TLSH hash if computed
     if !tlsh_hash.is_empty() {
         body["tlsh_hash"] = serde_json::Value::String(tlsh_hash);
     }
+    body
+}
 
-    // 3. Sign wallet message if keypair exists
-    if keypair.exists() {
-        if let Ok(key) = load_keypair(&keypair) {
-            let msg = format!("R3L: attest {content_hash}");
-            let wallet_sig = sign_b58(&key, &msg);
-            body["wallet_signature"] = serde_json::Value::String(wallet_sig);
-            eprintln!("Wallet signature: included");
+/// Sign (if the node has a keypair) and POST an attestation body. Retries
+/// reuse one `Idempotency-Key`, so the API replays a response it already
+/// sent instead of attesting twice. Challenges are single-use, so each
+/// attempt fetches and signs a fresh one.
+fn submit_attestation(node: &EdgeNode, mut body: serde_json::Value) -> Result<serde_json::Value> {
+    let content_hash = body["content_hash"].as_str().unwrap_or("").to_string();
+    let idempotency_key = hex::encode(rand::random::<[u8; 16]>());
+    let key = node.keypair.exists().then(|| load_keypair(&node.keypair).ok()).flatten();
+    if let Some(key) = &key {
+        let msg = format!("R3L: attest {content_hash}");
+        body["wallet_signature"] = serde_json::Value::String(sign_b58(key, &msg));
+    }
+    post_json_with(&format!("{}/api/edge/attest", node.api), &body, || {
+        let mut headers = vec![
+            ("X-API-Key".to_string(), node.api_key.clone()),
+            ("Idempotency-Key".to_string(), idempotency_key.clone()),
+        ];
+        if let Some(key) = &key {
+            headers.extend(challenge_headers(&node.api, key)?.map(|(k, v)| (k.to_string(), v)));
         }
+        Ok(headers)
+    })
+}
+
+fn cmd_attest(file: &Path, node: &EdgeNode, json: bool) -> Result<()> {
+    // 1. Run verifier
+    eprintln!("Verifying: {}", file.display());
+    let verify_output = verify_file(file, node)?;
+    let content_hash = verify_output["content_hash"]
+        .as_str()
+        .context("no content_hash in verifier output")?;
+
+    eprintln!("Content hash: {content_hash}");
+    eprintln!("C2PA: {}", verify_output["has_c2pa"].as_bool().unwrap_or(false));
+
+    // 1b. Compute TLSH hash for similarity search
+    let tlsh_hash = tlsh_file(file)?;
+    if !tlsh_hash.is_empty() {
+        eprintln!("TLSH: {tlsh_hash}");
+    }
+
+    // 2. Build attestation body
+    let body = attest_body(&verify_output, tlsh_hash);
+    if node.keypair.exists() {
+        eprintln!("Wallet signature: included");
     }
 
-    // 4. Submit
+    // 3. Sign and submit
     eprintln!("Submitting attestation...");
-    let resp = post_json(
-        &format!("{api}/api/edge/attest"),
-        &body,
-        &[("X-API-Key", &api_key)],
-    )?;
+    let resp = submit_attestation(node, body)?;
 
+    if json {
+        return print_json(&AttestResult {
+            content_hash: resp["content_hash"].as_str().unwrap_or("").to_string(),
+            attestation_pda: resp["attestation_pda"].as_str().unwrap_or("").to_string(),
+            signature: resp["signature"].as_str().map(str::to_string),
+            wallet_pubkey: resp["wallet_pubkey"].as_str().map(str::to_string),
+            existing: resp["existing"].as_bool().unwrap_or(false),
+        });
+    }
     if resp.get("existing").and_then(|v| v.as_bool()).unwrap_or(false) {
         println!("\nAttestation already exists:");
     } else {
@@ -278,28 +1074,696 @@ fn cmd_attest(
     Ok(())
 }
 
+/// Whether the API already has an attestation for `hash`.
+fn attestation_exists(api: &str, hash: &ContentHash) -> Result<bool> {
+    let resp = reqwest::blocking::get(format!("{api}/api/attestation/{hash}")).context("HTTP GET failed")?;
+    match resp.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(false),
+        s if s.is_success() => Ok(true),
+        s => bail!("HTTP {s} checking for an existing attestation"),
+    }
+}
+
+/// What happened to one file in a batch.
+enum BatchOutcome {
+    Attested(String),
+    Verified(String),
+    Skipped(String),
+}
+
+/// Files directly in `dir` (not subdirectories), sorted, optionally kept to
+/// the given extensions.
+fn batch_files(dir: &Path, extensions: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("reading directory: {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && has_extension(p, extensions))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// `path` has one of `extensions` (any case), or `extensions` is empty.
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    extensions.is_empty()
+        || path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| extensions.iter().any(|want| want.eq_ignore_ascii_case(e)))
+}
+
+fn attest_one(file: &Path, node: &EdgeNode, skip_existing: bool, dry_run: bool) -> Result<BatchOutcome> {
+    if skip_existing {
+        let hash = hash_file(file)?;
+        if attestation_exists(&node.api, &hash)? {
+            return Ok(BatchOutcome::Skipped(hash.to_string()));
+        }
+    }
+    let verify_output = verify_file(file, node)?;
+    let content_hash = verify_output["content_hash"]
+        .as_str()
+        .context("no content_hash in verifier output")?
+        .to_string();
+    if dry_run {
+        return Ok(BatchOutcome::Verified(content_hash));
+    }
+    let resp = submit_attestation(node, attest_body(&verify_output, tlsh_file(file)?))?;
+    Ok(BatchOutcome::Attested(resp["signature"].as_str().unwrap_or(&content_hash).to_string()))
+}
+
+fn cmd_batch(
+    dir: &Path,
+    extensions: &[String],
+    workers: usize,
+    skip_existing: bool,
+    dry_run: bool,
+    node: &EdgeNode,
+) -> Result<()> {
+    let start = Instant::now();
+    let files = batch_files(dir, extensions)?;
+    eprintln!("Batch: {} file(s) in {}, {workers} worker(s)", files.len(), dir.display());
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers.max(1))
+        .build()
+        .context("starting worker pool")?;
+    let outcomes: Vec<_> = pool.install(|| {
+        files
+            .par_iter()
+            .map(|file| {
+                let outcome = attest_one(file, node, skip_existing, dry_run);
+                match &outcome {
+                    Ok(BatchOutcome::Attested(sig)) => println!("attested  {}  {sig}", file.display()),
+                    Ok(BatchOutcome::Verified(hash)) => println!("verified  {}  {hash}", file.display()),
+                    Ok(BatchOutcome::Skipped(hash)) => println!("skipped   {}  {hash}", file.display()),
+                    Err(e) => println!("failed    {}  {e:#}", file.display()),
+                }
+                outcome
+            })
+            .collect()
+    });
+
+    let skipped = outcomes.iter().filter(|o| matches!(o, Ok(BatchOutcome::Skipped(_)))).count();
+    let failed = outcomes.iter().filter(|o| o.is_err()).count();
+    let processed = outcomes.len() - skipped - failed;
+
+    let mut table = comfy_table::Table::new();
+    table.load_preset(comfy_table::presets::UTF8_FULL);
+    table.add_row(vec![if dry_run { "Verified" } else { "Attested" }.to_string(), processed.to_string()]);
+    table.add_row(vec!["Skipped (already attested)".to_string(), skipped.to_string()]);
+    table.add_row(vec!["Failed".to_string(), failed.to_string()]);
+    table.add_row(vec!["Total time".to_string(), format!("{:.1}s", start.elapsed().as_secs_f64())]);
+    println!("{table}");
+
+    if failed > 0 {
+        bail!("{failed} of {} file(s) failed", outcomes.len());
+    }
+    Ok(())
+}
+
+/// Wait until `file` stops growing, so a file still being written isn't
+/// attested half-done.
+fn wait_until_settled(file: &Path) {
+    let mut last = None;
+    for _ in 0..120 {
+        let size = fs::metadata(file).map(|m| m.len()).ok();
+        if size.is_some() && size == last {
+            return;
+        }
+        last = size;
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+/// Verify and submit one watched file: (content hash, trust list match,
+/// tx signature).
+fn watch_attest(file: &Path, node: &EdgeNode) -> Result<(String, String, String)> {
+    let verify_output = verify_file(file, node)?;
+    let resp = submit_attestation(node, attest_body(&verify_output, tlsh_file(file)?))?;
+    Ok((
+        verify_output["content_hash"].as_str().unwrap_or("").to_string(),
+        verify_output["trust_list_match"].as_str().unwrap_or("").to_string(),
+        resp["signature"].as_str().unwrap_or("").to_string(),
+    ))
+}
+
+/// Attest `file`, retrying failures up to `max_retries` times (1s, 2s, 4s, ...
+/// apart), and print one JSON log line with the result.
+fn watch_file(file: &Path, node: &EdgeNode, max_retries: u32) {
+    wait_until_settled(file);
+    let mut attempt = 0;
+    let result = loop {
+        match watch_attest(file, node) {
+            Err(e) if attempt < max_retries => {
+                let delay = Duration::from_secs(1 << attempt.min(6));
+                eprintln!("{}: {e:#}; retrying in {}s", file.display(), delay.as_secs());
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            result => break result,
+        }
+    };
+    let line = match result {
+        Ok((content_hash, trust_list_match, signature)) => serde_json::json!({
+            "file": file.display().to_string(),
+            "content_hash": content_hash,
+            "trust_list_match": trust_list_match,
+            "signature": signature,
+        }),
+        Err(e) => serde_json::json!({
+            "file": file.display().to_string(),
+            "error": format!("{e:#}"),
+            "attempts": attempt + 1,
+        }),
+    };
+    println!("{line}");
+}
+
+fn cmd_watch(
+    dir: &Path,
+    extensions: &[String],
+    workers: usize,
+    max_retries: u32,
+    poll_interval: u64,
+    node: &EdgeNode,
+) -> Result<()> {
+    if !dir.is_dir() {
+        bail!("not a directory: {}", dir.display());
+    }
+    let (tx, rx) = async_channel::unbounded::<PathBuf>();
+
+    // Queue each new matching file once; renames count, since many tools
+    // write a temp file and move it into place
+    let seen = Arc::new(Mutex::new(HashSet::new()));
+    let queue = tx.clone();
+    let extensions_owned = extensions.to_vec();
+    let handler = move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(notify::event::ModifyKind::Name(_))) {
+            return;
+        }
+        for path in event.paths {
+            if path.is_file() && has_extension(&path, &extensions_owned) && seen.lock().unwrap().insert(path.clone()) {
+                let _ = queue.send_blocking(path);
+            }
+        }
+    };
+
+    let mut watcher: Box<dyn Watcher> = match RecommendedWatcher::new(handler.clone(), notify::Config::default()) {
+        Ok(w) => Box::new(w),
+        Err(e) => {
+            eprintln!("native file events unavailable ({e}); polling every {poll_interval}s");
+            let config = notify::Config::default().with_poll_interval(Duration::from_secs(poll_interval));
+            Box::new(PollWatcher::new(handler, config).context("starting polling watcher")?)
+        }
+    };
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("watching {}", dir.display()))?;
+
+    // Closing the channel stops new work; queued and in-flight files finish
+    ctrlc::set_handler(move || {
+        eprintln!("shutting down after in-flight attestations...");
+        tx.close();
+    })
+    .context("installing signal handler")?;
+
+    eprintln!("Watching {} with {} worker(s)", dir.display(), workers.max(1));
+    std::thread::scope(|scope| {
+        for _ in 0..workers.max(1) {
+            let rx = rx.clone();
+            scope.spawn(move || {
+                while let Ok(file) = rx.recv_blocking() {
+                    watch_file(&file, node, max_retries);
+                }
+            });
+        }
+    });
+    drop(watcher);
+    Ok(())
+}
+
+/// Run one self-test step, printing its outcome and duration.
+fn run_step<T>(name: &str, f: impl FnOnce() -> Result<T>) -> Option<T> {
+    let start = Instant::now();
+    let result = f();
+    let ms = start.elapsed().as_millis();
+    match result {
+        Ok(v) => {
+            println!("  [PASS] {name} ({ms} ms)");
+            Some(v)
+        }
+        Err(e) => {
+            println!("  [FAIL] {name} ({ms} ms): {e:#}");
+            None
+        }
+    }
+}
+
+fn cmd_test(
+    api: String,
+    api_key: String,
+    cleanup: bool,
+    keypair: PathBuf,
+    verifier: String,
+    trust_dir: String,
+) -> Result<()> {
+    let start = Instant::now();
+    let file = std::env::temp_dir().join(format!("r3l-selftest-{}.png", std::process::id()));
+
+    println!("Self-test against {api}");
+    let mut ok = true;
+
+    let hash = run_step("generate synthetic PNG", || {
+        fs::write(&file, synthetic_png())
+            .with_context(|| format!("writing {}", file.display()))?;
+        hash_file(&file)
+    });
+
+    if let Some(hash) = hash {
+        ok &= run_step("attest", || {
+            let node = EdgeNode { keypair, api: api.clone(), api_key: api_key.clone(), verifier, trust_dir };
+            cmd_attest(&file, &node, false)
+        })
+        .is_some();
+
+        ok &= run_step("lookup on-chain attestation", || {
+            let resp = get_json(&format!("{api}/api/attestation/{hash}"))?;
+            if resp["content_hash"].as_str().and_then(|h| h.parse().ok()) != Some(hash) {
+                bail!("lookup returned a different content hash");
+            }
+            Ok(())
+        })
+        .is_some();
+
+        if cleanup {
+            ok &= run_step("close attestation", || {
+                delete_json(&format!("{api}/api/attestation/{hash}"), &[("X-API-Key", &api_key)])
+            })
+            .is_some();
+        }
+    } else {
+        ok = false;
+    }
+
+    let _ = fs::remove_file(&file);
+
+    println!(
+        "\nSelf-test {} in {:.1}s",
+        if ok { "passed" } else { "FAILED" },
+        start.elapsed().as_secs_f64()
+    );
+    if !ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+// ── Trust sync ───────────────────────────────────────────────────
+
+struct TrustList<'a> {
+    subdir: &'a str,
+    file_name: &'a str,
+    url: &'a str,
+}
+
+/// Parse a `--schedule` value into a sync interval.
+fn parse_schedule(s: &str) -> Result<Duration> {
+    let secs = match s {
+        "@hourly" => 3600,
+        "@daily" => 86_400,
+        "@weekly" => 7 * 86_400,
+        _ => {
+            let (num, unit) = s.split_at(s.len().saturating_sub(1));
+            let n: u64 = num.parse().with_context(|| format!("invalid schedule: {s}"))?;
+            match unit {
+                "s" => n,
+                "m" => n * 60,
+                "h" => n * 3600,
+                "d" => n * 86_400,
+                _ => bail!("invalid schedule unit in {s} (expected s, m, h or d)"),
+            }
+        }
+    };
+    if secs == 0 {
+        bail!("schedule interval must be non-zero");
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Fetch the hash manifest (`<sha256>  <file_name>` per line), checking its
+/// signature at `<url>.sig` when a pubkey is given.
+fn fetch_trust_manifest(url: &str, pubkey: Option<&str>) -> Result<Vec<(String, String)>> {
+    let body = get_bytes(url)?;
+
+    if let Some(pubkey) = pubkey {
+        let pk: [u8; 32] = bs58::decode(pubkey)
+            .into_vec()
+            .context("decoding manifest pubkey")?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("manifest pubkey must be 32 bytes"))?;
+        let sig_b58 = String::from_utf8(get_bytes(&format!("{url}.sig"))?)
+            .context("manifest signature is not UTF-8")?;
+        let sig: [u8; 64] = bs58::decode(sig_b58.trim())
+            .into_vec()
+            .context("decoding manifest signature")?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("manifest signature must be 64 bytes"))?;
+        VerifyingKey::from_bytes(&pk)
+            .context("invalid manifest pubkey")?
+            .verify_strict(&body, &Signature::from_bytes(&sig))
+            .context("manifest signature verification failed")?;
+    }
+
+    let text = String::from_utf8(body).context("manifest is not UTF-8")?;
+    Ok(text
+        .lines()
+        .filter_map(|line| {
+            let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+            Some((name.trim().to_string(), hash.to_ascii_lowercase()))
+        })
+        .collect())
+}
+
+/// Download one list, check it against the manifest, and swap it in atomically.
+fn sync_trust_list(trust_dir: &str, list: &TrustList, manifest: Option<&[(String, String)]>) -> Result<String> {
+    let pem = get_bytes(list.url)?;
+    if !pem.windows(27).any(|w| w == b"-----BEGIN CERTIFICATE-----") {
+        bail!("{} contains no PEM certificates", list.url);
+    }
+    let hash = hex::encode(Sha256::digest(&pem));
+
+    if let Some(manifest) = manifest {
+        let expected = manifest
+            .iter()
+            .find(|(name, _)| name == list.file_name)
+            .map(|(_, h)| h)
+            .with_context(|| format!("{} not listed in manifest", list.file_name))?;
+        if *expected != hash {
+            bail!("{} hash mismatch: manifest {expected}, downloaded {hash}", list.file_name);
+        }
+    }
+
+    let dir = PathBuf::from(trust_dir).join(list.subdir);
+    fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    let dest = dir.join(list.file_name);
+    let tmp = dir.join(format!(".{}.tmp", list.file_name));
+    fs::write(&tmp, &pem).with_context(|| format!("writing {}", tmp.display()))?;
+    fs::rename(&tmp, &dest).with_context(|| format!("replacing {}", dest.display()))?;
+    Ok(hash)
+}
+
+fn sync_trust_once(
+    trust_dir: &str,
+    manifest_url: Option<&str>,
+    manifest_pubkey: Option<&str>,
+    lists: &[TrustList],
+) -> Result<()> {
+    let manifest = manifest_url
+        .map(|url| fetch_trust_manifest(url, manifest_pubkey))
+        .transpose()?;
+
+    let mut failed = 0;
+    for list in lists {
+        match sync_trust_list(trust_dir, list, manifest.as_deref()) {
+            Ok(hash) => println!("  {}/{}: {hash}", list.subdir, list.file_name),
+            Err(e) => {
+                println!("  {}/{}: FAILED: {e:#}", list.subdir, list.file_name);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{failed} trust list(s) failed to sync");
+    }
+    Ok(())
+}
+
+fn cmd_sync_trust(
+    trust_dir: String,
+    manifest_url: Option<String>,
+    verify_sig: bool,
+    manifest_pubkey: Option<String>,
+    official_url: String,
+    curated_url: Option<String>,
+    schedule: Option<String>,
+) -> Result<()> {
+    let interval = schedule.as_deref().map(parse_schedule).transpose()?;
+    let mut lists = vec![TrustList {
+        subdir: "official",
+        file_name: "cai-official.pem",
+        url: &official_url,
+    }];
+    if let Some(url) = &curated_url {
+        lists.push(TrustList {
+            subdir: "curated",
+            file_name: "cai-curated.pem",
+            url,
+        });
+    }
+    let pubkey = if verify_sig { manifest_pubkey.as_deref() } else { None };
+
+    loop {
+        println!("Syncing trust lists into {trust_dir}");
+        let result = sync_trust_once(&trust_dir, manifest_url.as_deref(), pubkey, &lists);
+        match (&result, interval) {
+            (Ok(()), _) => println!("Trust sync OK"),
+            (Err(e), Some(_)) => eprintln!("Trust sync failed: {e:#}"),
+            (Err(_), None) => {}
+        }
+        let Some(interval) = interval else {
+            return result;
+        };
+        println!("Next sync in {}s", interval.as_secs());
+        std::thread::sleep(interval);
+    }
+}
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let config = match find_config() {
+        Some(path) => load_config(&path)?,
+        None => Config::default(),
+    };
+    let cli = parse_cli(&config);
+    let json = cli.json;
 
+    let result = run(cli);
+    if json {
+        if let Err(e) = &result {
+            println!("{}", serde_json::json!({ "error": format!("{e:#}"), "code": error_code(e) }));
+            std::process::exit(1);
+        }
+    }
+    result
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let json = cli.json;
     match cli.command {
-        Cmd::Register { name, keypair, api } => cmd_register(name, keypair, api),
-        Cmd::Attest { file, keypair, api, api_key, verifier, trust_dir } => {
-            cmd_attest(file, keypair, api, api_key, verifier, trust_dir)
+        Cmd::Register { name, keypair, api } => cmd_register(name, keypair, api, json),
+        Cmd::Attest { file, keypair, api, api_key, verifier, trust_dir, retry } => {
+            let _ = RETRY.set(retry);
+            cmd_attest(&file, &EdgeNode { keypair, api, api_key, verifier, trust_dir }, json)
+        }
+        Cmd::Batch { dir, extension, workers, skip_existing, dry_run, keypair, api, api_key, verifier, trust_dir } => {
+            let node = EdgeNode { keypair, api, api_key, verifier, trust_dir };
+            cmd_batch(&dir, &extension, workers, skip_existing, dry_run, &node)
+        }
+        Cmd::Watch { dir, extension, workers, max_retries, poll_interval, keypair, api, api_key, verifier, trust_dir } => {
+            let node = EdgeNode { keypair, api, api_key, verifier, trust_dir };
+            cmd_watch(&dir, &extension, workers, max_retries, poll_interval, &node)
+        }
+        Cmd::Config { action: ConfigCmd::Init { force } } => cmd_config_init(force),
+        Cmd::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "r3l-edge", &mut std::io::stdout());
+            Ok(())
         }
         Cmd::Hash { file } => {
             let hash = hash_file(&file)?;
+            if json {
+                return print_json(&HashResult { content_hash: hash, path: file.display().to_string() });
+            }
             println!("{hash}  {}", file.display());
             Ok(())
         }
-        Cmd::Query { hash, api } => {
-            let resp = get_json(&format!("{api}/api/v1/query/{hash}"))?;
-            println!("{}", serde_json::to_string_pretty(&resp)?);
-            Ok(())
+        Cmd::Query { hash, api, cache_ttl, force_refresh } => {
+            let url = format!("{api}/api/v1/query/{hash}");
+            let (resp, cached) = cached_get_json(&format!("query:{hash}"), &url, cache_ttl, force_refresh)?;
+            if json {
+                return print_json(&resp);
+            }
+            print_response(&resp, cached)
         }
-        Cmd::Lookup { hash, api } => {
-            let resp = get_json(&format!("{api}/api/attestation/{hash}"))?;
-            println!("{}", serde_json::to_string_pretty(&resp)?);
-            Ok(())
+        Cmd::Lookup { hash, api, cache_ttl, force_refresh } => {
+            let url = format!("{api}/api/attestation/{hash}");
+            let (resp, cached) = cached_get_json(&format!("lookup:{hash}"), &url, cache_ttl, force_refresh)?;
+            if json {
+                return print_json(&resp);
+            }
+            print_response(&resp, cached)
+        }
+        Cmd::List { api, limit, filter, output } => cmd_list(api, limit, filter, output),
+        Cmd::Cache { clear } => cmd_cache(clear),
+        Cmd::Test { api, api_key, cleanup, keypair, verifier, trust_dir } => {
+            cmd_test(api, api_key, cleanup, keypair, verifier, trust_dir)
+        }
+        Cmd::ImportKey { solana_keypair, output, force } => cmd_import_key(solana_keypair, output, force),
+        Cmd::ExportKey { format, keypair } => cmd_export_key(format, keypair),
+        Cmd::SyncTrust { trust_dir, manifest_url, verify_sig, manifest_pubkey, official_url, curated_url, schedule } => {
+            cmd_sync_trust(trust_dir, manifest_url, verify_sig, manifest_pubkey, official_url, curated_url, schedule)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("r3l-edge-{}-{name}", std::process::id()))
+    }
+
+    fn config_from(name: &str, toml: &str) -> Config {
+        let path = temp_path(name);
+        fs::write(&path, toml).unwrap();
+        let config = load_config(&path);
+        fs::remove_file(&path).unwrap();
+        config.unwrap()
+    }
+
+    fn attest_args(cli: Cli) -> (PathBuf, String, String, String, String) {
+        match cli.command {
+            Cmd::Attest { keypair, api, api_key, verifier, trust_dir, .. } => (keypair, api, api_key, verifier, trust_dir),
+            _ => panic!("expected attest"),
         }
     }
+
+    const CONFIG: &str = r#"
+api = "http://config.example:3001"
+keypair = "config-keypair.json"
+trust_dir = "/etc/r3l/trust"
+api_key = "config-key"
+"#;
+
+    #[test]
+    fn config_file_sets_defaults() {
+        let config = config_from("defaults.toml", CONFIG);
+        let cli = parse_cli_from(&config, ["r3l-edge", "attest", "a.png"].map(Into::into));
+
+        let (keypair, api, api_key, verifier, trust_dir) = attest_args(cli);
+        assert_eq!(keypair, PathBuf::from("config-keypair.json"));
+        assert_eq!(api, "http://config.example:3001");
+        assert_eq!(api_key, "config-key");
+        assert_eq!(verifier, "verifier", "unset in the file, so the built-in default");
+        assert_eq!(trust_dir, "/etc/r3l/trust");
+    }
+
+    #[test]
+    fn flags_override_config_file() {
+        let config = config_from("override.toml", CONFIG);
+        let args = [
+            "r3l-edge", "attest", "a.png",
+            "--api", "http://flag.example:8080",
+            "--api-key", "flag-key",
+            "--trust-dir", "flag-trust",
+        ];
+        let cli = parse_cli_from(&config, args.map(Into::into));
+
+        let (keypair, api, api_key, _, trust_dir) = attest_args(cli);
+        assert_eq!(api, "http://flag.example:8080");
+        assert_eq!(api_key, "flag-key");
+        assert_eq!(trust_dir, "flag-trust");
+        assert_eq!(keypair, PathBuf::from("config-keypair.json"), "not given as a flag");
+    }
+
+    #[test]
+    fn unknown_config_key_is_rejected() {
+        let path = temp_path("unknown.toml");
+        fs::write(&path, "api_url = \"http://typo\"\n").unwrap();
+        let err = load_config(&path).err();
+        fs::remove_file(&path).unwrap();
+        assert!(err.is_some());
+    }
+
+    /// Serve `statuses` in order, one per connection, on a local port.
+    /// Returns the base URL and a count of requests answered.
+    fn mock_server(statuses: &'static [u16]) -> (String, Arc<Mutex<usize>>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let served = Arc::new(Mutex::new(0));
+        let count = Arc::clone(&served);
+        std::thread::spawn(move || {
+            for &status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let body = if status == 200 { r#"{"ok":true}"# } else { "nope" };
+                let resp = format!(
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                *count.lock().unwrap() += 1;
+                reader.get_mut().write_all(resp.as_bytes()).unwrap();
+            }
+        });
+        (url, served)
+    }
+
+    fn fast_retries() {
+        let _ = RETRY.set(RetryConfig { max_attempts: 3, initial_delay_ms: 1, multiplier: 1.0 });
+    }
+
+    #[test]
+    fn retries_server_errors_until_success() {
+        fast_retries();
+        let (url, served) = mock_server(&[503, 503, 503, 200]);
+        let client = reqwest::blocking::Client::new();
+
+        let body = send_with_retry("GET", &url, || Ok(client.get(&url))).unwrap();
+
+        assert_eq!(body, r#"{"ok":true}"#);
+        assert_eq!(*served.lock().unwrap(), 4);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        fast_retries();
+        let (url, served) = mock_server(&[500, 502, 503, 504, 200]);
+        let client = reqwest::blocking::Client::new();
+
+        let err = send_with_retry("GET", &url, || Ok(client.get(&url))).unwrap_err();
+
+        assert_eq!(err.downcast_ref::<HttpError>().unwrap().status, 504);
+        assert_eq!(*served.lock().unwrap(), 4);
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        fast_retries();
+        let (url, served) = mock_server(&[404, 200]);
+        let client = reqwest::blocking::Client::new();
+
+        let err = send_with_retry("GET", &url, || Ok(client.get(&url))).unwrap_err();
+
+        assert_eq!(err.downcast_ref::<HttpError>().unwrap().status, 404);
+        assert_eq!(error_code(&err), "http_404");
+        assert_eq!(*served.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn too_many_requests_is_retried() {
+        fast_retries();
+        let (url, served) = mock_server(&[429, 200]);
+        let client = reqwest::blocking::Client::new();
+
+        send_with_retry("GET", &url, || Ok(client.get(&url))).unwrap();
+
+        assert_eq!(*served.lock().unwrap(), 2);
+    }
 }
@@ -0,0 +1,66 @@
+//! Fail the build when `SP1_VKEY_HASH` in src/constants.rs no longer matches
+//! the guest ELF. A stale hash makes every `submit_proof` fail proof
+//! verification.
+//!
+//! Asks a prebuilt `prove --vkey-only` for the current hash: `PROVE_BIN`,
+//! else services/prover/target/release/prove. The check is skipped, with a
+//! warning if the binary fails to run, when it isn't available (e.g. CI
+//! without the SP1 toolchain).
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/constants.rs");
+    println!("cargo:rerun-if-env-changed=PROVE_BIN");
+
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let prove_bin = std::env::var_os("PROVE_BIN")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| manifest_dir.join("../../../prover/target/release/prove"));
+    if !prove_bin.is_file() {
+        return;
+    }
+    println!("cargo:rerun-if-changed={}", prove_bin.display());
+
+    let Some(stored) = stored_vkey_hash(&manifest_dir.join("src/constants.rs")) else {
+        println!("cargo:warning=could not find SP1_VKEY_HASH in src/constants.rs");
+        return;
+    };
+
+    let output = match Command::new(&prove_bin).arg("--vkey-only").output() {
+        Ok(o) if o.status.success() => o,
+        Ok(o) => {
+            println!(
+                "cargo:warning=skipping SP1_VKEY_HASH check: {} --vkey-only exited with {}",
+                prove_bin.display(),
+                o.status
+            );
+            return;
+        }
+        Err(e) => {
+            println!(
+                "cargo:warning=skipping SP1_VKEY_HASH check: {}: {e}",
+                prove_bin.display()
+            );
+            return;
+        }
+    };
+    let actual = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    if !actual.eq_ignore_ascii_case(&stored) {
+        panic!(
+            "SP1_VKEY_HASH in src/constants.rs is stale: {} --vkey-only gives {actual} but it is {stored}",
+            prove_bin.display()
+        );
+    }
+}
+
+/// The string literal assigned to `SP1_VKEY_HASH`, which may sit on the next line.
+fn stored_vkey_hash(constants: &Path) -> Option<String> {
+    let src = std::fs::read_to_string(constants).ok()?;
+    let rest = &src[src.find("SP1_VKEY_HASH")?..];
+    let start = rest.find('"')? + 1;
+    let len = rest[start..].find('"')?;
+    Some(rest[start..start + len].to_string())
+}
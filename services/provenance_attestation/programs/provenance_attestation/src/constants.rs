@@ -1,9 +1,26 @@
 /// PDA seed prefix for attestation accounts
 pub const ATTESTATION_SEED: &[u8] = b"attestation";
 
+/// PDA seed prefix for per-content-hash `NonceCounter` accounts
+pub const NONCE_SEED: &[u8] = b"nonce";
+
+/// PDA seed prefix for `RevokedApiKey` accounts
+pub const REVOKED_SEED: &[u8] = b"revoked";
+
+/// Upper bound on `submit_proof` proof bytes (Groth16 proofs are ~900 bytes)
+pub const MAX_PROOF_LEN: usize = 2048;
+
+/// Upper bound on `submit_proof` public inputs (bincode PublicOutputs are ~200 bytes)
+pub const MAX_PUBLIC_INPUTS_LEN: usize = 512;
+
+/// How long an attestation's email domain association is valid, from
+/// creation or the last `renew_identity` (365 days)
+pub const IDENTITY_VALIDITY_SECS: i64 = 365 * 86400;
+
 /// SP1 verification key hash for the provenance guest program.
 /// Generated by running: cargo run --bin vkey
-/// This must be updated whenever the guest program changes.
+/// This must be updated whenever the guest program changes; build.rs fails
+/// the build while it disagrees with `prove --vkey-only`.
 pub const SP1_VKEY_HASH: &str =
     "0x009878322602e195e87e92e6771e1b212993077890856ee1b40a169c8d4cff27";
 
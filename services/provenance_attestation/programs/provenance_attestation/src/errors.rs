@@ -18,4 +18,24 @@ pub enum ProvenanceError {
     InvalidWalletSigVerify,
     #[msg("Wallet pubkey in Ed25519 instruction does not match wallet parameter")]
     WalletPubkeyMismatch,
+    #[msg("Account is not an attestation owned by this program")]
+    InvalidAttestationAccount,
+    #[msg("Too many co-signing wallets")]
+    TooManyWallets,
+    #[msg("Wallet listed more than once")]
+    DuplicateWallet,
+    #[msg("Proof exceeds maximum length")]
+    ProofTooLarge,
+    #[msg("Public inputs exceed maximum length")]
+    PublicInputsTooLarge,
+    #[msg("An attestation was already submitted for this content hash")]
+    AttestationReplay,
+    #[msg("Attestation has no email identity to renew")]
+    NoIdentity,
+    #[msg("Only the original submitter can renew this identity")]
+    NotSubmitter,
+    #[msg("Attestation is already revoked")]
+    AlreadyRevoked,
+    #[msg("Content hash and certificate fingerprint cannot be changed")]
+    ImmutableField,
 }
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+/// Emitted with `emit!` when `submit_proof` or `submit_attestation`
+/// creates an attestation. Written to the program log as a
+/// `Program data:` line, so indexers can follow it with `logsSubscribe`
+/// without fetching each transaction.
+#[event]
+pub struct AttestationCreated {
+    /// SHA-256 of the original file bytes
+    pub content_hash: [u8; 32],
+    /// "zk_groth16" or "trusted_verifier"
+    pub proof_type: String,
+    /// "official", "curated", "untrusted", or "" without a C2PA manifest
+    pub trust_list_match: String,
+    /// Solana clock timestamp
+    pub timestamp: i64,
+    /// Who submitted the transaction
+    pub submitted_by: Pubkey,
+}
+
+/// Emitted with `emit!` alongside `AttestationCreated` when the new
+/// attestation carries a verified email domain.
+#[event]
+pub struct IdentityAttested {
+    /// SHA-256 of the original file bytes
+    pub content_hash: [u8; 32],
+    /// Verified email domain (e.g. "example.com")
+    pub domain: String,
+    /// Solana clock timestamp
+    pub timestamp: i64,
+}
+
+/// Emitted with `emit_cpi!` when `submit_proof` or `submit_attestation`
+/// creates an attestation. The event is a self-CPI to the event authority
+/// PDA, so it is recorded as an inner instruction of the transaction
+/// rather than a log line, and can't be truncated or spoofed by other
+/// programs' logs.
+#[event]
+pub struct AttestationCreatedCpi {
+    /// SHA-256 of the original file bytes
+    pub content_hash: [u8; 32],
+    /// The new `Attestation` PDA
+    pub attestation: Pubkey,
+    /// Who submitted the transaction
+    pub submitted_by: Pubkey,
+    /// "zk_groth16" or "trusted_verifier"
+    pub proof_type: String,
+    /// Whether the file had valid C2PA metadata
+    pub has_c2pa: bool,
+    /// "official", "curated", "untrusted", or "" without a C2PA manifest
+    pub trust_list_match: String,
+    /// Solana clock timestamp
+    pub timestamp: i64,
+}
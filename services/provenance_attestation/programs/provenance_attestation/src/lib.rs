@@ -1,18 +1,21 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
 use anchor_lang::solana_program::ed25519_program;
 
 mod constants;
 mod errors;
-mod state;
+pub mod events;
+pub mod state;
 
-use constants::ATTESTATION_SEED;
+use constants::{ATTESTATION_SEED, IDENTITY_VALIDITY_SECS, MAX_PROOF_LEN, MAX_PUBLIC_INPUTS_LEN, NONCE_SEED, REVOKED_SEED};
 #[cfg(not(feature = "skip-authority-check"))]
 use constants::AUTHORITY;
 #[cfg(not(feature = "skip-verification"))]
 use constants::SP1_VKEY_HASH;
 use errors::ProvenanceError;
-use state::Attestation;
+use events::{AttestationCreated, AttestationCreatedCpi, IdentityAttested};
+use state::{Attestation, NonceCounter, RevokedApiKey};
 #[cfg(not(feature = "skip-authority-check"))]
 use anchor_lang::solana_program::pubkey::Pubkey as SolPubkey;
 #[cfg(not(feature = "skip-authority-check"))]
@@ -40,10 +43,19 @@ pub mod provenance_attestation {
         content_hash: [u8; 32],
         email_domain: String,
         email_hash: [u8; 32],
-        wallet: Pubkey,
+        wallets: Vec<Pubkey>,
         verifier_version: String,
         trust_bundle_hash: String,
+        manifest_hash: String,
+        assertions_digest: String,
+        software_agent_canonical: String,
     ) -> Result<()> {
+        require!(proof.len() <= MAX_PROOF_LEN, ProvenanceError::ProofTooLarge);
+        require!(
+            public_inputs.len() <= MAX_PUBLIC_INPUTS_LEN,
+            ProvenanceError::PublicInputsTooLarge
+        );
+
         // 1. Verify the Groth16 proof on-chain
         #[cfg(not(feature = "skip-verification"))]
         {
@@ -69,6 +81,12 @@ pub mod provenance_attestation {
             ProvenanceError::ContentHashMismatch
         );
 
+        // 3b. One proof attestation per content hash, ever (survives account closure)
+        let nonce_counter = &mut ctx.accounts.nonce_counter;
+        require!(nonce_counter.count == 0, ProvenanceError::AttestationReplay);
+        nonce_counter.count += 1;
+        nonce_counter.bump = ctx.bumps.nonce_counter;
+
         // 4. Validate string lengths
         require!(outputs.trust_list_match.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
         require!(outputs.validation_state.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
@@ -81,6 +99,10 @@ pub mod provenance_attestation {
         require!(email_domain.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
         require!(verifier_version.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
         require!(trust_bundle_hash.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(manifest_hash.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(assertions_digest.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(software_agent_canonical.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(wallets.len() <= Attestation::MAX_WALLETS, ProvenanceError::TooManyWallets);
 
         // 5. Store attestation from verified outputs
         let attestation = &mut ctx.accounts.attestation;
@@ -100,20 +122,27 @@ pub mod provenance_attestation {
         attestation.proof_type = "zk_groth16".to_string();
         attestation.email_domain = email_domain;
         attestation.email_hash = email_hash;
-        attestation.wallet = wallet;
         attestation.verifier_version = verifier_version;
         attestation.trust_bundle_hash = trust_bundle_hash;
-
-        // Verify wallet signature on-chain via Ed25519 precompile
-        if wallet != Pubkey::default() {
-            let sig = verify_wallet_sig(&ctx.accounts.instructions, &wallet, &content_hash)?;
-            attestation.wallet_sig = sig;
+        attestation.manifest_hash = manifest_hash;
+        attestation.assertions_digest = assertions_digest;
+        attestation.software_agent_canonical = software_agent_canonical;
+        attestation.expiry_timestamp = identity_expiry(&attestation.email_domain, attestation.timestamp);
+
+        // Verify wallet signatures on-chain via Ed25519 precompile
+        if !wallets.is_empty() {
+            let sigs = verify_wallet_sigs(&ctx.accounts.instructions, &wallets, &content_hash)?;
+            store_wallets(attestation, &wallets, sigs);
         }
 
+        let event = attestation_created(attestation);
+
         msg!(
             "Attestation stored for content_hash: {:?}",
             hex::encode(content_hash)
         );
+        emit_created_logs(attestation);
+        emit_cpi!(event);
 
         Ok(())
     }
@@ -137,20 +166,15 @@ pub mod provenance_attestation {
         cert_fingerprint: String,
         email_domain: String,
         email_hash: [u8; 32],
-        wallet: Pubkey,
+        wallets: Vec<Pubkey>,
         verifier_version: String,
         trust_bundle_hash: String,
+        manifest_hash: String,
+        assertions_digest: String,
+        software_agent_canonical: String,
     ) -> Result<()> {
         // 1. Verify authority
-        #[cfg(not(feature = "skip-authority-check"))]
-        {
-            let expected = SolPubkey::from_str(AUTHORITY)
-                .map_err(|_| ProvenanceError::Unauthorized)?;
-            require!(
-                ctx.accounts.authority.key() == expected,
-                ProvenanceError::Unauthorized
-            );
-        }
+        require_authority(&ctx.accounts.authority.key())?;
 
         // 2. Validate string lengths
         require!(trust_list_match.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
@@ -164,6 +188,10 @@ pub mod provenance_attestation {
         require!(email_domain.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
         require!(verifier_version.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
         require!(trust_bundle_hash.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(manifest_hash.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(assertions_digest.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(software_agent_canonical.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(wallets.len() <= Attestation::MAX_WALLETS, ProvenanceError::TooManyWallets);
 
         // 3. Store attestation
         let attestation = &mut ctx.accounts.attestation;
@@ -183,35 +211,282 @@ pub mod provenance_attestation {
         attestation.bump = ctx.bumps.attestation;
         attestation.email_domain = email_domain;
         attestation.email_hash = email_hash;
-        attestation.wallet = wallet;
         attestation.verifier_version = verifier_version;
         attestation.trust_bundle_hash = trust_bundle_hash;
-
-        // Verify wallet signature on-chain via Ed25519 precompile
-        if wallet != Pubkey::default() {
-            let sig = verify_wallet_sig(&ctx.accounts.instructions, &wallet, &content_hash)?;
-            attestation.wallet_sig = sig;
+        attestation.manifest_hash = manifest_hash;
+        attestation.assertions_digest = assertions_digest;
+        attestation.software_agent_canonical = software_agent_canonical;
+        attestation.expiry_timestamp = identity_expiry(&attestation.email_domain, attestation.timestamp);
+
+        // Verify wallet signatures on-chain via Ed25519 precompile
+        if !wallets.is_empty() {
+            let sigs = verify_wallet_sigs(&ctx.accounts.instructions, &wallets, &content_hash)?;
+            store_wallets(attestation, &wallets, sigs);
         }
 
+        let event = attestation_created(attestation);
+
         msg!(
             "Trusted attestation stored for content_hash: {:?}",
             hex::encode(content_hash),
         );
+        emit_created_logs(attestation);
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Grow an existing attestation PDA to the current `Attestation::SPACE`.
+    /// Authority-gated. The authority pays any additional rent.
+    ///
+    /// Accounts created before new fields were added are too small to
+    /// deserialize the current layout, so this works on the raw account.
+    /// New trailing bytes are zeroed, which decodes as "" / zeros / default.
+    pub fn reallocate_attestation(
+        ctx: Context<ReallocateAttestation>,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        require_authority(&ctx.accounts.authority.key())?;
+
+        let info = ctx.accounts.attestation.to_account_info();
+        require!(info.owner == &crate::ID, ProvenanceError::InvalidAttestationAccount);
+        {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == Attestation::DISCRIMINATOR,
+                ProvenanceError::InvalidAttestationAccount
+            );
+        }
+
+        let old_len = info.data_len();
+        if old_len >= Attestation::SPACE {
+            msg!("Attestation already at current size ({} bytes)", old_len);
+            return Ok(());
+        }
+
+        // Top up rent for the new size
+        let required = Rent::get()?.minimum_balance(Attestation::SPACE);
+        let current = info.lamports();
+        if required > current {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                required - current,
+            )?;
+        }
+
+        info.realloc(Attestation::SPACE, false)?;
+        info.try_borrow_mut_data()?[old_len..].fill(0);
+
+        msg!(
+            "Attestation reallocated {} -> {} bytes for content_hash: {:?}",
+            old_len,
+            Attestation::SPACE,
+            hex::encode(content_hash),
+        );
+
+        Ok(())
+    }
+
+    /// Revoke an edge node API key by creating its `RevokedApiKey` PDA.
+    /// Authority-gated. Revoking the same key twice fails (account in use).
+    pub fn revoke_api_key(ctx: Context<RevokeApiKey>, api_key_hash: [u8; 32]) -> Result<()> {
+        require_authority(&ctx.accounts.authority.key())?;
+
+        let revoked = &mut ctx.accounts.revoked;
+        revoked.api_key_hash = api_key_hash;
+        revoked.revoked_at = Clock::get()?.unix_timestamp;
+        revoked.bump = ctx.bumps.revoked;
+
+        msg!("API key revoked: {:?}", hex::encode(api_key_hash));
+
+        Ok(())
+    }
+
+    /// Extend an attestation's email domain association to
+    /// IDENTITY_VALIDITY_SECS from now. Only the original `submitted_by`
+    /// can renew, and only attestations that carry an email domain.
+    pub fn renew_identity(ctx: Context<RenewIdentity>, content_hash: [u8; 32]) -> Result<()> {
+        let attestation = &mut ctx.accounts.attestation;
+        require!(!attestation.email_domain.is_empty(), ProvenanceError::NoIdentity);
+
+        attestation.expiry_timestamp = Clock::get()?.unix_timestamp + IDENTITY_VALIDITY_SECS;
+        attestation.renewal_count += 1;
+
+        msg!(
+            "Identity renewed until {} for content_hash: {:?}",
+            attestation.expiry_timestamp,
+            hex::encode(content_hash),
+        );
 
         Ok(())
     }
+
+    /// Mark an attestation as revoked, e.g. when the content turns out to
+    /// be manipulated. Authority-gated. The account stays readable so the
+    /// record and its revocation remain public.
+    pub fn revoke_attestation(
+        ctx: Context<RevokeAttestation>,
+        content_hash: [u8; 32],
+        reason: String,
+    ) -> Result<()> {
+        require_authority(&ctx.accounts.authority.key())?;
+        require!(reason.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+
+        let attestation = &mut ctx.accounts.attestation;
+        require!(!attestation.revoked, ProvenanceError::AlreadyRevoked);
+        attestation.revoked = true;
+        attestation.revocation_reason = reason;
+
+        msg!(
+            "Attestation revoked for content_hash: {:?}",
+            hex::encode(content_hash),
+        );
+
+        Ok(())
+    }
+
+    /// Refresh the infrastructure fields of an attestation after the R3L
+    /// verifier or trust lists are upgraded, without a new proof.
+    /// Authority-gated. Only `verifier_version`, `trust_bundle_hash` and
+    /// `trust_list_match` change; `cert_fingerprint` must be passed as
+    /// stored, and any other value fails with `ImmutableField`.
+    pub fn update_attestation(
+        ctx: Context<UpdateAttestation>,
+        content_hash: [u8; 32],
+        cert_fingerprint: String,
+        verifier_version: String,
+        trust_bundle_hash: String,
+        trust_list_match: String,
+    ) -> Result<()> {
+        require_authority(&ctx.accounts.authority.key())?;
+        require!(verifier_version.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(trust_bundle_hash.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+        require!(trust_list_match.len() <= Attestation::MAX_STRING_LEN, ProvenanceError::StringTooLong);
+
+        let attestation = &mut ctx.accounts.attestation;
+        require!(attestation.content_hash == content_hash, ProvenanceError::ImmutableField);
+        require!(attestation.cert_fingerprint == cert_fingerprint, ProvenanceError::ImmutableField);
+
+        attestation.verifier_version = verifier_version;
+        attestation.trust_bundle_hash = trust_bundle_hash;
+        attestation.trust_list_match = trust_list_match;
+        attestation.last_updated = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Attestation updated for content_hash: {:?}",
+            hex::encode(content_hash),
+        );
+
+        Ok(())
+    }
+
+    /// Close an attestation PDA and return its rent to `destination`.
+    /// Callable by the attestation's `submitted_by` or the R3L authority.
+    /// The `NonceCounter` is left open, so `submit_proof` still rejects
+    /// the content hash afterwards.
+    pub fn close_attestation(ctx: Context<CloseAttestation>, content_hash: [u8; 32]) -> Result<()> {
+        let signer = ctx.accounts.signer.key();
+        if signer != ctx.accounts.attestation.submitted_by {
+            require_authority(&signer)?;
+        }
+
+        msg!(
+            "Attestation closed for content_hash: {:?}",
+            hex::encode(content_hash),
+        );
+
+        Ok(())
+    }
+}
+
+/// Expiry for a new attestation's email domain, or 0 if it has none.
+fn identity_expiry(email_domain: &str, now: i64) -> i64 {
+    if email_domain.is_empty() {
+        0
+    } else {
+        now + IDENTITY_VALIDITY_SECS
+    }
+}
+
+/// `AttestationCreatedCpi` for a just-populated attestation account.
+fn attestation_created(attestation: &Account<Attestation>) -> AttestationCreatedCpi {
+    AttestationCreatedCpi {
+        content_hash: attestation.content_hash,
+        attestation: attestation.key(),
+        submitted_by: attestation.submitted_by,
+        proof_type: attestation.proof_type.clone(),
+        has_c2pa: attestation.has_c2pa,
+        trust_list_match: attestation.trust_list_match.clone(),
+        timestamp: attestation.timestamp,
+    }
+}
+
+/// Log-based `AttestationCreated`, plus `IdentityAttested` if the
+/// attestation has an email domain.
+fn emit_created_logs(attestation: &Attestation) {
+    emit!(AttestationCreated {
+        content_hash: attestation.content_hash,
+        proof_type: attestation.proof_type.clone(),
+        trust_list_match: attestation.trust_list_match.clone(),
+        timestamp: attestation.timestamp,
+        submitted_by: attestation.submitted_by,
+    });
+    if !attestation.email_domain.is_empty() {
+        emit!(IdentityAttested {
+            content_hash: attestation.content_hash,
+            domain: attestation.email_domain.clone(),
+            timestamp: attestation.timestamp,
+        });
+    }
+}
+
+/// Ensure the signer is the R3L authority (no-op with `skip-authority-check`).
+fn require_authority(key: &Pubkey) -> Result<()> {
+    #[cfg(not(feature = "skip-authority-check"))]
+    {
+        let expected = SolPubkey::from_str(AUTHORITY)
+            .map_err(|_| ProvenanceError::Unauthorized)?;
+        require!(*key == expected, ProvenanceError::Unauthorized);
+    }
+
+    #[cfg(feature = "skip-authority-check")]
+    let _ = key;
+
+    Ok(())
 }
 
 const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
 
-/// Verify that the transaction contains an Ed25519 precompile instruction
-/// with the expected wallet pubkey and message ("R3L: attest " + hex(content_hash)).
-/// Returns the 64-byte signature extracted from the instruction data.
-fn verify_wallet_sig(
+/// Put the first wallet in `wallet`/`wallet_sig`, where accounts from before
+/// multi-wallet support keep it, and any others in `co_signers`.
+fn store_wallets(attestation: &mut Attestation, wallets: &[Pubkey], sigs: Vec<[u8; 64]>) {
+    let mut sigs = sigs.into_iter();
+    if let (Some(wallet), Some(sig)) = (wallets.first(), sigs.next()) {
+        attestation.wallet = *wallet;
+        attestation.wallet_sig = sig;
+    }
+    attestation.co_signers = wallets.iter().skip(1).copied().collect();
+    attestation.co_signer_sigs = sigs.collect();
+}
+
+/// Verify that the transaction contains one Ed25519 precompile instruction
+/// per wallet, each signing "R3L: attest " + hex(content_hash).
+/// Returns the 64-byte signatures in the same order as `wallets`.
+fn verify_wallet_sigs(
     instructions_account: &UncheckedAccount,
-    wallet: &Pubkey,
+    wallets: &[Pubkey],
     content_hash: &[u8; 32],
-) -> Result<[u8; 64]> {
+) -> Result<Vec<[u8; 64]>> {
+    for (i, wallet) in wallets.iter().enumerate() {
+        require!(!wallets[..i].contains(wallet), ProvenanceError::DuplicateWallet);
+    }
+
     let ix_sysvar_data = instructions_account.try_borrow_data()
         .map_err(|_| error!(ProvenanceError::InvalidWalletSigVerify))?;
 
@@ -229,6 +504,8 @@ fn verify_wallet_sig(
     // Use load_instruction_at_checked from the sysvar
     drop(ix_sysvar_data); // release borrow before calling sysvar functions
 
+    let mut sigs: Vec<Option<[u8; 64]>> = vec![None; wallets.len()];
+
     for i in 0..num_ix {
         let ix = match ix_sysvar::load_instruction_at_checked(i, &instructions_account.to_account_info()) {
             Ok(ix) => ix,
@@ -250,29 +527,30 @@ fn verify_wallet_sig(
             continue;
         }
 
-        // Extract signature (bytes 16..80)
-        let mut sig = [0u8; 64];
-        sig.copy_from_slice(&data[16..80]);
+        // Only attestation messages for this content hash count
+        let message = &data[112..];
+        if !verify_wallet_message(message, content_hash) {
+            continue;
+        }
 
-        // Extract pubkey (bytes 80..112)
+        // Extract pubkey (bytes 80..112) — must be one of the listed wallets
         let ix_pubkey = Pubkey::try_from(&data[80..112])
             .map_err(|_| error!(ProvenanceError::InvalidWalletSigVerify))?;
+        let idx = wallets
+            .iter()
+            .position(|w| *w == ix_pubkey)
+            .ok_or(error!(ProvenanceError::WalletPubkeyMismatch))?;
 
-        // Verify pubkey matches the wallet parameter
-        require!(ix_pubkey == *wallet, ProvenanceError::WalletPubkeyMismatch);
-
-        // Extract and verify message
-        let message = &data[112..];
-        require!(
-            verify_wallet_message(message, content_hash),
-            ProvenanceError::InvalidWalletSigVerify
-        );
-
-        return Ok(sig);
+        // Extract signature (bytes 16..80)
+        let mut sig = [0u8; 64];
+        sig.copy_from_slice(&data[16..80]);
+        sigs[idx] = Some(sig);
     }
 
-    // No Ed25519 instruction found
-    err!(ProvenanceError::InvalidWalletSigVerify)
+    // Every listed wallet needs its own Ed25519 instruction
+    sigs.into_iter()
+        .map(|s| s.ok_or(error!(ProvenanceError::InvalidWalletSigVerify)))
+        .collect()
 }
 
 /// Verify that a message matches "R3L: attest " + hex(content_hash)
@@ -312,18 +590,39 @@ struct ParsedOutputs {
     cert_fingerprint: String,
 }
 
+/// Where and why `parse_with_diagnostics` rejected the public outputs.
+#[derive(Debug)]
+struct ParseError {
+    field: &'static str,
+    offset: usize,
+    reason: &'static str,
+}
+
 /// Parse bincode 1.x serialized PublicOutputs from SP1 public values.
-///
+/// On failure the [`ParseError`] is logged before returning
+/// `InvalidPublicOutputs`.
+fn parse_public_outputs(data: &[u8]) -> Result<ParsedOutputs> {
+    parse_with_diagnostics(data).map_err(|e| {
+        msg!("{:?}", e);
+        error!(ProvenanceError::InvalidPublicOutputs)
+    })
+}
+
 /// Layout:
 /// - `[u8; 32]`: 32 raw bytes (content_hash)
 /// - `bool`: 1 byte (has_c2pa)
-/// - 8x `String`: each is u64 LE length prefix + UTF-8 bytes
-fn parse_public_outputs(data: &[u8]) -> Result<ParsedOutputs> {
+/// - `TrustLevel`, `ValidationState`: u32 LE variant index each
+/// - 6x `String`: each is u64 LE length prefix + UTF-8 bytes
+fn parse_with_diagnostics(data: &[u8]) -> core::result::Result<ParsedOutputs, ParseError> {
     let mut cursor = 0usize;
 
     // content_hash: [u8; 32]
     if data.len() < cursor + 32 {
-        return err!(ProvenanceError::InvalidPublicOutputs);
+        return Err(ParseError {
+            field: "content_hash",
+            offset: cursor,
+            reason: "truncated",
+        });
     }
     let mut content_hash = [0u8; 32];
     content_hash.copy_from_slice(&data[cursor..cursor + 32]);
@@ -331,20 +630,27 @@ fn parse_public_outputs(data: &[u8]) -> Result<ParsedOutputs> {
 
     // has_c2pa: bool (1 byte)
     if data.len() < cursor + 1 {
-        return err!(ProvenanceError::InvalidPublicOutputs);
+        return Err(ParseError {
+            field: "has_c2pa",
+            offset: cursor,
+            reason: "truncated",
+        });
     }
     let has_c2pa = data[cursor] != 0;
     cursor += 1;
 
-    // 8 String fields
-    let trust_list_match = read_bincode_string(data, &mut cursor)?;
-    let validation_state = read_bincode_string(data, &mut cursor)?;
-    let digital_source_type = read_bincode_string(data, &mut cursor)?;
-    let issuer = read_bincode_string(data, &mut cursor)?;
-    let common_name = read_bincode_string(data, &mut cursor)?;
-    let software_agent = read_bincode_string(data, &mut cursor)?;
-    let signing_time = read_bincode_string(data, &mut cursor)?;
-    let cert_fingerprint = read_bincode_string(data, &mut cursor)?;
+    // Enum fields, stored under their string names
+    let trust_list_match = read_bincode_variant(data, &mut cursor, "trust_list_match", TRUST_LEVELS)?;
+    let validation_state =
+        read_bincode_variant(data, &mut cursor, "validation_state", VALIDATION_STATES)?;
+
+    // 6 String fields
+    let digital_source_type = read_bincode_string(data, &mut cursor, "digital_source_type")?;
+    let issuer = read_bincode_string(data, &mut cursor, "issuer")?;
+    let common_name = read_bincode_string(data, &mut cursor, "common_name")?;
+    let software_agent = read_bincode_string(data, &mut cursor, "software_agent")?;
+    let signing_time = read_bincode_string(data, &mut cursor, "signing_time")?;
+    let cert_fingerprint = read_bincode_string(data, &mut cursor, "cert_fingerprint")?;
 
     Ok(ParsedOutputs {
         content_hash,
@@ -360,28 +666,80 @@ fn parse_public_outputs(data: &[u8]) -> Result<ParsedOutputs> {
     })
 }
 
-/// Read a bincode 1.x String: u64 LE length prefix followed by UTF-8 bytes.
-fn read_bincode_string(data: &[u8], cursor: &mut usize) -> Result<String> {
-    if data.len() < *cursor + 8 {
-        return err!(ProvenanceError::InvalidPublicOutputs);
-    }
-    let len_bytes: [u8; 8] = data[*cursor..*cursor + 8]
-        .try_into()
-        .map_err(|_| error!(ProvenanceError::InvalidPublicOutputs))?;
-    let len = u64::from_le_bytes(len_bytes) as usize;
-    *cursor += 8;
-
-    if data.len() < *cursor + len {
-        return err!(ProvenanceError::InvalidPublicOutputs);
-    }
-    let s = core::str::from_utf8(&data[*cursor..*cursor + len])
-        .map_err(|_| error!(ProvenanceError::InvalidPublicOutputs))?
+/// `prover_shared::TrustLevel` names, in variant order. `None` (no C2PA
+/// manifest) is stored empty, as direct submissions store it.
+const TRUST_LEVELS: &[&str] = &["official", "curated", "untrusted", ""];
+
+/// `prover_shared::ValidationState` names, in variant order.
+const VALIDATION_STATES: &[&str] = &[
+    "Verified",
+    "SignatureOnly",
+    "ExpiredCertificate",
+    "Revoked",
+    "InvalidKeyUsage",
+    "None",
+    "ParseError",
+];
+
+/// Read a bincode unit-enum variant index and return its name from `names`.
+fn read_bincode_variant(
+    data: &[u8],
+    cursor: &mut usize,
+    field: &'static str,
+    names: &[&str],
+) -> core::result::Result<String, ParseError> {
+    let offset = *cursor;
+    let fail = |reason| ParseError {
+        field,
+        offset,
+        reason,
+    };
+
+    let tag_bytes: [u8; 4] = data
+        .get(offset..offset + 4)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| fail("truncated variant index"))?;
+    let name = names
+        .get(u32::from_le_bytes(tag_bytes) as usize)
+        .ok_or_else(|| fail("unknown variant index"))?;
+    *cursor = offset + 4;
+
+    Ok(name.to_string())
+}
+
+fn read_bincode_string(
+    data: &[u8],
+    cursor: &mut usize,
+    field: &'static str,
+) -> core::result::Result<String, ParseError> {
+    let offset = *cursor;
+    let fail = |reason| ParseError {
+        field,
+        offset,
+        reason,
+    };
+
+    let len_bytes: [u8; 8] = data
+        .get(offset..offset + 8)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| fail("truncated length prefix"))?;
+    let len = usize::try_from(u64::from_le_bytes(len_bytes))
+        .map_err(|_| fail("length overflows usize"))?;
+
+    let start = offset + 8;
+    let bytes = start
+        .checked_add(len)
+        .and_then(|end| data.get(start..end))
+        .ok_or_else(|| fail("length exceeds remaining data"))?;
+    let s = core::str::from_utf8(bytes)
+        .map_err(|_| fail("invalid UTF-8"))?
         .to_string();
-    *cursor += len;
+    *cursor = start + len;
 
     Ok(s)
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(
     proof: Vec<u8>,
@@ -403,8 +761,17 @@ pub struct SubmitProof<'info> {
     /// CHECK: Instructions sysvar for Ed25519 signature verification
     #[account(address = ix_sysvar::ID)]
     pub instructions: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = NonceCounter::SPACE,
+        seeds = [NONCE_SEED, content_hash.as_ref()],
+        bump,
+    )]
+    pub nonce_counter: Account<'info, NonceCounter>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(content_hash: [u8; 32])]
 pub struct SubmitAttestation<'info> {
@@ -423,3 +790,89 @@ pub struct SubmitAttestation<'info> {
     #[account(address = ix_sysvar::ID)]
     pub instructions: UncheckedAccount<'info>,
 }
+
+#[derive(Accounts)]
+#[instruction(content_hash: [u8; 32])]
+pub struct ReallocateAttestation<'info> {
+    /// CHECK: May predate the current layout, so it cannot be deserialized
+    /// as `Attestation`. Owner and discriminator are checked in the handler.
+    #[account(
+        mut,
+        seeds = [ATTESTATION_SEED, content_hash.as_ref()],
+        bump,
+    )]
+    pub attestation: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(api_key_hash: [u8; 32])]
+pub struct RevokeApiKey<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = RevokedApiKey::SPACE,
+        seeds = [REVOKED_SEED, api_key_hash.as_ref()],
+        bump,
+    )]
+    pub revoked: Account<'info, RevokedApiKey>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(content_hash: [u8; 32])]
+pub struct RenewIdentity<'info> {
+    #[account(
+        mut,
+        seeds = [ATTESTATION_SEED, content_hash.as_ref()],
+        bump = attestation.bump,
+        has_one = submitted_by @ ProvenanceError::NotSubmitter,
+    )]
+    pub attestation: Account<'info, Attestation>,
+    pub submitted_by: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(content_hash: [u8; 32])]
+pub struct RevokeAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [ATTESTATION_SEED, content_hash.as_ref()],
+        bump = attestation.bump,
+    )]
+    pub attestation: Account<'info, Attestation>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(content_hash: [u8; 32])]
+pub struct UpdateAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [ATTESTATION_SEED, content_hash.as_ref()],
+        bump = attestation.bump,
+    )]
+    pub attestation: Account<'info, Attestation>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(content_hash: [u8; 32])]
+pub struct CloseAttestation<'info> {
+    #[account(
+        mut,
+        close = destination,
+        seeds = [ATTESTATION_SEED, content_hash.as_ref()],
+        bump = attestation.bump,
+    )]
+    pub attestation: Account<'info, Attestation>,
+    /// `submitted_by` or the R3L authority (checked in the handler)
+    pub signer: Signer<'info>,
+    /// CHECK: Only receives the reclaimed rent
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
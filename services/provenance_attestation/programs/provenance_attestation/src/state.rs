@@ -4,13 +4,17 @@ use anchor_lang::prelude::*;
 /// Contains C2PA verification signals, optional email identity,
 /// optional wallet identity, and verifier versioning.
 /// PDA seeded by [b"attestation", content_hash].
+///
+/// `close_attestation` reclaims the rent. A closed account can't be
+/// re-initialized with the same content hash until it is fully gone
+/// (closing transaction landed, zero lamports, owned by the system program).
 #[account]
 pub struct Attestation {
     /// SHA-256 of the original file bytes
     pub content_hash: [u8; 32],
     /// Whether the file had valid C2PA metadata
     pub has_c2pa: bool,
-    /// "official", "curated", or "untrusted"
+    /// "official", "curated", "untrusted", or "" without a C2PA manifest
     pub trust_list_match: String,
     /// "Trusted", "Valid", or "Invalid"
     pub validation_state: String,
@@ -41,7 +45,8 @@ pub struct Attestation {
     pub email_domain: String,
     /// SHA-256 of the full email address (privacy-preserving)
     pub email_hash: [u8; 32],
-    /// Wallet pubkey that signed an attestation message
+    /// Wallet pubkey that signed an attestation message (the first
+    /// co-signer; zeros if none)
     pub wallet: Pubkey,
     /// Ed25519 signature from the wallet (verified on-chain via precompile)
     pub wallet_sig: [u8; 64],
@@ -52,16 +57,104 @@ pub struct Attestation {
     pub verifier_version: String,
     /// SHA-256 hex of the concatenated trust list PEM bundle
     pub trust_bundle_hash: String,
+    /// SHA-256 hex of the C2PA manifest store JSON ("" if none); shared by
+    /// files that carry the same manifest
+    pub manifest_hash: String,
+    /// SHA-256 hex of the active manifest's sorted assertion labels ("" if
+    /// none); changes when assertions are added or removed
+    pub assertions_digest: String,
+
+    // ── Identity expiry ──
+
+    /// When the email domain association lapses (0 if no email_domain);
+    /// `renew_identity` moves it to IDENTITY_VALIDITY_SECS from now
+    pub expiry_timestamp: i64,
+    /// Number of `renew_identity` calls
+    pub renewal_count: u32,
+
+    // ── Revocation ──
+
+    /// Set by `revoke_attestation` when the content is found to be
+    /// manipulated; the record itself is kept
+    pub revoked: bool,
+    /// Why the attestation was revoked ("" unless `revoked`)
+    pub revocation_reason: String,
+
+    // ── Updates ──
+
+    /// Solana clock timestamp of the last `update_attestation` (0 if never
+    /// updated)
+    pub last_updated: i64,
+
+    // ── Co-signers ──
+
+    /// Wallets that co-signed after `wallet` (max MAX_WALLETS - 1)
+    pub co_signers: Vec<Pubkey>,
+    /// Ed25519 signature from each co-signer, same order as `co_signers`
+    pub co_signer_sigs: Vec<[u8; 64]>,
+
+    /// Verifier's canonical product name for `software_agent` (e.g.
+    /// "Adobe Photoshop"; "" if unrecognized)
+    pub software_agent_canonical: String,
+}
+
+/// Number of `submit_proof` attestations ever created for a content hash.
+/// Never closed, so it outlives the attestation and blocks replaying old
+/// public inputs into a re-opened PDA.
+/// PDA seeded by [b"nonce", content_hash].
+#[account]
+pub struct NonceCounter {
+    pub count: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl NonceCounter {
+    /// 8 (discriminator) + 8 (count) + 1 (bump)
+    pub const SPACE: usize = 8 + 8 + 1;
+}
+
+/// Marks an edge node API key as revoked. The API refuses edge
+/// attestations made with a key whose PDA exists.
+/// PDA seeded by [b"revoked", sha256(api_key)].
+#[account]
+pub struct RevokedApiKey {
+    /// SHA-256 of the API key (the key itself never goes on-chain)
+    pub api_key_hash: [u8; 32],
+    /// Solana clock timestamp
+    pub revoked_at: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RevokedApiKey {
+    /// 8 (discriminator) + 32 (api_key_hash) + 8 (revoked_at) + 1 (bump)
+    pub const SPACE: usize = 8 + 32 + 8 + 1;
 }
 
 impl Attestation {
     /// Max size for each string field (bytes)
     pub const MAX_STRING_LEN: usize = 128;
 
+    /// Max co-signing wallets per attestation, counting `wallet`
+    pub const MAX_WALLETS: usize = 5;
+
     /// Space needed for the account:
     /// 8 (discriminator) + 32 (content_hash) + 1 (has_c2pa) +
-    /// 12 * (4 + MAX_STRING_LEN) (12 string fields) +
+    /// 14 * (4 + MAX_STRING_LEN) (14 string fields) +
     /// 32 (submitted_by) + 8 (timestamp) + 1 (bump) +
-    /// 32 (email_hash) + 32 (wallet) + 64 (wallet_sig)
-    pub const SPACE: usize = 8 + 32 + 1 + 12 * (4 + Self::MAX_STRING_LEN) + 32 + 8 + 1 + 32 + 32 + 64;
+    /// 32 (email_hash) + 32 (wallet) + 64 (wallet_sig) +
+    /// 8 (expiry_timestamp) + 4 (renewal_count) +
+    /// 1 (revoked) + 4 + MAX_STRING_LEN (revocation_reason) +
+    /// 8 (last_updated) +
+    /// 4 + (MAX_WALLETS - 1) * 32 (co_signers) +
+    /// 4 + (MAX_WALLETS - 1) * 64 (co_signer_sigs) +
+    /// 4 + MAX_STRING_LEN (software_agent_canonical)
+    pub const SPACE: usize = 8 + 32 + 1 + 14 * (4 + Self::MAX_STRING_LEN) + 32 + 8 + 1 + 32 + 32 + 64
+        + 8 + 4
+        + 1 + (4 + Self::MAX_STRING_LEN)
+        + 8
+        + (4 + (Self::MAX_WALLETS - 1) * 32)
+        + (4 + (Self::MAX_WALLETS - 1) * 64)
+        + (4 + Self::MAX_STRING_LEN);
 }
@@ -0,0 +1,1030 @@
+//! In-process program tests against a BanksClient bank (the same runtime
+//! `solana-bankrun` wraps), no `solana-test-validator` needed.
+//!
+//! Run from programs/provenance_attestation:
+//!   cargo test --features skip-authority-check,skip-verification
+//!   cargo test --features skip-verification   # authority rejection
+
+use anchor_lang::prelude::AccountInfo;
+use anchor_lang::solana_program::{entrypoint::ProgramResult, sysvar};
+use anchor_lang::{system_program, Discriminator, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+#[cfg(feature = "skip-authority-check")]
+use anchor_lang::AccountDeserialize;
+#[cfg(feature = "skip-authority-check")]
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+#[cfg(feature = "skip-authority-check")]
+use provenance_attestation::events::{AttestationCreated, IdentityAttested};
+#[cfg(feature = "skip-authority-check")]
+use provenance_attestation::state::Attestation;
+#[cfg(feature = "skip-authority-check")]
+use solana_sdk::clock::Clock;
+
+const ATTESTATION_SEED: &[u8] = b"attestation";
+const NONCE_SEED: &[u8] = b"nonce";
+const REVOKED_SEED: &[u8] = b"revoked";
+/// Anchor's `#[event_cpi]` signer seed
+const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Must match `Attestation::MAX_STRING_LEN` in state.rs
+const MAX_STRING_LEN: usize = 128;
+
+/// Anchor custom error codes, in `ProvenanceError` declaration order from 6000
+const INVALID_PUBLIC_OUTPUTS: u32 = 6001;
+const STRING_TOO_LONG: u32 = 6002;
+#[cfg(not(feature = "skip-authority-check"))]
+const UNAUTHORIZED: u32 = 6004;
+const ATTESTATION_REPLAY: u32 = 6013;
+#[cfg(feature = "skip-authority-check")]
+const NO_IDENTITY: u32 = 6014;
+#[cfg(feature = "skip-authority-check")]
+const NOT_SUBMITTER: u32 = 6015;
+#[cfg(feature = "skip-authority-check")]
+const ALREADY_REVOKED: u32 = 6016;
+#[cfg(feature = "skip-authority-check")]
+const IMMUTABLE_FIELD: u32 = 6017;
+
+/// Must match `IDENTITY_VALIDITY_SECS` in constants.rs
+#[cfg(feature = "skip-authority-check")]
+const IDENTITY_VALIDITY_SECS: i64 = 365 * 86400;
+
+/// System program error when `init` targets an account that already exists
+const ACCOUNT_ALREADY_IN_USE: u32 = 0;
+
+/// Anchor's generated entrypoint needs `'info` on the account slice;
+/// program-test hands out a shorter borrow, so leak a copy for the test.
+fn entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    provenance_attestation::entry(program_id, accounts, data)
+}
+
+fn program_test() -> ProgramTest {
+    ProgramTest::new(
+        "provenance_attestation",
+        provenance_attestation::ID,
+        processor!(entry),
+    )
+}
+
+async fn start() -> ProgramTestContext {
+    program_test().start_with_context().await
+}
+
+fn attestation_pda(content_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ATTESTATION_SEED, content_hash],
+        &provenance_attestation::ID,
+    )
+    .0
+}
+
+fn nonce_pda(content_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[NONCE_SEED, content_hash], &provenance_attestation::ID).0
+}
+
+fn event_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &provenance_attestation::ID).0
+}
+
+fn revoked_pda(api_key_hash: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[REVOKED_SEED, api_key_hash], &provenance_attestation::ID).0
+}
+
+/// Bincode PublicOutputs for `content_hash`: has_c2pa, TrustLevel::Official,
+/// ValidationState::Verified, then 6 length-prefixed strings
+fn public_outputs(content_hash: [u8; 32]) -> Vec<u8> {
+    let mut data = content_hash.to_vec();
+    data.push(1);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    for s in ["", "OpenAI", "", "", "", ""] {
+        data.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        data.extend_from_slice(s.as_bytes());
+    }
+    data
+}
+
+fn submit_attestation_ix(authority: &Pubkey, content_hash: [u8; 32], issuer: &str) -> Instruction {
+    submit_identity_attestation_ix(authority, content_hash, issuer, "")
+}
+
+fn submit_identity_attestation_ix(
+    authority: &Pubkey,
+    content_hash: [u8; 32],
+    issuer: &str,
+    email_domain: &str,
+) -> Instruction {
+    let data = provenance_attestation::instruction::SubmitAttestation {
+        content_hash,
+        has_c2pa: true,
+        trust_list_match: "official".to_string(),
+        validation_state: "Trusted".to_string(),
+        digital_source_type: String::new(),
+        issuer: issuer.to_string(),
+        common_name: "Test Signer".to_string(),
+        software_agent: "bankrun".to_string(),
+        signing_time: "2024-12-01T00:00:00Z".to_string(),
+        cert_fingerprint: String::new(),
+        email_domain: email_domain.to_string(),
+        email_hash: [0u8; 32],
+        wallets: vec![],
+        verifier_version: "0.1.0".to_string(),
+        trust_bundle_hash: String::new(),
+        manifest_hash: String::new(),
+        assertions_digest: String::new(),
+        software_agent_canonical: String::new(),
+    };
+    let accounts = provenance_attestation::accounts::SubmitAttestation {
+        attestation: attestation_pda(&content_hash),
+        authority: *authority,
+        system_program: system_program::ID,
+        instructions: sysvar::instructions::ID,
+        event_authority: event_authority_pda(),
+        program: provenance_attestation::ID,
+    };
+    Instruction {
+        program_id: provenance_attestation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn submit_proof_ix(
+    submitter: &Pubkey,
+    content_hash: [u8; 32],
+    public_inputs: Vec<u8>,
+) -> Instruction {
+    let data = provenance_attestation::instruction::SubmitProof {
+        proof: vec![],
+        public_inputs,
+        content_hash,
+        email_domain: String::new(),
+        email_hash: [0u8; 32],
+        wallets: vec![],
+        verifier_version: String::new(),
+        trust_bundle_hash: String::new(),
+        manifest_hash: String::new(),
+        assertions_digest: String::new(),
+        software_agent_canonical: String::new(),
+    };
+    let accounts = provenance_attestation::accounts::SubmitProof {
+        attestation: attestation_pda(&content_hash),
+        submitter: *submitter,
+        system_program: system_program::ID,
+        instructions: sysvar::instructions::ID,
+        nonce_counter: nonce_pda(&content_hash),
+        event_authority: event_authority_pda(),
+        program: provenance_attestation::ID,
+    };
+    Instruction {
+        program_id: provenance_attestation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn revoke_api_key_ix(authority: &Pubkey, api_key_hash: [u8; 32]) -> Instruction {
+    let data = provenance_attestation::instruction::RevokeApiKey { api_key_hash };
+    let accounts = provenance_attestation::accounts::RevokeApiKey {
+        revoked: revoked_pda(&api_key_hash),
+        authority: *authority,
+        system_program: system_program::ID,
+    };
+    Instruction {
+        program_id: provenance_attestation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+#[cfg(feature = "skip-authority-check")]
+fn renew_identity_ix(submitted_by: &Pubkey, content_hash: [u8; 32]) -> Instruction {
+    let data = provenance_attestation::instruction::RenewIdentity { content_hash };
+    let accounts = provenance_attestation::accounts::RenewIdentity {
+        attestation: attestation_pda(&content_hash),
+        submitted_by: *submitted_by,
+    };
+    Instruction {
+        program_id: provenance_attestation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn revoke_attestation_ix(authority: &Pubkey, content_hash: [u8; 32], reason: &str) -> Instruction {
+    let data = provenance_attestation::instruction::RevokeAttestation {
+        content_hash,
+        reason: reason.to_string(),
+    };
+    let accounts = provenance_attestation::accounts::RevokeAttestation {
+        attestation: attestation_pda(&content_hash),
+        authority: *authority,
+    };
+    Instruction {
+        program_id: provenance_attestation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Move an attestation to verifier "0.2.0" and trust bundle "bundle-v2"
+fn update_attestation_ix(
+    authority: &Pubkey,
+    content_hash: [u8; 32],
+    cert_fingerprint: &str,
+    trust_list_match: &str,
+) -> Instruction {
+    let data = provenance_attestation::instruction::UpdateAttestation {
+        content_hash,
+        cert_fingerprint: cert_fingerprint.to_string(),
+        verifier_version: "0.2.0".to_string(),
+        trust_bundle_hash: "bundle-v2".to_string(),
+        trust_list_match: trust_list_match.to_string(),
+    };
+    let accounts = provenance_attestation::accounts::UpdateAttestation {
+        attestation: attestation_pda(&content_hash),
+        authority: *authority,
+    };
+    Instruction {
+        program_id: provenance_attestation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn close_attestation_ix(
+    signer: &Pubkey,
+    content_hash: [u8; 32],
+    destination: &Pubkey,
+) -> Instruction {
+    let data = provenance_attestation::instruction::CloseAttestation { content_hash };
+    let accounts = provenance_attestation::accounts::CloseAttestation {
+        attestation: attestation_pda(&content_hash),
+        signer: *signer,
+        destination: *destination,
+    };
+    Instruction {
+        program_id: provenance_attestation::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+#[cfg(feature = "skip-authority-check")]
+async fn fetch_attestation(ctx: &mut ProgramTestContext, content_hash: &[u8; 32]) -> Attestation {
+    let account = ctx
+        .banks_client
+        .get_account(attestation_pda(content_hash))
+        .await
+        .unwrap()
+        .expect("attestation account should exist");
+    Attestation::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Send `ix` (after a compute budget bump) signed by `signer`, who also pays
+async fn send(
+    ctx: &mut ProgramTestContext,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), BanksClientError> {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+            ix,
+        ],
+        Some(&signer.pubkey()),
+        &[signer],
+        blockhash,
+    );
+    ctx.banks_client.process_transaction(tx).await
+}
+
+/// Like `send`, but `signer` signs `ix` while `payer` covers the fee
+async fn send_as(
+    ctx: &mut ProgramTestContext,
+    payer: &Keypair,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), BanksClientError> {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+            ix,
+        ],
+        Some(&payer.pubkey()),
+        &[payer, signer],
+        blockhash,
+    );
+    ctx.banks_client.process_transaction(tx).await
+}
+
+/// Extract the custom error code of the program instruction (index 1)
+fn custom_error(result: Result<(), BanksClientError>) -> u32 {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(1, InstructionError::Custom(code)) => code,
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn submit_attestation_with_authority_succeeds() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0x11u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+    )
+    .await
+    .unwrap();
+
+    let account = ctx
+        .banks_client
+        .get_account(attestation_pda(&content_hash))
+        .await
+        .unwrap()
+        .expect("attestation account should exist");
+    assert_eq!(account.owner, provenance_attestation::ID);
+    // 8-byte discriminator, then content_hash
+    assert_eq!(&account.data[8..40], &content_hash);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn submit_attestation_emits_cpi_event() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0x12u8; 32];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+            submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        ctx.last_blockhash,
+    );
+    let result = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    // emit_cpi! is a self-invocation one level down
+    let logs = result.metadata.expect("metadata").log_messages;
+    let self_cpi = format!("Program {} invoke [2]", provenance_attestation::ID);
+    assert!(logs.contains(&self_cpi), "no event CPI in logs: {logs:#?}");
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn submit_attestation_emits_log_events() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0x13u8; 32];
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+            submit_identity_attestation_ix(&payer.pubkey(), content_hash, "OpenAI", "example.com"),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        ctx.last_blockhash,
+    );
+    let result = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    // emit! writes "Program data: <base64(discriminator ++ borsh)>"
+    let logs = result.metadata.expect("metadata").log_messages;
+    let events: Vec<Vec<u8>> = logs
+        .iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .map(|data| BASE64.decode(data).expect("base64 event"))
+        .collect();
+    for disc in [
+        AttestationCreated::DISCRIMINATOR,
+        IdentityAttested::DISCRIMINATOR,
+    ] {
+        assert!(
+            events.iter().any(|e| e.starts_with(&disc)),
+            "missing event {disc:?} in logs: {logs:#?}"
+        );
+    }
+    let created = events
+        .iter()
+        .find(|e| e.starts_with(&AttestationCreated::DISCRIMINATOR))
+        .unwrap();
+    assert_eq!(&created[8..40], &content_hash);
+}
+
+#[cfg(not(feature = "skip-authority-check"))]
+#[tokio::test]
+async fn submit_attestation_with_wrong_authority_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), [0x22u8; 32], "OpenAI"),
+    )
+    .await;
+    assert_eq!(custom_error(result), UNAUTHORIZED);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn submit_attestation_with_long_string_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let issuer = "x".repeat(MAX_STRING_LEN + 1);
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), [0x33u8; 32], &issuer),
+    )
+    .await;
+    assert_eq!(custom_error(result), STRING_TOO_LONG);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn duplicate_submit_attestation_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0x44u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+    )
+    .await
+    .unwrap();
+    // Different issuer so the bank doesn't dedupe it as the same transaction
+    let result = send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "Adobe"),
+    )
+    .await;
+    assert_eq!(custom_error(result), ACCOUNT_ALREADY_IN_USE);
+}
+
+#[cfg(feature = "skip-verification")]
+#[tokio::test]
+async fn submit_proof_with_truncated_public_outputs_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0x55u8; 32];
+
+    // content_hash + has_c2pa + both enum tags, then a string length prefix
+    // claiming more bytes than remain
+    let mut public_inputs = content_hash.to_vec();
+    public_inputs.push(1);
+    public_inputs.extend_from_slice(&0u32.to_le_bytes());
+    public_inputs.extend_from_slice(&0u32.to_le_bytes());
+    public_inputs.extend_from_slice(&100u64.to_le_bytes());
+    public_inputs.extend_from_slice(b"official");
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        submit_proof_ix(&payer.pubkey(), content_hash, public_inputs),
+    )
+    .await;
+    assert_eq!(custom_error(result), INVALID_PUBLIC_OUTPUTS);
+}
+
+#[cfg(feature = "skip-verification")]
+#[tokio::test]
+async fn submit_proof_increments_nonce_counter() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0x66u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_proof_ix(&payer.pubkey(), content_hash, public_outputs(content_hash)),
+    )
+    .await
+    .unwrap();
+
+    let counter = ctx
+        .banks_client
+        .get_account(nonce_pda(&content_hash))
+        .await
+        .unwrap()
+        .expect("nonce counter should exist");
+    // 8-byte discriminator, then count: u64
+    assert_eq!(&counter.data[8..16], &1u64.to_le_bytes());
+}
+
+/// A counter left behind by an earlier (since closed) attestation blocks a
+/// new `submit_proof` for the same hash.
+#[cfg(feature = "skip-verification")]
+#[tokio::test]
+async fn submit_proof_with_used_nonce_fails() {
+    let content_hash = [0x77u8; 32];
+    let (nonce, bump) =
+        Pubkey::find_program_address(&[NONCE_SEED, &content_hash], &provenance_attestation::ID);
+
+    let mut data = solana_sdk::hash::hash(b"account:NonceCounter").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.push(bump);
+
+    let mut test = program_test();
+    test.add_account(
+        nonce,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: provenance_attestation::ID,
+            ..Account::default()
+        },
+    );
+    let mut ctx = test.start_with_context().await;
+    let payer = ctx.payer.insecure_clone();
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        submit_proof_ix(&payer.pubkey(), content_hash, public_outputs(content_hash)),
+    )
+    .await;
+    assert_eq!(custom_error(result), ATTESTATION_REPLAY);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn revoke_api_key_creates_pda() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let api_key_hash = [0x88u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        revoke_api_key_ix(&payer.pubkey(), api_key_hash),
+    )
+    .await
+    .unwrap();
+
+    let account = ctx
+        .banks_client
+        .get_account(revoked_pda(&api_key_hash))
+        .await
+        .unwrap()
+        .expect("revoked account should exist");
+    assert_eq!(account.owner, provenance_attestation::ID);
+    // 8-byte discriminator, then api_key_hash
+    assert_eq!(&account.data[8..40], &api_key_hash);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn duplicate_revoke_api_key_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let api_key_hash = [0x99u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        revoke_api_key_ix(&payer.pubkey(), api_key_hash),
+    )
+    .await
+    .unwrap();
+    // A fresh blockhash keeps the bank from deduping it as the same transaction
+    ctx.get_new_latest_blockhash().await.unwrap();
+    let result = send(
+        &mut ctx,
+        &payer,
+        revoke_api_key_ix(&payer.pubkey(), api_key_hash),
+    )
+    .await;
+    assert_eq!(custom_error(result), ACCOUNT_ALREADY_IN_USE);
+}
+
+#[cfg(not(feature = "skip-authority-check"))]
+#[tokio::test]
+async fn revoke_api_key_with_wrong_authority_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        revoke_api_key_ix(&payer.pubkey(), [0xaau8; 32]),
+    )
+    .await;
+    assert_eq!(custom_error(result), UNAUTHORIZED);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn submit_attestation_with_domain_sets_expiry() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xb1u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_identity_attestation_ix(&payer.pubkey(), content_hash, "OpenAI", "example.com"),
+    )
+    .await
+    .unwrap();
+
+    let attestation = fetch_attestation(&mut ctx, &content_hash).await;
+    assert_eq!(
+        attestation.expiry_timestamp,
+        attestation.timestamp + IDENTITY_VALIDITY_SECS
+    );
+    assert_eq!(attestation.renewal_count, 0);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn renew_identity_extends_expiry() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xb2u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_identity_attestation_ix(&payer.pubkey(), content_hash, "OpenAI", "example.com"),
+    )
+    .await
+    .unwrap();
+    let created = fetch_attestation(&mut ctx, &content_hash).await;
+
+    // Move the clock forward a day so the renewal lands later
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += 86400;
+    ctx.set_sysvar(&clock);
+
+    send(
+        &mut ctx,
+        &payer,
+        renew_identity_ix(&payer.pubkey(), content_hash),
+    )
+    .await
+    .unwrap();
+
+    let renewed = fetch_attestation(&mut ctx, &content_hash).await;
+    assert_eq!(
+        renewed.expiry_timestamp,
+        clock.unix_timestamp + IDENTITY_VALIDITY_SECS
+    );
+    assert!(renewed.expiry_timestamp > created.expiry_timestamp);
+    assert_eq!(renewed.renewal_count, 1);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn renew_identity_without_domain_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xb3u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        renew_identity_ix(&payer.pubkey(), content_hash),
+    )
+    .await;
+    assert_eq!(custom_error(result), NO_IDENTITY);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn renew_identity_by_other_signer_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let other = Keypair::new();
+    let content_hash = [0xb4u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_identity_attestation_ix(&payer.pubkey(), content_hash, "OpenAI", "example.com"),
+    )
+    .await
+    .unwrap();
+
+    let result = send_as(
+        &mut ctx,
+        &payer,
+        &other,
+        renew_identity_ix(&other.pubkey(), content_hash),
+    )
+    .await;
+    assert_eq!(custom_error(result), NOT_SUBMITTER);
+}
+
+#[cfg(feature = "skip-verification")]
+#[tokio::test]
+async fn close_attestation_by_submitter_reclaims_rent() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let destination = Keypair::new().pubkey();
+    let content_hash = [0xc1u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_proof_ix(&payer.pubkey(), content_hash, public_outputs(content_hash)),
+    )
+    .await
+    .unwrap();
+    let rent = ctx
+        .banks_client
+        .get_account(attestation_pda(&content_hash))
+        .await
+        .unwrap()
+        .expect("attestation account should exist")
+        .lamports;
+
+    send(
+        &mut ctx,
+        &payer,
+        close_attestation_ix(&payer.pubkey(), content_hash, &destination),
+    )
+    .await
+    .unwrap();
+
+    let closed = ctx
+        .banks_client
+        .get_account(attestation_pda(&content_hash))
+        .await
+        .unwrap();
+    assert!(closed.is_none(), "attestation account should be gone");
+    let reclaimed = ctx.banks_client.get_balance(destination).await.unwrap();
+    assert_eq!(reclaimed, rent);
+}
+
+#[cfg(all(feature = "skip-verification", not(feature = "skip-authority-check")))]
+#[tokio::test]
+async fn close_attestation_by_other_signer_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let other = Keypair::new();
+    let content_hash = [0xc2u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_proof_ix(&payer.pubkey(), content_hash, public_outputs(content_hash)),
+    )
+    .await
+    .unwrap();
+
+    let result = send_as(
+        &mut ctx,
+        &payer,
+        &other,
+        close_attestation_ix(&other.pubkey(), content_hash, &other.pubkey()),
+    )
+    .await;
+    assert_eq!(custom_error(result), UNAUTHORIZED);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn revoke_attestation_marks_revoked() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xd1u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+    )
+    .await
+    .unwrap();
+    send(
+        &mut ctx,
+        &payer,
+        revoke_attestation_ix(&payer.pubkey(), content_hash, "manipulated"),
+    )
+    .await
+    .unwrap();
+
+    let attestation = fetch_attestation(&mut ctx, &content_hash).await;
+    assert!(attestation.revoked);
+    assert_eq!(attestation.revocation_reason, "manipulated");
+    // The record itself is untouched
+    assert_eq!(attestation.issuer, "OpenAI");
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn revoke_attestation_twice_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xd2u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+    )
+    .await
+    .unwrap();
+    send(
+        &mut ctx,
+        &payer,
+        revoke_attestation_ix(&payer.pubkey(), content_hash, "manipulated"),
+    )
+    .await
+    .unwrap();
+    ctx.get_new_latest_blockhash().await.unwrap();
+    let result = send(
+        &mut ctx,
+        &payer,
+        revoke_attestation_ix(&payer.pubkey(), content_hash, "manipulated"),
+    )
+    .await;
+    assert_eq!(custom_error(result), ALREADY_REVOKED);
+}
+
+#[cfg(all(feature = "skip-verification", not(feature = "skip-authority-check")))]
+#[tokio::test]
+async fn revoke_attestation_with_wrong_authority_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xd3u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_proof_ix(&payer.pubkey(), content_hash, public_outputs(content_hash)),
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        revoke_attestation_ix(&payer.pubkey(), content_hash, "manipulated"),
+    )
+    .await;
+    assert_eq!(custom_error(result), UNAUTHORIZED);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn update_attestation_changes_infrastructure_fields() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xe1u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+    )
+    .await
+    .unwrap();
+    let before = fetch_attestation(&mut ctx, &content_hash).await;
+    assert_eq!(before.last_updated, 0);
+
+    send(
+        &mut ctx,
+        &payer,
+        update_attestation_ix(&payer.pubkey(), content_hash, "", "curated"),
+    )
+    .await
+    .unwrap();
+
+    let attestation = fetch_attestation(&mut ctx, &content_hash).await;
+    assert_eq!(attestation.verifier_version, "0.2.0");
+    assert_eq!(attestation.trust_bundle_hash, "bundle-v2");
+    assert_eq!(attestation.trust_list_match, "curated");
+    assert!(attestation.last_updated > 0);
+    // Content fields are untouched
+    assert_eq!(attestation.issuer, "OpenAI");
+    assert_eq!(attestation.timestamp, before.timestamp);
+}
+
+#[cfg(feature = "skip-authority-check")]
+#[tokio::test]
+async fn update_attestation_rejects_changed_fingerprint() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xe2u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_attestation_ix(&payer.pubkey(), content_hash, "OpenAI"),
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        update_attestation_ix(&payer.pubkey(), content_hash, "abcd1234", "curated"),
+    )
+    .await;
+    assert_eq!(custom_error(result), IMMUTABLE_FIELD);
+}
+
+#[cfg(all(feature = "skip-verification", not(feature = "skip-authority-check")))]
+#[tokio::test]
+async fn update_attestation_with_wrong_authority_fails() {
+    let mut ctx = start().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0xe3u8; 32];
+
+    send(
+        &mut ctx,
+        &payer,
+        submit_proof_ix(&payer.pubkey(), content_hash, public_outputs(content_hash)),
+    )
+    .await
+    .unwrap();
+
+    let result = send(
+        &mut ctx,
+        &payer,
+        update_attestation_ix(&payer.pubkey(), content_hash, "", "curated"),
+    )
+    .await;
+    assert_eq!(custom_error(result), UNAUTHORIZED);
+}
+
+/// Discriminator derived the way the API does it: sha256(preimage)[..8].
+fn api_discriminator(preimage: &str) -> [u8; 8] {
+    let digest = anchor_lang::solana_program::hash::hash(preimage.as_bytes()).to_bytes();
+    digest[..8].try_into().unwrap()
+}
+
+/// The API derives discriminators as sha256("global:<ix>")[..8] and
+/// sha256("account:<Name>")[..8] (solana_tx.discriminator_for /
+/// account_discriminator). Check Anchor's generated values against that
+/// derivation for every discriminator the API uses.
+#[test]
+fn discriminators_match_api_derivation() {
+    use provenance_attestation::{instruction, state};
+
+    assert_eq!(
+        instruction::SubmitAttestation::DISCRIMINATOR,
+        api_discriminator("global:submit_attestation")
+    );
+    assert_eq!(
+        instruction::SubmitProof::DISCRIMINATOR,
+        api_discriminator("global:submit_proof")
+    );
+    assert_eq!(
+        instruction::RevokeApiKey::DISCRIMINATOR,
+        api_discriminator("global:revoke_api_key")
+    );
+    assert_eq!(
+        instruction::RevokeAttestation::DISCRIMINATOR,
+        api_discriminator("global:revoke_attestation")
+    );
+    assert_eq!(
+        state::Attestation::DISCRIMINATOR,
+        api_discriminator("account:Attestation")
+    );
+    assert_eq!(
+        state::RevokedApiKey::DISCRIMINATOR,
+        api_discriminator("account:RevokedApiKey")
+    );
+}
@@ -0,0 +1,82 @@
+//! Example of another program building on R3L attestations via CPI.
+//!
+//! `attest_with_proof` forwards an SP1 proof to
+//! `provenance_attestation::submit_proof`, then reads the new attestation
+//! in the same instruction, so the caller's own logic can depend on it.
+//! The attestation program still emits `AttestationCreatedCpi`; it shows up
+//! as an inner instruction beneath this program's invocation.
+
+use anchor_lang::prelude::*;
+use provenance_attestation::cpi::accounts::SubmitProof;
+use provenance_attestation::program::ProvenanceAttestation;
+use provenance_attestation::state::Attestation;
+
+declare_id!("GwrDt45J3tG79eHeiKyZLrFhYwpeNQJvoCFV8uhXFN6f");
+
+#[program]
+pub mod cpi_example {
+    use super::*;
+
+    /// Attest `content_hash` with a ZK proof, then log the stored trust signals.
+    pub fn attest_with_proof(
+        ctx: Context<AttestWithProof>,
+        proof: Vec<u8>,
+        public_inputs: Vec<u8>,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        let accounts = SubmitProof {
+            attestation: ctx.accounts.attestation.to_account_info(),
+            submitter: ctx.accounts.submitter.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            instructions: ctx.accounts.instructions.to_account_info(),
+            nonce_counter: ctx.accounts.nonce_counter.to_account_info(),
+            event_authority: ctx.accounts.event_authority.to_account_info(),
+            program: ctx.accounts.attestation_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.attestation_program.to_account_info(), accounts);
+        provenance_attestation::cpi::submit_proof(
+            cpi_ctx,
+            proof,
+            public_inputs,
+            content_hash,
+            String::new(),
+            [0u8; 32],
+            vec![],
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+        )?;
+
+        // Created inside the CPI, so deserialize it by hand
+        let info = ctx.accounts.attestation.to_account_info();
+        require_keys_eq!(*info.owner, provenance_attestation::ID);
+        let attestation = Attestation::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        msg!(
+            "Attested via CPI: trust_list_match={} proof_type={}",
+            attestation.trust_list_match,
+            attestation.proof_type
+        );
+
+        Ok(())
+    }
+}
+
+/// `provenance_attestation::SubmitProof`'s accounts, plus the program itself.
+#[derive(Accounts)]
+pub struct AttestWithProof<'info> {
+    /// CHECK: initialized by the attestation program, which checks the seeds
+    #[account(mut)]
+    pub attestation: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: instructions sysvar, address checked by the attestation program
+    pub instructions: UncheckedAccount<'info>,
+    /// CHECK: initialized by the attestation program, which checks the seeds
+    #[account(mut)]
+    pub nonce_counter: UncheckedAccount<'info>,
+    /// CHECK: the attestation program's `#[event_cpi]` authority PDA
+    pub event_authority: UncheckedAccount<'info>,
+    pub attestation_program: Program<'info, ProvenanceAttestation>,
+}
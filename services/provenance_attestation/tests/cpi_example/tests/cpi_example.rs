@@ -0,0 +1,105 @@
+//! Runs the example program and the attestation program together in a
+//! BanksClient bank.
+//!
+//! Run from tests/cpi_example:
+//!   cargo test --features skip-verification
+
+use anchor_lang::prelude::AccountInfo;
+use anchor_lang::solana_program::{entrypoint::ProgramResult, sysvar};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, instruction::Instruction, pubkey::Pubkey,
+    signature::Signer, transaction::Transaction,
+};
+
+/// Anchor's generated entrypoints need `'info` on the account slice;
+/// program-test hands out a shorter borrow, so leak a copy for the test.
+fn attestation_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    provenance_attestation::entry(program_id, accounts, data)
+}
+
+fn example_entry(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    cpi_example::entry(program_id, accounts, data)
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &provenance_attestation::ID).0
+}
+
+/// Bincode PublicOutputs for `content_hash`: has_c2pa + 8 length-prefixed strings
+fn public_outputs(content_hash: [u8; 32]) -> Vec<u8> {
+    let mut data = content_hash.to_vec();
+    data.push(1);
+    for s in ["official", "Verified", "", "OpenAI", "", "", "", ""] {
+        data.extend_from_slice(&(s.len() as u64).to_le_bytes());
+        data.extend_from_slice(s.as_bytes());
+    }
+    data
+}
+
+#[tokio::test]
+async fn attest_with_proof_creates_attestation_via_cpi() {
+    let mut test = ProgramTest::new(
+        "provenance_attestation",
+        provenance_attestation::ID,
+        processor!(attestation_entry),
+    );
+    test.add_program("cpi_example", cpi_example::ID, processor!(example_entry));
+    let mut ctx = test.start_with_context().await;
+    let payer = ctx.payer.insecure_clone();
+    let content_hash = [0x33u8; 32];
+    let attestation = pda(&[b"attestation", &content_hash]);
+
+    let data = cpi_example::instruction::AttestWithProof {
+        proof: vec![],
+        public_inputs: public_outputs(content_hash),
+        content_hash,
+    };
+    let accounts = cpi_example::accounts::AttestWithProof {
+        attestation,
+        submitter: payer.pubkey(),
+        system_program: system_program::ID,
+        instructions: sysvar::instructions::ID,
+        nonce_counter: pda(&[b"nonce", &content_hash]),
+        event_authority: pda(&[b"__event_authority"]),
+        attestation_program: provenance_attestation::ID,
+    };
+    let ix = Instruction {
+        program_id: cpi_example::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+            ix,
+        ],
+        Some(&payer.pubkey()),
+        &[&payer],
+        ctx.last_blockhash,
+    );
+    let result = ctx
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    let account = ctx
+        .banks_client
+        .get_account(attestation)
+        .await
+        .unwrap()
+        .expect("attestation account should exist");
+    assert_eq!(account.owner, provenance_attestation::ID);
+
+    // example -> submit_proof [2] -> AttestationCreatedCpi self-CPI [3]
+    let logs = result.metadata.expect("metadata").log_messages;
+    for depth in [2, 3] {
+        let invoke = format!("Program {} invoke [{depth}]", provenance_attestation::ID);
+        assert!(logs.contains(&invoke), "missing {invoke:?} in {logs:#?}");
+    }
+}
@@ -5,6 +5,25 @@ import { expect } from "chai";
 
 const ATTESTATION_SEED = Buffer.from("attestation");
 
+// Must match Attestation::SPACE in state.rs
+const MAX_WALLETS = 5;
+const ATTESTATION_SPACE =
+  8 + 32 + 1 + 14 * (4 + 128) + 32 + 8 + 1 + 32 + 32 + 64 +
+  8 + 4 + 1 + (4 + 128) + 8 + (4 + (MAX_WALLETS - 1) * 32) + (4 + (MAX_WALLETS - 1) * 64) +
+  (4 + 128);
+
+// prover_shared::TrustLevel / ValidationState names, in variant order
+const TRUST_LEVELS: readonly string[] = ["official", "curated", "untrusted"];
+const VALIDATION_STATES: readonly string[] = [
+  "Verified",
+  "SignatureOnly",
+  "ExpiredCertificate",
+  "Revoked",
+  "InvalidKeyUsage",
+  "None",
+  "ParseError",
+];
+
 /**
  * Encode a PublicOutputs struct in bincode 1.x format.
  * Must match the on-chain parser in lib.rs and the SP1 guest's commit format.
@@ -12,7 +31,8 @@ const ATTESTATION_SEED = Buffer.from("attestation");
  * Layout:
  * - [u8; 32]: raw bytes (content_hash)
  * - bool: 1 byte (has_c2pa)
- * - 8x String: u64 LE length prefix + UTF-8 bytes
+ * - TrustLevel, ValidationState: u32 LE variant index each
+ * - 6x String: u64 LE length prefix + UTF-8 bytes
  */
 function encodeBincodePublicOutputs(outputs: {
   contentHash: Buffer;
@@ -34,10 +54,18 @@ function encodeBincodePublicOutputs(outputs: {
   // has_c2pa: bool - 1 byte
   parts.push(Buffer.from([outputs.hasC2pa ? 1 : 0]));
 
-  // 8 String fields: u64 LE length + UTF-8 bytes
+  // trust_list_match, validation_state: u32 LE variant index
+  for (const [name, variants] of [
+    [outputs.trustListMatch, TRUST_LEVELS],
+    [outputs.validationState, VALIDATION_STATES],
+  ] as const) {
+    const tagBuf = Buffer.alloc(4);
+    tagBuf.writeUInt32LE(variants.indexOf(name));
+    parts.push(tagBuf);
+  }
+
+  // 6 String fields: u64 LE length + UTF-8 bytes
   for (const s of [
-    outputs.trustListMatch,
-    outputs.validationState,
     outputs.digitalSourceType,
     outputs.issuer,
     outputs.commonName,
@@ -55,6 +83,29 @@ function encodeBincodePublicOutputs(outputs: {
   return Buffer.concat(parts);
 }
 
+/**
+ * Decode the `emit!` events (`AttestationCreated`, `IdentityAttested`)
+ * from a confirmed transaction's `Program data:` log lines.
+ */
+async function fetchEvents(
+  program: Program,
+  signature: string
+): Promise<{ name: string; data: any }[]> {
+  const tx = await program.provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  return Array.from(parser.parseLogs(tx?.meta?.logMessages ?? []));
+}
+
+/** The single event named `name` in `events`. */
+function findEvent(events: { name: string; data: any }[], name: string): any {
+  const matches = events.filter((e) => e.name === name);
+  expect(matches, `${name} events`).to.have.length(1);
+  return matches[0].data;
+}
+
 describe("provenance_attestation", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
@@ -71,6 +122,9 @@ describe("provenance_attestation", () => {
     program.programId
   );
 
+  // Signature of the first submitProof, for decoding its events
+  let submitProofTx: string;
+
   it("submits a proof and stores an attestation", async () => {
     // Empty proof: skip-verification feature active in test builds
     const proof = Buffer.alloc(0);
@@ -78,7 +132,7 @@ describe("provenance_attestation", () => {
       contentHash,
       hasC2pa: true,
       trustListMatch: "official",
-      validationState: "Trusted",
+      validationState: "Verified",
       digitalSourceType:
         "http://cv.iptc.org/newscodes/digitalsourcetype/trainedAlgorithmicMedia",
       issuer: "OpenAI",
@@ -103,6 +157,7 @@ describe("provenance_attestation", () => {
       .rpc();
 
     console.log("    tx:", tx);
+    submitProofTx = tx;
 
     // Fetch and verify — Anchor uses camelCase field names
     const attestation: any = await (
@@ -114,7 +169,7 @@ describe("provenance_attestation", () => {
     );
     expect(attestation.hasC2Pa).to.be.true;
     expect(attestation.trustListMatch).to.equal("official");
-    expect(attestation.validationState).to.equal("Trusted");
+    expect(attestation.validationState).to.equal("Verified");
     expect(attestation.issuer).to.equal("OpenAI");
     expect(attestation.commonName).to.equal("Truepic Lens CLI in Sora");
     expect(attestation.softwareAgent).to.equal("GPT-4o");
@@ -126,12 +181,30 @@ describe("provenance_attestation", () => {
     expect(attestation.timestamp.toNumber()).to.be.greaterThan(0);
   });
 
+  it("emits AttestationCreated for indexers", async () => {
+    const events = await fetchEvents(program, submitProofTx);
+
+    const created = findEvent(events, "AttestationCreated");
+    expect(Buffer.from(created.contentHash).toString("hex")).to.equal(
+      contentHash.toString("hex")
+    );
+    expect(created.proofType).to.equal("zk_groth16");
+    expect(created.trustListMatch).to.equal("official");
+    expect(created.submittedBy.toBase58()).to.equal(
+      provider.wallet.publicKey.toBase58()
+    );
+    expect(created.timestamp.toNumber()).to.be.greaterThan(0);
+
+    // No email domain was submitted, so no identity event
+    expect(events.filter((e) => e.name === "IdentityAttested")).to.be.empty;
+  });
+
   it("rejects duplicate attestation for same content hash", async () => {
     const publicInputs = encodeBincodePublicOutputs({
       contentHash,
       hasC2pa: true,
       trustListMatch: "official",
-      validationState: "Trusted",
+      validationState: "Verified",
       digitalSourceType: "",
       issuer: "OpenAI",
       commonName: "Test",
@@ -168,7 +241,7 @@ describe("provenance_attestation", () => {
     const publicInputs = encodeBincodePublicOutputs({
       contentHash: mismatchContentHash,
       hasC2pa: false,
-      trustListMatch: "",
+      trustListMatch: "untrusted",
       validationState: "None",
       digitalSourceType: "",
       issuer: "",
@@ -203,10 +276,10 @@ describe("provenance_attestation", () => {
     const publicInputs = encodeBincodePublicOutputs({
       contentHash: badHash,
       hasC2pa: false,
-      trustListMatch: "x".repeat(129), // exceeds MAX_STRING_LEN
-      validationState: "Valid",
+      trustListMatch: "untrusted",
+      validationState: "SignatureOnly",
       digitalSourceType: "",
-      issuer: "",
+      issuer: "x".repeat(129), // exceeds MAX_STRING_LEN
       commonName: "",
       softwareAgent: "",
       signingTime: "",
@@ -227,4 +300,119 @@ describe("provenance_attestation", () => {
       expect(e.toString()).to.include("StringTooLong");
     }
   });
+
+  it("reallocate is a no-op for an attestation already at current size", async () => {
+    await (program.methods as any)
+      .reallocateAttestation(Array.from(contentHash))
+      .accounts({
+        attestation: attestationPda,
+        authority: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const info = await provider.connection.getAccountInfo(attestationPda);
+    expect(info!.data.length).to.equal(ATTESTATION_SPACE);
+
+    // Existing fields are untouched
+    const attestation: any = await (
+      program.account as any
+    ).attestation.fetch(attestationPda);
+    expect(attestation.trustListMatch).to.equal("official");
+    expect(attestation.issuer).to.equal("OpenAI");
+  });
+
+  it("rejects reallocating a PDA that holds no attestation", async () => {
+    const emptyHash = Buffer.alloc(32, 0x11);
+    const [emptyPda] = PublicKey.findProgramAddressSync(
+      [ATTESTATION_SEED, emptyHash],
+      program.programId
+    );
+
+    try {
+      await (program.methods as any)
+        .reallocateAttestation(Array.from(emptyHash))
+        .accounts({
+          attestation: emptyPda,
+          authority: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.toString()).to.include("InvalidAttestationAccount");
+    }
+  });
+
+  it("rejects more than MAX_WALLETS co-signing wallets", async () => {
+    const badHash = Buffer.alloc(32, 0x22);
+    const [badPda] = PublicKey.findProgramAddressSync(
+      [ATTESTATION_SEED, badHash],
+      program.programId
+    );
+
+    const publicInputs = encodeBincodePublicOutputs({
+      contentHash: badHash,
+      hasC2pa: false,
+      trustListMatch: "untrusted",
+      validationState: "None",
+      digitalSourceType: "",
+      issuer: "",
+      commonName: "",
+      softwareAgent: "",
+      signingTime: "",
+      certFingerprint: "",
+    });
+    const wallets = Array.from({ length: MAX_WALLETS + 1 }, () =>
+      anchor.web3.Keypair.generate().publicKey
+    );
+
+    try {
+      await (program.methods as any)
+        .submitProof(
+          Buffer.alloc(0),
+          publicInputs,
+          Array.from(badHash),
+          "",
+          Array.from(Buffer.alloc(32)),
+          wallets,
+          "",
+          ""
+        )
+        .accounts({
+          attestation: badPda,
+          submitter: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.toString()).to.include("TooManyWallets");
+    }
+  });
+
+  it("rejects public inputs over the length bound", async () => {
+    const badHash = Buffer.alloc(32, 0x33);
+    const [badPda] = PublicKey.findProgramAddressSync(
+      [ATTESTATION_SEED, badHash],
+      program.programId
+    );
+
+    // Must match MAX_PUBLIC_INPUTS_LEN in constants.rs
+    const publicInputs = Buffer.alloc(513);
+
+    try {
+      await (program.methods as any)
+        .submitProof(Buffer.alloc(0), publicInputs, Array.from(badHash))
+        .accounts({
+          attestation: badPda,
+          submitter: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.toString()).to.include("PublicInputsTooLarge");
+    }
+  });
 });
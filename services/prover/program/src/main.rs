@@ -3,9 +3,15 @@ sp1_zkvm::entrypoint!(main);
 
 use coset::{CborSerializable, CoseSign1, TaggedCborSerializable};
 use der::Decode;
-use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
-use prover_shared::{CryptoEvidence, PublicOutputs};
+use prover_shared::key_usage::check_key_usage;
+use prover_shared::sig::SigAlg;
+use prover_shared::timestamp::sig_tst_time;
+use prover_shared::{
+    normalize_issuer, verify_assertion_hashes, Bytes, ContentHash, CryptoEvidence,
+    PublicOutputs, TrustLevel, ValidationState,
+};
 use sha2::{Digest, Sha256};
+use x509_cert::crl::CertificateList;
 use x509_cert::Certificate;
 
 pub fn main() {
@@ -20,19 +26,71 @@ pub fn main() {
     sp1_zkvm::io::commit(&outputs);
 }
 
+/// CBOR-encode COSE Sig_structure1 `["Signature1", protected, h'', payload]`
+/// straight into one buffer. Going through `ciborium::Value` would copy the
+/// detached payload (the whole claim) just to serialize it.
+fn sig_structure1(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+    const CONTEXT: &[u8] = b"Signature1";
+
+    let mut out =
+        Vec::with_capacity(1 + 1 + CONTEXT.len() + 9 + protected.len() + 1 + 9 + payload.len());
+    out.push(0x84); // array(4)
+    cbor_header(&mut out, 3, CONTEXT.len()); // text string
+    out.extend_from_slice(CONTEXT);
+    cbor_header(&mut out, 2, protected.len()); // byte string
+    out.extend_from_slice(protected);
+    out.push(0x40); // external_aad: empty byte string for C2PA
+    cbor_header(&mut out, 2, payload.len()); // detached payload
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Append a CBOR item header for `major` type with length `len`, using the
+/// shortest encoding (as ciborium does).
+fn cbor_header(out: &mut Vec<u8>, major: u8, len: usize) {
+    let major = major << 5;
+    let len = len as u64;
+    if len < 24 {
+        out.push(major | len as u8);
+    } else if len <= u8::MAX as u64 {
+        out.extend_from_slice(&[major | 24, len as u8]);
+    } else if len <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else if len <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&len.to_be_bytes());
+    }
+}
+
+/// The `SigAlg` for a COSE protected-header `alg`, if the guest supports it.
+fn sig_alg(alg: Option<&coset::Algorithm>) -> Option<SigAlg> {
+    match alg? {
+        coset::Algorithm::Assigned(coset::iana::Algorithm::ES256) => Some(SigAlg::Es256),
+        coset::Algorithm::Assigned(coset::iana::Algorithm::ES384) => Some(SigAlg::Es384),
+        _ => None,
+    }
+}
+
 /// Outputs for files with no C2PA manifest.
-fn unsigned_outputs(content_hash: [u8; 32]) -> PublicOutputs {
+fn unsigned_outputs(content_hash: ContentHash) -> PublicOutputs {
     PublicOutputs {
         content_hash,
         has_c2pa: false,
-        trust_list_match: String::new(),
-        validation_state: "None".to_string(),
+        trust_list_match: TrustLevel::None,
+        validation_state: ValidationState::None,
         digital_source_type: String::new(),
         issuer: String::new(),
         common_name: String::new(),
         software_agent: String::new(),
         signing_time: String::new(),
         cert_fingerprint: String::new(),
+        assertions_hash_verified: false,
+        cert_valid_until: String::new(),
+        key_usage_ok: false,
     }
 }
 
@@ -46,16 +104,12 @@ fn verify_and_extract(evidence: &CryptoEvidence) -> PublicOutputs {
         Err(_) => return unsigned_outputs(evidence.asset_hash),
     };
 
-    // 2. Verify algorithm is ES256 (only supported algorithm for now)
-    let is_es256 = matches!(
-        cose.protected.header.alg,
-        Some(coset::Algorithm::Assigned(coset::iana::Algorithm::ES256))
-    );
-    if !is_es256 {
+    // 2. Only ES256 and ES384 are supported
+    let Some(alg) = sig_alg(cose.protected.header.alg.as_ref()) else {
         return unsigned_outputs(evidence.asset_hash);
-    }
+    };
 
-    // 3. Parse leaf certificate and extract P-256 public key
+    // 3. Parse leaf certificate and extract its public key
     if evidence.cert_chain_der.is_empty() {
         return unsigned_outputs(evidence.asset_hash);
     }
@@ -71,12 +125,7 @@ fn verify_and_extract(evidence: &CryptoEvidence) -> PublicOutputs {
         .subject_public_key
         .raw_bytes();
 
-    let verifying_key = match VerifyingKey::from_sec1_bytes(pk_bytes) {
-        Ok(k) => k,
-        Err(_) => return unsigned_outputs(evidence.asset_hash),
-    };
-
-    // 4. Build COSE Sig_structure1 and verify ECDSA P-256 signature
+    // 4. Build COSE Sig_structure1 and verify the ECDSA signature
     //    Sig_structure1 = ["Signature1", protected, external_aad, payload]
     let protected_bytes = cose
         .protected
@@ -85,24 +134,9 @@ fn verify_and_extract(evidence: &CryptoEvidence) -> PublicOutputs {
         .map(|v| v.as_slice())
         .unwrap_or(&[]);
 
-    let sig_structure = ciborium::Value::Array(vec![
-        ciborium::Value::Text("Signature1".to_string()),
-        ciborium::Value::Bytes(protected_bytes.to_vec()),
-        ciborium::Value::Bytes(Vec::new()), // external_aad (empty for C2PA)
-        ciborium::Value::Bytes(evidence.claim_cbor.clone()), // detached payload
-    ]);
+    let tbs = sig_structure1(protected_bytes, &evidence.claim_cbor);
 
-    let mut tbs = Vec::new();
-    if ciborium::ser::into_writer(&sig_structure, &mut tbs).is_err() {
-        return unsigned_outputs(evidence.asset_hash);
-    }
-
-    let signature = match Signature::from_slice(&cose.signature) {
-        Ok(s) => s,
-        Err(_) => return unsigned_outputs(evidence.asset_hash),
-    };
-
-    if verifying_key.verify(&tbs, &signature).is_err() {
+    if !alg.verify(pk_bytes, &tbs, &cose.signature) {
         return unsigned_outputs(evidence.asset_hash);
     }
 
@@ -115,23 +149,44 @@ fn verify_and_extract(evidence: &CryptoEvidence) -> PublicOutputs {
         &evidence.curated_trust_anchors_der,
     );
 
-    let validation_state = if trust_list_match == "untrusted" {
-        "SignatureOnly".to_string()
-    } else {
-        "Verified".to_string()
-    };
-
     // 6. Extract issuer org and common name from verified leaf cert
     let (issuer, common_name) = extract_cert_names(&leaf_cert);
 
     // 7. Extract claim_generator from verified claim CBOR
     let software_agent = extract_claim_generator(&evidence.claim_cbor);
 
-    // 8. Extract digitalSourceType and signing time from assertion boxes
-    let (digital_source_type, signing_time) =
-        extract_from_actions(&evidence.assertion_boxes);
+    // 8. Check assertions against the hashes committed in the signed claim
+    let assertions_hash_verified =
+        verify_assertion_hashes(&evidence.claim_cbor, &evidence.assertion_superboxes)
+            .unwrap_or(false);
+
+    // 9. Extract digitalSourceType and signing time from assertion boxes.
+    //    The actions' "when" only counts once the assertions are verified
+    let (digital_source_type, actions_when) = extract_from_actions(&evidence.assertion_boxes);
+    let signed_at = signing_time(&cose, &actions_when, assertions_hash_verified);
+    let signing_time = signed_at.and_then(rfc3339).unwrap_or_default();
+
+    // A valid signature from a revoked cert, one not allowed to sign claims,
+    // or one without a trusted signing time inside its validity window
+    // doesn't count as verified, whatever the trust list says
+    let revoked = !evidence
+        .crl_bytes
+        .iter()
+        .all(|crl| check_not_revoked(&leaf_cert, crl));
+    let key_usage_ok = check_key_usage(&leaf_cert);
+    let validation_state = if revoked {
+        ValidationState::Revoked
+    } else if !key_usage_ok {
+        ValidationState::InvalidKeyUsage
+    } else if !check_validity_period(&leaf_cert, signed_at) {
+        ValidationState::ExpiredCertificate
+    } else if trust_list_match.is_trusted() {
+        ValidationState::Verified
+    } else {
+        ValidationState::SignatureOnly
+    };
 
-    // 9. Compute SHA-256 fingerprint of the leaf signing certificate
+    // 10. Compute SHA-256 fingerprint of the leaf signing certificate
     let cert_fingerprint = hex::encode(Sha256::digest(&evidence.cert_chain_der[0]));
 
     PublicOutputs {
@@ -145,7 +200,74 @@ fn verify_and_extract(evidence: &CryptoEvidence) -> PublicOutputs {
         software_agent,
         signing_time,
         cert_fingerprint,
+        assertions_hash_verified,
+        cert_valid_until: cert_valid_until(&leaf_cert),
+        key_usage_ok,
+    }
+}
+
+/// Signing time in unix seconds that the signature vouches for: the
+/// actions assertion's `when` if the assertion hashes verified, else the
+/// RFC 3161 time stamp in the COSE protected header (`sigTst2`/`sigTst`).
+/// None if neither is available.
+fn signing_time(cose: &CoseSign1, actions_when: &str, assertions_verified: bool) -> Option<i64> {
+    let from_actions = assertions_verified
+        .then(|| chrono::DateTime::parse_from_rfc3339(actions_when).ok())
+        .flatten()
+        .map(|t| t.timestamp());
+    from_actions.or_else(|| {
+        cose.protected
+            .header
+            .rest
+            .iter()
+            .find(|(label, _)| {
+                matches!(label, coset::Label::Text(l) if l == "sigTst2" || l == "sigTst")
+            })
+            .and_then(|(_, value)| sig_tst_time(value))
+    })
+}
+
+/// Whether `signed_at` lies within the certificate's notBefore..=notAfter
+/// window. The guest has no trusted clock, so without a signing time the
+/// check fails rather than passing unchecked.
+fn check_validity_period(cert: &Certificate, signed_at: Option<i64>) -> bool {
+    let Some(signed_at) = signed_at else {
+        return false;
+    };
+    let validity = &cert.tbs_certificate.validity;
+    (unix_secs(&validity.not_before)..=unix_secs(&validity.not_after)).contains(&signed_at)
+}
+
+fn rfc3339(unix_secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(unix_secs, 0)
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+/// False if `crl_bytes` is a DER CRL from the cert's issuer that lists the
+/// cert's serial number. CRLs from other issuers, or that don't parse, say
+/// nothing about the cert and return true. The CRL's own signature isn't
+/// checked; the host chooses which CRLs to supply.
+fn check_not_revoked(cert: &Certificate, crl_bytes: &[u8]) -> bool {
+    let Ok(crl) = CertificateList::from_der(crl_bytes) else {
+        return true;
+    };
+    if crl.tbs_cert_list.issuer != cert.tbs_certificate.issuer {
+        return true;
     }
+    !crl.tbs_cert_list
+        .revoked_certificates
+        .iter()
+        .flatten()
+        .any(|entry| entry.serial_number == cert.tbs_certificate.serial_number)
+}
+
+/// The certificate's notAfter as RFC 3339, e.g. "2050-11-01T16:27:13Z".
+fn cert_valid_until(cert: &Certificate) -> String {
+    rfc3339(unix_secs(&cert.tbs_certificate.validity.not_after)).unwrap_or_default()
+}
+
+fn unix_secs(time: &x509_cert::time::Time) -> i64 {
+    time.to_unix_duration().as_secs() as i64
 }
 
 /// Match the root certificate (last in chain) against trust anchor lists.
@@ -153,30 +275,31 @@ fn determine_trust_level(
     cert_chain: &[Vec<u8>],
     official_anchors: &[Vec<u8>],
     curated_anchors: &[Vec<u8>],
-) -> String {
+) -> TrustLevel {
     let root_der = match cert_chain.last() {
         Some(r) => r,
-        None => return "untrusted".to_string(),
+        None => return TrustLevel::Untrusted,
     };
 
     for anchor in official_anchors {
         if anchor == root_der {
-            return "official".to_string();
+            return TrustLevel::Official;
         }
     }
 
     for anchor in curated_anchors {
         if anchor == root_der {
-            return "curated".to_string();
+            return TrustLevel::Curated;
         }
     }
 
-    "untrusted".to_string()
+    TrustLevel::Untrusted
 }
 
 /// Extract Organization (issuer) and Common Name from an X.509 certificate.
+/// The issuer is normalized so CA spelling variants group together on-chain.
 fn extract_cert_names(cert: &Certificate) -> (String, String) {
-    let issuer_org = extract_rdn_attr(&cert.tbs_certificate.issuer, OID_ORG);
+    let issuer_org = normalize_issuer(&extract_rdn_attr(&cert.tbs_certificate.issuer, OID_ORG));
     let cn = extract_rdn_attr(&cert.tbs_certificate.subject, OID_CN);
 
     // Fallback: if subject CN is empty, try issuer CN
@@ -246,13 +369,13 @@ fn extract_claim_generator(claim_cbor: &[u8]) -> String {
 /// Extract digitalSourceType and signing time from C2PA actions assertion.
 /// Looks for "c2pa.actions" or "c2pa.actions.v2" assertion boxes.
 /// Actions CBOR has: { "actions": [{ "action": "...", "digitalSourceType": "...", "when": "..." }] }
-fn extract_from_actions(assertion_boxes: &[(String, Vec<u8>)]) -> (String, String) {
+fn extract_from_actions(assertion_boxes: &[(String, Bytes)]) -> (String, String) {
     for (label, data) in assertion_boxes {
         if !label.starts_with("c2pa.actions") {
             continue;
         }
 
-        let cbor: ciborium::Value = match ciborium::de::from_reader(data.as_slice()) {
+        let cbor: ciborium::Value = match ciborium::de::from_reader(&data[..]) {
             Ok(v) => v,
             Err(_) => continue,
         };
@@ -0,0 +1,110 @@
+#!/usr/bin/env python3
+"""Regenerate the container fixtures used by the src/jumbf_extract.rs tests.
+
+Each wraps the C2PA manifest store from data/test_files/a2.png in a
+different container, placed the way that format carries it. Only the
+container structure is real: image payloads are placeholders, and the
+manifest's hard binding doesn't match the new bytes.
+
+  signed.heic       HEIC, C2PA uuid box inside the top-level meta box
+  signed_hevc.mp4   HEVC video (hvc1 brand), C2PA uuid box under moov/udta
+  signed.avif       AVIF, application/c2pa mime item stored in meta/idat
+  unsigned.avif     AVIF with no manifest
+  signed.webp       WEBP, manifest in a C2PA RIFF chunk
+"""
+
+import struct
+from pathlib import Path
+
+HERE = Path(__file__).resolve().parent
+SOURCE = HERE / "../../../../../data/test_files/a2.png"
+
+C2PA_UUID = bytes.fromhex("d8fec3d61b0e483c92975828877ec481")
+
+
+def manifest_store() -> bytes:
+    """JUMBF from the PNG's caBX chunk."""
+    data = SOURCE.read_bytes()
+    pos = 8
+    while pos + 12 <= len(data):
+        (length,) = struct.unpack(">I", data[pos:pos + 4])
+        if data[pos + 4:pos + 8] == b"caBX":
+            return data[pos + 8:pos + 8 + length]
+        pos += 12 + length
+    raise SystemExit(f"no caBX chunk in {SOURCE}")
+
+
+def box(box_type: bytes, *content: bytes) -> bytes:
+    payload = b"".join(content)
+    return struct.pack(">I", 8 + len(payload)) + box_type + payload
+
+
+def full_box(box_type: bytes, version: int, *content: bytes) -> bytes:
+    return box(box_type, struct.pack(">I", version << 24), *content)
+
+
+def ftyp(major: bytes, *compatible: bytes) -> bytes:
+    return box(b"ftyp", major, struct.pack(">I", 0), *compatible)
+
+
+def c2pa_uuid_box(jumbf: bytes) -> bytes:
+    # uuid, FullBox header, purpose, aux uuid offset, manifest store
+    return box(b"uuid", C2PA_UUID, bytes(4), b"manifest\0", bytes(8), jumbf)
+
+
+def hdlr(handler: bytes) -> bytes:
+    return full_box(b"hdlr", 0, bytes(4), handler, bytes(12), b"\0")
+
+
+def heic(jumbf: bytes) -> bytes:
+    meta = full_box(b"meta", 0, hdlr(b"pict"), c2pa_uuid_box(jumbf))
+    return ftyp(b"heic", b"mif1", b"heic") + meta + box(b"mdat", b"placeholder image data")
+
+
+def hevc_mp4(jumbf: bytes) -> bytes:
+    moov = box(b"moov", full_box(b"mvhd", 0, bytes(96)), box(b"udta", c2pa_uuid_box(jumbf)))
+    return ftyp(b"isom", b"isom", b"hvc1") + moov + box(b"mdat", b"placeholder video data")
+
+
+def avif(jumbf: bytes | None) -> bytes:
+    children = [hdlr(b"pict")]
+    if jumbf is not None:
+        # infe v2: item_ID 1, protection index 0, type mime, name, content type
+        infe = full_box(b"infe", 2, struct.pack(">HH", 1, 0), b"mime", b"C2PA\0", b"application/c2pa\0")
+        iinf = full_box(b"iinf", 0, struct.pack(">H", 1), infe)
+        # iloc v1: 4-byte offset and length, no base offset or index;
+        # item 1 built from idat (construction method 1), two extents
+        half = len(jumbf) // 2
+        iloc = full_box(
+            b"iloc", 1,
+            struct.pack(">HH", 0x4400, 1),
+            struct.pack(">HHHH", 1, 1, 0, 2),
+            struct.pack(">II", 0, half),
+            struct.pack(">II", half, len(jumbf) - half),
+        )
+        children += [iinf, iloc, box(b"idat", jumbf)]
+    meta = full_box(b"meta", 0, *children)
+    return ftyp(b"avif", b"mif1", b"avif") + meta + box(b"mdat", b"placeholder image data")
+
+
+def riff_chunk(fourcc: bytes, payload: bytes) -> bytes:
+    pad = b"\0" if len(payload) % 2 else b""
+    return fourcc + struct.pack("<I", len(payload)) + payload + pad
+
+
+def webp(jumbf: bytes) -> bytes:
+    body = b"WEBP" + riff_chunk(b"VP8L", b"placeholder image data") + riff_chunk(b"C2PA", jumbf)
+    return b"RIFF" + struct.pack("<I", len(body)) + body
+
+
+def main():
+    jumbf = manifest_store()
+    (HERE / "signed.heic").write_bytes(heic(jumbf))
+    (HERE / "signed_hevc.mp4").write_bytes(hevc_mp4(jumbf))
+    (HERE / "signed.avif").write_bytes(avif(jumbf))
+    (HERE / "unsigned.avif").write_bytes(avif(None))
+    (HERE / "signed.webp").write_bytes(webp(jumbf))
+
+
+if __name__ == "__main__":
+    main()
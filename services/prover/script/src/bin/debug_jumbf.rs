@@ -1,19 +1,24 @@
-//! Debug tool: dump the JUMBF box tree from a PNG file's caBX chunk.
+//! Debug tool: dump the JUMBF box tree from a PNG file's caBX chunk,
+//! then the active manifest's claim and assertion CBOR in diagnostic
+//! notation (RFC 8949 §8).
 //!
-//! Usage: cargo run --bin debug_jumbf -- <path-to-png>
+//! Usage: cargo run --bin debug_jumbf -- [--raw-cbor] <path-to-png>
+//!
+//! `--raw-cbor` prints CBOR payloads as hex instead of diagnostic notation.
 
 use std::env;
 use std::fs;
 use std::process;
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let path = if args.len() > 1 {
-        &args[1]
-    } else {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let raw_cbor = args.iter().any(|a| a == "--raw-cbor");
+    let path = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(String::as_str)
         // Default to chatgpt.png sample
-        "../../data/samples/chatgpt.png"
-    };
+        .unwrap_or("../../data/samples/chatgpt.png");
 
     eprintln!("Reading: {path}");
 
@@ -46,8 +51,8 @@ fn main() {
     dump_boxes(&jumbf, 0);
 
     eprintln!();
-    eprintln!("=== DEBUG: C2PA Claim CBOR Keys ===");
-    debug_claim_cbor(&jumbf);
+    eprintln!("=== DEBUG: C2PA Claim and Assertion CBOR ===");
+    debug_claim_cbor(&jumbf, raw_cbor);
 }
 
 // ---------------------------------------------------------------------------
@@ -359,7 +364,7 @@ fn read_null_terminated(data: &[u8]) -> (String, usize) {
 // DEBUG: Extract and parse c2pa.claim.v2 CBOR to show keys
 // ---------------------------------------------------------------------------
 
-fn debug_claim_cbor(jumbf_data: &[u8]) {
+fn debug_claim_cbor(jumbf_data: &[u8], raw_cbor: bool) {
     // The JUMBF data is structured as:
     // - Top-level jumb (c2pa manifest store)
     //   - Child jumb boxes (each is a manifest)
@@ -385,20 +390,26 @@ fn debug_claim_cbor(jumbf_data: &[u8]) {
     let active_manifest_data = manifests.last().unwrap();
 
     // 2. Find the c2pa.claim box in the manifest
-    let claim_cbor = find_claim_cbor(active_manifest_data);
-    let claim_cbor = match claim_cbor {
-        Some(data) => data,
-        None => {
-            eprintln!("No c2pa.claim cbor box found in active manifest");
-            return;
+    match find_claim_cbor(active_manifest_data) {
+        Some(claim_cbor) => {
+            eprintln!("Found c2pa.claim cbor box: {} bytes", claim_cbor.len());
+            eprintln!();
+            // 3. Parse the CBOR and print it
+            println!("--- c2pa.claim ---");
+            print_cbor(&claim_cbor, raw_cbor);
         }
-    };
+        None => eprintln!("No c2pa.claim cbor box found in active manifest"),
+    }
 
-    eprintln!("Found c2pa.claim cbor box: {} bytes", claim_cbor.len());
+    // 4. Every CBOR assertion in the active manifest's assertion store
+    let assertions = find_assertion_cbor(active_manifest_data);
     eprintln!();
-
-    // 3. Parse the CBOR and dump keys
-    parse_and_dump_cbor_keys(&claim_cbor);
+    eprintln!("Found {} CBOR assertion(s)", assertions.len());
+    for (label, cbor) in &assertions {
+        println!();
+        println!("--- {label} ({} bytes) ---", cbor.len());
+        print_cbor(cbor, raw_cbor);
+    }
 }
 
 /// Skip to the content of the top-level c2pa store box
@@ -577,11 +588,90 @@ fn find_cbor_box(jumb_content: &[u8]) -> Option<Vec<u8>> {
     None
 }
 
-/// Parse CBOR data and dump all keys and values
-fn parse_and_dump_cbor_keys(cbor_data: &[u8]) {
-    use ciborium::Value;
+/// Split a box sequence into (type, content) pairs
+fn child_boxes(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut boxes = Vec::new();
+    let mut pos = 0;
+
+    while pos + 8 <= data.len() {
+        let raw_size = u32::from_be_bytes(
+            data[pos..pos + 4].try_into().unwrap_or([0; 4]),
+        ) as usize;
+        let box_type: [u8; 4] = data[pos + 4..pos + 8].try_into().unwrap_or([0; 4]);
+
+        let (header_size, box_size) = if raw_size == 1 && pos + 16 <= data.len() {
+            let ext_size = u64::from_be_bytes(
+                data[pos + 8..pos + 16].try_into().unwrap_or([0; 8]),
+            ) as usize;
+            (16, ext_size)
+        } else if raw_size == 0 {
+            (8, data.len() - pos)
+        } else {
+            (8, raw_size)
+        };
+
+        if box_size < header_size || pos + box_size > data.len() {
+            break;
+        }
+
+        boxes.push((box_type, &data[pos + header_size..pos + box_size]));
+        pos += box_size;
+    }
+
+    boxes
+}
+
+/// UUID (hex, no spaces) and label of a jumb superbox's leading jumd box
+fn jumd_uuid_and_label(jumb_content: &[u8]) -> Option<(String, String)> {
+    let (box_type, jumd) = *child_boxes(jumb_content).first()?;
+    if &box_type != b"jumd" || jumd.len() < 17 {
+        return None;
+    }
+    let uuid_hex: String = hex_string(&jumd[..16]).chars().filter(|c| !c.is_whitespace()).collect();
+    let label = if jumd[16] & 0x02 != 0 {
+        read_null_terminated(&jumd[17..]).0
+    } else {
+        String::new()
+    };
+    Some((uuid_hex, label))
+}
+
+/// Find (label, cbor) for each CBOR assertion in a manifest's assertion store
+fn find_assertion_cbor(manifest_data: &[u8]) -> Vec<(String, Vec<u8>)> {
+    // c2pa.assertions UUID: 6332617300110010800000aa00389b71
+    let store = child_boxes(manifest_data)
+        .into_iter()
+        .filter(|(t, _)| t == b"jumb")
+        .find(|(_, content)| {
+            jumd_uuid_and_label(content)
+                .is_some_and(|(uuid, _)| uuid == "6332617300110010800000aa00389b71")
+        });
+    let Some((_, store)) = store else {
+        return Vec::new();
+    };
 
-    let value: Value = match ciborium::from_reader(cbor_data) {
+    child_boxes(store)
+        .into_iter()
+        .filter(|(t, _)| t == b"jumb")
+        .filter_map(|(_, assertion)| {
+            let (_, label) = jumd_uuid_and_label(assertion)?;
+            let cbor = child_boxes(assertion)
+                .into_iter()
+                .find(|(t, _)| t == b"cbor")?
+                .1;
+            Some((label, cbor.to_vec()))
+        })
+        .collect()
+}
+
+/// Print CBOR as diagnostic notation, or as hex with `--raw-cbor`
+fn print_cbor(cbor_data: &[u8], raw_cbor: bool) {
+    if raw_cbor {
+        println!("{}", hex::encode(cbor_data));
+        return;
+    }
+
+    let value: ciborium::Value = match ciborium::from_reader(cbor_data) {
         Ok(v) => v,
         Err(e) => {
             eprintln!("Failed to parse CBOR: {e}");
@@ -591,64 +681,58 @@ fn parse_and_dump_cbor_keys(cbor_data: &[u8]) {
         }
     };
 
-    eprintln!("Successfully parsed CBOR!");
-    eprintln!();
-    dump_cbor_value(&value, 0);
+    println!("{}", to_diagnostic(&value, 0));
 }
 
-/// Recursively dump CBOR value structure
-fn dump_cbor_value(value: &ciborium::Value, depth: usize) {
+/// Format a CBOR value in diagnostic notation (RFC 8949 §8):
+/// `42`, `"text"`, `h'deadbeef'`, `[1, 2]`, `{1: "a"}`, `tag(value)`.
+/// Arrays and maps holding nested containers are broken across lines.
+fn to_diagnostic(value: &ciborium::Value, indent: usize) -> String {
     use ciborium::Value;
-    let indent = "  ".repeat(depth);
+
+    let pad = "  ".repeat(indent + 1);
+    let close_pad = "  ".repeat(indent);
+    let is_container = |v: &Value| matches!(v, Value::Array(_) | Value::Map(_) | Value::Tag(..));
 
     match value {
-        Value::Map(map) => {
-            eprintln!("{indent}Map with {} entries:", map.len());
-            for (key, val) in map {
-                eprint!("{indent}  Key: ");
-                match key {
-                    Value::Text(s) => eprint!("\"{s}\""),
-                    Value::Integer(i) => eprint!("{i:?}"),
-                    Value::Bytes(b) => eprint!("Bytes({})", hex_string(b)),
-                    _ => eprint!("{key:?}"),
-                }
-                eprintln!();
-
-                eprint!("{indent}  Value: ");
-                match val {
-                    Value::Text(s) => eprintln!("\"{s}\""),
-                    Value::Integer(i) => eprintln!("{i:?}"),
-                    Value::Bool(b) => eprintln!("{b}"),
-                    Value::Null => eprintln!("null"),
-                    Value::Bytes(b) => eprintln!("Bytes({} bytes): {}", b.len(), hex_string(&b[..b.len().min(40)])),
-                    Value::Array(arr) => {
-                        eprintln!("Array[{}]:", arr.len());
-                        for (i, item) in arr.iter().enumerate() {
-                            eprintln!("{indent}    [{i}]:");
-                            dump_cbor_value(item, depth + 3);
-                        }
-                    }
-                    Value::Map(_) => {
-                        eprintln!();
-                        dump_cbor_value(val, depth + 2);
-                    }
-                    _ => eprintln!("{val:?}"),
-                }
-                eprintln!();
-            }
+        Value::Integer(i) => i128::from(*i).to_string(),
+        Value::Bytes(b) => format!("h'{}'", hex::encode(b)),
+        Value::Text(s) => serde_json::to_string(s).unwrap_or_else(|_| format!("{s:?}")),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        Value::Float(f) if f.is_nan() => "NaN".to_string(),
+        Value::Float(f) if f.is_infinite() => {
+            if *f > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
         }
-        Value::Array(arr) => {
-            eprintln!("{indent}Array with {} items:", arr.len());
-            for (i, item) in arr.iter().enumerate() {
-                eprintln!("{indent}  [{i}]:");
-                dump_cbor_value(item, depth + 2);
-            }
+        Value::Float(f) => format!("{f:?}"),
+        Value::Tag(tag, inner) => format!("{tag}({})", to_diagnostic(inner, indent)),
+        Value::Array(items) if items.is_empty() => "[]".to_string(),
+        Value::Array(items) if !items.iter().any(is_container) => {
+            let parts: Vec<_> = items.iter().map(|v| to_diagnostic(v, indent)).collect();
+            format!("[{}]", parts.join(", "))
+        }
+        Value::Array(items) => {
+            let parts: Vec<_> = items
+                .iter()
+                .map(|v| format!("{pad}{}", to_diagnostic(v, indent + 1)))
+                .collect();
+            format!("[\n{}\n{close_pad}]", parts.join(",\n"))
+        }
+        Value::Map(entries) if entries.is_empty() => "{}".to_string(),
+        Value::Map(entries) => {
+            let parts: Vec<_> = entries
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{pad}{}: {}",
+                        to_diagnostic(k, indent + 1),
+                        to_diagnostic(v, indent + 1)
+                    )
+                })
+                .collect();
+            format!("{{\n{}\n{close_pad}}}", parts.join(",\n"))
         }
-        Value::Text(s) => eprintln!("{indent}\"{s}\""),
-        Value::Integer(i) => eprintln!("{indent}{i:?}"),
-        Value::Bool(b) => eprintln!("{indent}{b}"),
-        Value::Null => eprintln!("{indent}null"),
-        Value::Bytes(b) => eprintln!("{indent}Bytes({} bytes)", b.len()),
-        _ => eprintln!("{indent}{value:?}"),
+        // ciborium::Value is non_exhaustive
+        _ => format!("{value:?}"),
     }
 }
@@ -1,24 +1,55 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Parser;
-use prover_script::jumbf_extract;
-use prover_shared::PublicOutputs;
+use prover_script::{crl, jumbf_extract};
+use prover_shared::{CryptoEvidence, PublicOutputs};
+use serde::Deserialize;
 use sp1_prover::components::CpuProverComponents;
-use sp1_sdk::{include_elf, HashableKey, Prover, ProverClient, SP1ProofMode, SP1Stdin};
+use sp1_sdk::{
+    include_elf, HashableKey, Prover, ProverClient, SP1ProofMode, SP1ProvingKey, SP1Stdin,
+    SP1VerifyingKey,
+};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::time::Instant;
 
 const ELF: &[u8] = include_elf!("provenance-program");
 
+const MB: u64 = 1024 * 1024;
+
+/// Exit code when execution exceeds `--max-cycles`, distinct from proving failures
+const EXIT_TOO_MANY_CYCLES: i32 = 2;
+
+/// Evidence above this size still proves, but noticeably slower and costlier
+const EVIDENCE_WARN_BYTES: u64 = MB;
+
 #[derive(Parser)]
 #[command(about = "Generate a Groth16 proof of C2PA verification")]
 struct Args {
     /// Path to the media file to verify
+    #[arg(
+        long,
+        required_unless_present_any = ["stdin", "batch_file", "vkey_only"],
+        conflicts_with_all = ["stdin", "batch_file"]
+    )]
+    media: Option<String>,
+
+    /// Read raw media bytes from stdin instead of --media
+    #[arg(long, conflicts_with = "batch_file")]
+    stdin: bool,
+
+    /// JSONL file of {"media", "output", "json_out"} entries to prove with one prover setup
     #[arg(long)]
-    media: String,
+    batch_file: Option<String>,
+
+    /// Refuse batch files with more entries than this
+    #[arg(long, default_value_t = 100)]
+    max_batch_size: usize,
 
     /// Trust directory containing official/ and curated/ PEM subdirectories
     #[arg(long, env = "TRUST_DIR", default_value = "/data/trust")]
     trust_dir: String,
 
-    /// Output path for the proof file
+    /// Output path for the proof file ("-" for stdout)
     #[arg(long, default_value = "proof.bin")]
     output: String,
 
@@ -26,90 +57,361 @@ struct Args {
     #[arg(long)]
     mock: bool,
 
-    /// Write JSON sidecar with proof and public_values hex
+    /// Write JSON sidecar with proof and public_values hex ("-" for stdout)
+    #[arg(long)]
+    json_out: Option<String>,
+
+    /// Abort instead of proving if serialized CryptoEvidence exceeds this many MB
     #[arg(long)]
+    max_evidence_mb: Option<u64>,
+
+    /// Don't download the leaf certificate's CRLs (revocation goes unchecked)
+    #[arg(long, env = "SKIP_CRL_FETCH")]
+    skip_crl: bool,
+
+    /// Abort (exit code 2) if execution exceeds this many cycles
+    #[arg(long, env = "MAX_PROVE_CYCLES", default_value_t = 100_000_000)]
+    max_cycles: u64,
+
+    /// Print the guest's vkey hash (as in SP1_VKEY_HASH) and exit without proving
+    #[arg(long, conflicts_with_all = ["media", "stdin", "batch_file"])]
+    vkey_only: bool,
+}
+
+/// One line of a `--batch-file`
+#[derive(Deserialize)]
+struct BatchEntry {
+    media: String,
+    output: String,
     json_out: Option<String>,
 }
 
+/// What to prove once the prover client is set up
+enum Job {
+    Single(SP1Stdin),
+    Batch(Vec<BatchEntry>),
+}
+
+/// Execution exceeded `--max-cycles`; proving was skipped
+#[derive(Debug)]
+struct TooManyCycles {
+    cycles: u64,
+    max_cycles: u64,
+}
+
+impl fmt::Display for TooManyCycles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "execution took {} cycles, over --max-cycles {}",
+            self.cycles, self.max_cycles
+        )
+    }
+}
+
+impl std::error::Error for TooManyCycles {}
+
 fn main() -> Result<()> {
-    sp1_sdk::utils::setup_logger();
     let args = Args::parse();
 
-    // Extract all cryptographic evidence from the media file
-    let evidence = jumbf_extract::extract_crypto_evidence(&args.media, &args.trust_dir)?;
-
-    println!("Asset hash: {}", hex::encode(evidence.asset_hash));
-    println!("Has manifest: {}", evidence.has_manifest);
-    println!("COSE signature: {} bytes", evidence.cose_sign1_bytes.len());
-    println!("Cert chain: {} cert(s)", evidence.cert_chain_der.len());
-    println!("Claim CBOR: {} bytes", evidence.claim_cbor.len());
+    if args.vkey_only {
+        let (_, vk) = ProverClient::builder().mock().build().setup(ELF);
+        println!("{}", vk.bytes32());
+        return Ok(());
+    }
 
-    // Write CryptoEvidence to SP1 stdin — guest derives everything from this
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&evidence);
+    let job = if let Some(batch_file) = &args.batch_file {
+        sp1_sdk::utils::setup_logger();
+        let entries = read_batch_file(batch_file, args.max_batch_size)?;
+        eprintln!("Batch: {} file(s) from {batch_file}", entries.len());
+        Job::Batch(entries)
+    } else {
+        single_job(&args)?
+    };
 
     // Create prover client and run
     if args.mock {
         let client = ProverClient::builder().mock().build();
-        run_prover(client, ELF, stdin, &args.output, args.json_out.as_deref())?;
+        run(client, job, &args)?;
     } else if std::env::var("SP1_PROVER").unwrap_or_default() == "cuda" {
-        println!("Using CUDA GPU prover (set via SP1_PROVER=cuda)");
+        eprintln!("Using CUDA GPU prover (set via SP1_PROVER=cuda)");
         let client = ProverClient::builder().cuda().build();
-        run_prover(client, ELF, stdin, &args.output, args.json_out.as_deref())?;
+        run(client, job, &args)?;
     } else {
-        println!("Using CPU prover (set SP1_PROVER=cuda for GPU)");
+        eprintln!("Using CPU prover (set SP1_PROVER=cuda for GPU)");
         let client = ProverClient::builder().cpu().build();
-        run_prover(client, ELF, stdin, &args.output, args.json_out.as_deref())?;
+        run(client, job, &args)?;
+    };
+
+    Ok(())
+}
+
+/// Extract evidence for a single `--media` or `--stdin` run
+fn single_job(args: &Args) -> Result<Job> {
+    let json_to_stdout = args.json_out.as_deref() == Some("-");
+    if args.output == "-" && json_to_stdout {
+        bail!("--output - and --json-out - cannot both write to stdout");
+    }
+    // Progress goes to stderr; keep stdout clean when it carries the proof or sidecar
+    if args.output != "-" && !json_to_stdout {
+        sp1_sdk::utils::setup_logger();
+    }
+
+    // Extract all cryptographic evidence from the media file
+    let evidence = if args.stdin {
+        let mut file_bytes = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut file_bytes)
+            .context("reading media from stdin")?;
+        let mime = infer::get(&file_bytes).map_or("unknown", |t| t.mime_type());
+        eprintln!("Read {} bytes from stdin ({mime})", file_bytes.len());
+        jumbf_extract::extract_crypto_evidence_from_bytes(&file_bytes, &args.trust_dir)?
+    } else {
+        let media = args
+            .media
+            .as_deref()
+            .expect("clap requires --media without --stdin");
+        jumbf_extract::extract_crypto_evidence(media, &args.trust_dir)?
     };
 
+    Ok(Job::Single(evidence_stdin(
+        &with_crls(evidence, args),
+        args.max_evidence_mb,
+    )?))
+}
+
+/// Parse a JSONL batch file, skipping blank lines
+fn read_batch_file(path: &str, max_batch_size: usize) -> Result<Vec<BatchEntry>> {
+    let file = std::fs::File::open(path).with_context(|| format!("opening batch file {path}"))?;
+    let mut entries = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: BatchEntry = serde_json::from_str(&line)
+            .with_context(|| format!("{path}:{}: invalid batch entry", i + 1))?;
+        if entry.output == "-" || entry.json_out.as_deref() == Some("-") {
+            bail!("{path}:{}: batch entries cannot write to stdout", i + 1);
+        }
+        entries.push(entry);
+    }
+    if entries.is_empty() {
+        bail!("batch file {path} has no entries");
+    }
+    if entries.len() > max_batch_size {
+        bail!(
+            "batch file {path} has {} entries, over --max-batch-size {max_batch_size}",
+            entries.len()
+        );
+    }
+    Ok(entries)
+}
+
+/// Attach the leaf certificate's CRLs unless `--skip-crl`
+fn with_crls(mut evidence: CryptoEvidence, args: &Args) -> CryptoEvidence {
+    if !args.skip_crl {
+        crl::attach_crls(&mut evidence);
+    }
+    evidence
+}
+
+/// Print evidence stats, enforce size limits, and write it to SP1 stdin
+fn evidence_stdin(evidence: &CryptoEvidence, max_evidence_mb: Option<u64>) -> Result<SP1Stdin> {
+    eprintln!("Asset hash: {}", evidence.asset_hash);
+    eprintln!("Has manifest: {}", evidence.has_manifest);
+    eprintln!("COSE signature: {} bytes", evidence.cose_sign1_bytes.len());
+    eprintln!("Cert chain: {} cert(s)", evidence.cert_chain_der.len());
+    eprintln!("Claim CBOR: {} bytes", evidence.claim_cbor.len());
+
+    check_evidence_size(evidence, max_evidence_mb)?;
+
+    // Write CryptoEvidence to SP1 stdin — guest derives everything from this
+    let mut stdin = SP1Stdin::new();
+    stdin.write(evidence);
+    Ok(stdin)
+}
+
+/// Log the serialized size of the evidence written to SP1 stdin and enforce
+/// `--max-evidence-mb`. Sizes are bincode sizes, matching `SP1Stdin::write`.
+fn check_evidence_size(evidence: &CryptoEvidence, max_mb: Option<u64>) -> Result<()> {
+    let cose_size = bincode::serialized_size(&evidence.cose_sign1_bytes)?;
+    let cert_chain_size = bincode::serialized_size(&evidence.cert_chain_der)?;
+    let assertion_size = bincode::serialized_size(&evidence.assertion_boxes)?;
+    let crl_size = bincode::serialized_size(&evidence.crl_bytes)?;
+    let total_size = bincode::serialized_size(evidence)?;
+    tracing::info!(
+        cose_size,
+        cert_chain_size,
+        assertion_size,
+        crl_size,
+        total_size,
+        "CryptoEvidence size"
+    );
+
+    if let Some(max_mb) = max_mb {
+        if total_size > max_mb.saturating_mul(MB) {
+            bail!("CryptoEvidence is {total_size} bytes, over --max-evidence-mb {max_mb}");
+        }
+    }
+    if total_size > EVIDENCE_WARN_BYTES {
+        tracing::warn!(
+            total_size,
+            "CryptoEvidence exceeds 1 MB; proving will be slow and expensive"
+        );
+    }
     Ok(())
 }
 
-fn run_prover(
-    client: impl Prover<CpuProverComponents>,
-    elf: &[u8],
-    stdin: SP1Stdin,
+/// Set up proving keys once, then prove the single input or every batch entry
+fn run(client: impl Prover<CpuProverComponents>, job: Job, args: &Args) -> Result<()> {
+    let (pk, vk) = client.setup(ELF);
+    eprintln!("vkey hash: {}", vk.bytes32());
+
+    match job {
+        Job::Single(stdin) => {
+            let result = prove_one(
+                &client,
+                &pk,
+                &vk,
+                &stdin,
+                &args.output,
+                args.json_out.as_deref(),
+                args.max_cycles,
+            );
+            if let Err(e) = &result {
+                if e.is::<TooManyCycles>() {
+                    eprintln!("error: {e}; not proving");
+                    std::process::exit(EXIT_TOO_MANY_CYCLES);
+                }
+            }
+            result
+        }
+        Job::Batch(entries) => run_batch(&client, &pk, &vk, &entries, args),
+    }
+}
+
+/// Prove batch entries sequentially. A failing entry is logged and skipped;
+/// the run fails at the end if any entry did.
+fn run_batch(
+    client: &impl Prover<CpuProverComponents>,
+    pk: &SP1ProvingKey,
+    vk: &SP1VerifyingKey,
+    entries: &[BatchEntry],
+    args: &Args,
+) -> Result<()> {
+    let batch_start = Instant::now();
+    let mut failed = Vec::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        eprintln!("=== [{}/{}] {} ===", i + 1, entries.len(), entry.media);
+        let start = Instant::now();
+        let result = jumbf_extract::extract_crypto_evidence(&entry.media, &args.trust_dir)
+            .map(|evidence| with_crls(evidence, args))
+            .and_then(|evidence| evidence_stdin(&evidence, args.max_evidence_mb))
+            .and_then(|stdin| {
+                prove_one(
+                    client,
+                    pk,
+                    vk,
+                    &stdin,
+                    &entry.output,
+                    entry.json_out.as_deref(),
+                    args.max_cycles,
+                )
+            });
+        let elapsed = start.elapsed().as_secs_f64();
+        match result {
+            Ok(()) => eprintln!("[{}/{}] ok in {elapsed:.1}s", i + 1, entries.len()),
+            Err(e) => {
+                eprintln!(
+                    "[{}/{}] failed in {elapsed:.1}s: {e:#}",
+                    i + 1,
+                    entries.len()
+                );
+                failed.push((entry.media.as_str(), e));
+            }
+        }
+    }
+
+    eprintln!("--- Batch Summary ---");
+    eprintln!(
+        "{} succeeded, {} failed in {:.1}s",
+        entries.len() - failed.len(),
+        failed.len(),
+        batch_start.elapsed().as_secs_f64()
+    );
+    for (media, e) in &failed {
+        eprintln!("failed: {media}: {e:#}");
+    }
+    if !failed.is_empty() {
+        bail!("{} of {} batch entries failed", failed.len(), entries.len());
+    }
+    Ok(())
+}
+
+fn prove_one(
+    client: &impl Prover<CpuProverComponents>,
+    pk: &SP1ProvingKey,
+    vk: &SP1VerifyingKey,
+    stdin: &SP1Stdin,
     output_path: &str,
     json_out: Option<&str>,
+    max_cycles: u64,
 ) -> Result<()> {
-    let (pk, vk) = client.setup(elf);
-    println!("vkey hash: {}", vk.bytes32());
-
     // Execute first to check correctness
-    let (mut public_values, report) = client.execute(elf, &stdin)?;
-    println!("executed in {} cycles", report.total_instruction_count());
+    let (mut public_values, report) = client.execute(ELF, stdin)?;
+    let cycles = report.total_instruction_count();
+    eprintln!("executed in {} cycles", cycles);
+    if cycles > max_cycles {
+        return Err(TooManyCycles { cycles, max_cycles }.into());
+    }
 
     let outputs: PublicOutputs = public_values.read();
-    println!("--- Public Outputs ---");
-    println!("content_hash: {}", hex::encode(outputs.content_hash));
-    println!("has_c2pa: {}", outputs.has_c2pa);
-    println!("trust_list_match: {}", outputs.trust_list_match);
-    println!("validation_state: {}", outputs.validation_state);
-    println!("issuer: {}", outputs.issuer);
-    println!("common_name: {}", outputs.common_name);
-    println!("software_agent: {}", outputs.software_agent);
-    println!("digital_source_type: {}", outputs.digital_source_type);
-    println!("signing_time: {}", outputs.signing_time);
-    println!("cert_fingerprint: {}", outputs.cert_fingerprint);
+    eprintln!("--- Public Outputs ---");
+    eprintln!("content_hash: {}", outputs.content_hash);
+    eprintln!("has_c2pa: {}", outputs.has_c2pa);
+    eprintln!("trust_list_match: {}", outputs.trust_list_match);
+    eprintln!("validation_state: {}", outputs.validation_state);
+    eprintln!("issuer: {}", outputs.issuer);
+    eprintln!("common_name: {}", outputs.common_name);
+    eprintln!("software_agent: {}", outputs.software_agent);
+    eprintln!("digital_source_type: {}", outputs.digital_source_type);
+    eprintln!("signing_time: {}", outputs.signing_time);
+    eprintln!("cert_fingerprint: {}", outputs.cert_fingerprint);
+    eprintln!(
+        "assertions_hash_verified: {}",
+        outputs.assertions_hash_verified
+    );
+    eprintln!("cert_valid_until: {}", outputs.cert_valid_until);
+    eprintln!("key_usage_ok: {}", outputs.key_usage_ok);
 
     // Generate Groth16 proof
-    println!("generating Groth16 proof...");
-    let proof = client.prove(&pk, &stdin, SP1ProofMode::Groth16)?;
+    eprintln!("generating Groth16 proof...");
+    let proof = client.prove(pk, stdin, SP1ProofMode::Groth16)?;
 
     // Verify locally
+    eprintln!("verifying proof...");
     client
-        .verify(&proof, &vk)
-        .expect("proof verification failed");
-    println!("proof verified locally");
+        .verify(&proof, vk)
+        .context("proof verification failed")?;
+    eprintln!("proof verified locally");
 
     // Save proof
-    proof.save(output_path)?;
-    println!("proof saved to {}", output_path);
+    if output_path == "-" {
+        let mut stdout = std::io::stdout().lock();
+        bincode::serialize_into(&mut stdout, &proof)?;
+        stdout.flush()?;
+        eprintln!("proof written to stdout");
+    } else {
+        proof.save(output_path)?;
+        eprintln!("proof saved to {}", output_path);
+    }
 
     let proof_bytes = proof.bytes();
     let public_values_bytes = proof.public_values.as_slice();
-    println!("proof bytes: {} bytes", proof_bytes.len());
-    println!("public values: {} bytes", public_values_bytes.len());
+    eprintln!("proof bytes: {} bytes", proof_bytes.len());
+    eprintln!("public values: {} bytes", public_values_bytes.len());
 
     // Write JSON sidecar if requested
     if let Some(json_path) = json_out {
@@ -117,8 +419,14 @@ fn run_prover(
             "proof": hex::encode(&proof_bytes),
             "public_values": hex::encode(public_values_bytes),
         });
-        std::fs::write(json_path, serde_json::to_string_pretty(&sidecar)?)?;
-        println!("JSON sidecar written to {}", json_path);
+        let sidecar = serde_json::to_string_pretty(&sidecar)?;
+        if json_path == "-" {
+            println!("{sidecar}");
+            eprintln!("JSON sidecar written to stdout");
+        } else {
+            std::fs::write(json_path, sidecar)?;
+            eprintln!("JSON sidecar written to {}", json_path);
+        }
     }
 
     Ok(())
@@ -0,0 +1,50 @@
+//! Off-chain Groth16 pre-verification, mirroring the on-chain `submit_proof` check.
+//!
+//! Usage: verify_proof --proof <hex> --public-inputs <hex>
+//!
+//! Exits 0 if the proof verifies against the pinned `SP1_VKEY_HASH`, 1 otherwise.
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+// Same vkey hash the Solana program checks against, so a stale constant
+// fails here rather than on-chain.
+#[path = "../../../../provenance_attestation/programs/provenance_attestation/src/constants.rs"]
+#[allow(dead_code)]
+mod constants;
+
+use constants::SP1_VKEY_HASH;
+
+#[derive(Parser)]
+#[command(about = "Verify a Groth16 proof the same way the Solana program does")]
+struct Args {
+    /// Proof bytes (hex)
+    #[arg(long)]
+    proof: String,
+
+    /// SP1 public values (hex)
+    #[arg(long)]
+    public_inputs: String,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let proof = hex::decode(args.proof.trim_start_matches("0x"))?;
+    let public_inputs = hex::decode(args.public_inputs.trim_start_matches("0x"))?;
+
+    eprintln!("proof: {} bytes", proof.len());
+    eprintln!("public inputs: {} bytes", public_inputs.len());
+    eprintln!("vkey hash: {SP1_VKEY_HASH}");
+
+    sp1_solana::verify_proof(
+        &proof,
+        &public_inputs,
+        SP1_VKEY_HASH,
+        sp1_solana::GROTH16_VK_5_0_0_BYTES,
+    )
+    .map_err(|e| anyhow!("proof verification failed: {e:?}"))?;
+
+    println!("proof verified");
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! CRL download for the leaf signing certificate, so the zkVM guest can
+//! check revocation inside the proof.
+//!
+//! URLs come from the leaf's cRLDistributionPoints extension (OID
+//! 2.5.29.31). Only http(s) URIs are fetched; LDAP and other schemes are
+//! skipped. A failed download is logged and leaves revocation unchecked
+//! for that distribution point rather than failing the proof.
+
+use anyhow::{Context, Result};
+use der::{oid::ObjectIdentifier, Decode};
+use prover_shared::CryptoEvidence;
+use std::time::Duration;
+use x509_cert::ext::pkix::name::{DistributionPointName, GeneralName};
+use x509_cert::ext::pkix::CrlDistributionPoints;
+use x509_cert::Certificate;
+
+const OID_CRL_DISTRIBUTION_POINTS: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.31");
+
+const CRL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Larger CRLs are skipped; they would dominate the guest's cycle count
+const MAX_CRL_BYTES: u64 = 10 * 1024 * 1024;
+
+/// http(s) CRL distribution point URLs in a DER certificate.
+pub fn distribution_urls(cert_der: &[u8]) -> Vec<String> {
+    let Ok(cert) = Certificate::from_der(cert_der) else {
+        return Vec::new();
+    };
+    let Some(ext) = cert
+        .tbs_certificate
+        .extensions
+        .iter()
+        .flatten()
+        .find(|e| e.extn_id == OID_CRL_DISTRIBUTION_POINTS)
+    else {
+        return Vec::new();
+    };
+    let Ok(points) = CrlDistributionPoints::from_der(ext.extn_value.as_bytes()) else {
+        return Vec::new();
+    };
+
+    points
+        .0
+        .iter()
+        .filter_map(|dp| match &dp.distribution_point {
+            Some(DistributionPointName::FullName(names)) => Some(names),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|name| match name {
+            GeneralName::UniformResourceIdentifier(uri) => Some(uri.to_string()),
+            _ => None,
+        })
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+        .collect()
+}
+
+/// Download one DER CRL.
+pub fn fetch_crl(url: &str) -> Result<Vec<u8>> {
+    let mut resp = ureq::get(url)
+        .config()
+        .timeout_global(Some(CRL_TIMEOUT))
+        .build()
+        .call()
+        .with_context(|| format!("fetching CRL {url}"))?;
+    resp.body_mut()
+        .with_config()
+        .limit(MAX_CRL_BYTES)
+        .read_to_vec()
+        .with_context(|| format!("reading CRL {url} (max {MAX_CRL_BYTES} bytes)"))
+}
+
+/// Fill `evidence.crl_bytes` with the CRLs named by the leaf certificate.
+pub fn attach_crls(evidence: &mut CryptoEvidence) {
+    let Some(leaf) = evidence.cert_chain_der.first() else {
+        return;
+    };
+    for url in distribution_urls(leaf) {
+        match fetch_crl(&url) {
+            Ok(crl) => {
+                eprintln!("Fetched CRL {url} ({} bytes)", crl.len());
+                evidence.crl_bytes.push(crl);
+            }
+            Err(e) => eprintln!("Warning: {e:#}; revocation not checked against it"),
+        }
+    }
+}
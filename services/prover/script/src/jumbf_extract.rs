@@ -1,41 +1,104 @@
-//! Extract C2PA cryptographic evidence from media files (PNG, JPEG, MP4, PDF).
+//! Extract C2PA cryptographic evidence from media files (PNG, JPEG, WEBP, MP4, HEIF, AVIF, PDF).
 //!
 //! Supported formats:
 //!   PNG  — caBX chunk(s) contain raw JUMBF data
 //!   JPEG — APP11 (0xFFEB) marker segments per ISO 19566-5 (JUMBF-in-JPEG)
-//!   MP4  — top-level BMFF `uuid` box with C2PA UUID
+//!   WEBP — `C2PA` chunk in the RIFF/WEBP container
+//!   MP4  — top-level BMFF `uuid` box with C2PA UUID (or under `moov/udta`)
+//!   HEVC — as MP4, detected by `hvc1`/`hev1` in `ftyp` brands
+//!   HEIF — C2PA `uuid` box inside the top-level `meta` box, detected by the
+//!          `ftyp` major brand (`heic`, `heix`, `mif1`, `msf1`)
+//!   AVIF — `ftyp` major brand `avif`/`avis`; as HEIF, or a `mime` item of
+//!          type `application/c2pa` located through `meta/iinf` + `iloc`
 //!   PDF  — Associated File with /AFRelationship /C2PA_Manifest (ISO 32000-2)
 //!
 //! Pipeline: media → JUMBF → box tree → claim CBOR + COSE_Sign1 +
 //! assertion boxes, then extract certificate chain from COSE unprotected header.
 
 use anyhow::{anyhow, Context, Result};
-use prover_shared::CryptoEvidence;
+use prover_shared::{assertion_hash_mismatches, Bytes, ContentHash, CryptoEvidence};
+pub use prover_shared::{verify_assertion_hashes, AssertionHashError};
 use sha2::{Digest, Sha256};
 use std::fs;
+use std::io::Read;
+use std::ops::Deref;
 use std::path::Path;
 
+/// Files at least this large are memory-mapped instead of read (`MMAP_THRESHOLD_MB`)
+const DEFAULT_MMAP_THRESHOLD_MB: u64 = 100;
+
+/// Media file contents: memory-mapped for large files (multi-GB video)
+/// so they aren't copied into RAM, otherwise read into a buffer.
+/// Derefs to `&[u8]`, so extractors and hashing work on either.
+pub enum MmapOrVec {
+    Mmap(memmap2::Mmap),
+    Vec(Vec<u8>),
+}
+
+impl Deref for MmapOrVec {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mmap(m) => m,
+            Self::Vec(v) => v,
+        }
+    }
+}
+
+/// Open a media file, mapping it when `MMAP_ENABLED` (default true) and
+/// its size reaches `MMAP_THRESHOLD_MB`.
+pub fn read_media(media_path: &str) -> Result<MmapOrVec> {
+    let mut file =
+        fs::File::open(media_path).with_context(|| format!("reading media file: {media_path}"))?;
+    let len = file.metadata()?.len();
+
+    let mmap_enabled = std::env::var("MMAP_ENABLED").map_or(true, |v| {
+        !matches!(v.to_ascii_lowercase().as_str(), "false" | "0" | "no")
+    });
+    let threshold_mb = std::env::var("MMAP_THRESHOLD_MB")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MMAP_THRESHOLD_MB);
+
+    if mmap_enabled && len >= threshold_mb.saturating_mul(1024 * 1024) {
+        // SAFETY: the map is read-only and dropped before we return evidence;
+        // truncating the file underneath us would fault, same as any mmap reader.
+        let map = unsafe { memmap2::Mmap::map(&file) }
+            .with_context(|| format!("memory-mapping media file: {media_path}"))?;
+        eprintln!("Memory-mapped {len} bytes");
+        return Ok(MmapOrVec::Mmap(map));
+    }
+
+    let mut buf = Vec::with_capacity(len as usize);
+    file.read_to_end(&mut buf)
+        .with_context(|| format!("reading media file: {media_path}"))?;
+    Ok(MmapOrVec::Vec(buf))
+}
+
 /// Read a media file and trust directories, return CryptoEvidence for the zkVM guest.
 pub fn extract_crypto_evidence(media_path: &str, trust_dir: &str) -> Result<CryptoEvidence> {
-    let file_bytes =
-        fs::read(media_path).with_context(|| format!("reading media file: {media_path}"))?;
+    let media = read_media(media_path)?;
+    extract_crypto_evidence_from_bytes(&media, trust_dir)
+}
 
-    let asset_hash: [u8; 32] = Sha256::digest(&file_bytes).into();
+/// Same as [`extract_crypto_evidence`], for media already in memory (e.g. read from stdin).
+pub fn extract_crypto_evidence_from_bytes(
+    file_bytes: &[u8],
+    trust_dir: &str,
+) -> Result<CryptoEvidence> {
+    let asset_hash = ContentHash::from(<[u8; 32]>::from(Sha256::digest(file_bytes)));
 
     // Detect file type and extract C2PA JUMBF data
-    let (format_name, jumbf_data) = if file_bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
-        ("PNG", extract_c2pa_from_png(&file_bytes))
-    } else if file_bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
-        ("JPEG", extract_c2pa_from_jpeg(&file_bytes))
-    } else if is_bmff(&file_bytes) {
-        ("MP4/BMFF", extract_c2pa_from_bmff(&file_bytes))
-    } else if file_bytes.starts_with(b"%PDF-") {
-        ("PDF", extract_c2pa_from_pdf(&file_bytes))
-    } else {
-        ("unknown", None)
-    };
+    let (format_name, jumbf_data) = extract_jumbf(file_bytes);
     eprintln!("Detected format: {format_name}");
 
+    let jumbf_data = jumbf_data.map(Bytes::from);
+    let assertion_superboxes = jumbf_data
+        .as_ref()
+        .map(extract_assertion_superboxes)
+        .unwrap_or_default();
+
     let (has_manifest, cose_sign1_bytes, cert_chain_der, claim_cbor, assertion_boxes) =
         match jumbf_data {
             Some(jumbf) => {
@@ -59,19 +122,28 @@ pub fn extract_crypto_evidence(media_path: &str, trust_dir: &str) -> Result<Cryp
                             "Extracted {} certificate(s) from COSE x5chain",
                             certs.len()
                         );
+                        match assertion_hash_mismatches(&claim, &assertion_superboxes) {
+                            Ok(bad) if bad.is_empty() => eprintln!("Assertion hashes verified"),
+                            Ok(bad) => {
+                                for label in bad {
+                                    eprintln!("Warning: assertion hash mismatch: {label}");
+                                }
+                            }
+                            Err(e) => eprintln!("Warning: could not check assertion hashes: {e}"),
+                        }
                         (true, sig, certs, claim, assertions)
                     }
                     None => {
                         eprintln!(
                             "Warning: JUMBF found but could not extract claim/signature boxes"
                         );
-                        (false, Vec::new(), Vec::new(), Vec::new(), Vec::new())
+                        (false, Bytes::new(), Vec::new(), Bytes::new(), Vec::new())
                     }
                 }
             }
             None => {
                 eprintln!("No C2PA JUMBF data found in {format_name} file");
-                (false, Vec::new(), Vec::new(), Vec::new(), Vec::new())
+                (false, Bytes::new(), Vec::new(), Bytes::new(), Vec::new())
             }
         };
 
@@ -94,9 +166,34 @@ pub fn extract_crypto_evidence(media_path: &str, trust_dir: &str) -> Result<Cryp
         assertion_boxes,
         official_trust_anchors_der: official,
         curated_trust_anchors_der: curated,
+        assertion_superboxes,
+        // Downloaded separately by the prover (crl::attach_crls)
+        crl_bytes: Vec::new(),
     })
 }
 
+/// Detect the container format from magic bytes and pull out its C2PA JUMBF.
+pub fn extract_jumbf(file_bytes: &[u8]) -> (&'static str, Option<Vec<u8>>) {
+    if file_bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        ("PNG", extract_c2pa_from_png(file_bytes))
+    } else if file_bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        ("JPEG", extract_c2pa_from_jpeg(file_bytes))
+    } else if is_webp(file_bytes) {
+        ("WEBP", extract_c2pa_from_webp(file_bytes))
+    } else if let Some(format) = detect_format(file_bytes) {
+        match format {
+            BmffFormat::Avif => ("AVIF/BMFF", extract_c2pa_from_avif(file_bytes)),
+            BmffFormat::Heif => ("HEIF/BMFF", extract_c2pa_from_bmff(file_bytes, format)),
+            BmffFormat::Hevc => ("HEVC/BMFF", extract_c2pa_from_bmff(file_bytes, format)),
+            BmffFormat::Mp4 => ("MP4/BMFF", extract_c2pa_from_bmff(file_bytes, format)),
+        }
+    } else if file_bytes.starts_with(b"%PDF-") {
+        ("PDF", extract_c2pa_from_pdf(file_bytes))
+    } else {
+        ("unknown", None)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // PNG chunk parsing
 // ---------------------------------------------------------------------------
@@ -227,6 +324,49 @@ fn extract_c2pa_from_jpeg(data: &[u8]) -> Option<Vec<u8>> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// WEBP (RIFF) chunk parsing
+// ---------------------------------------------------------------------------
+
+/// Check for the `RIFF....WEBP` file header.
+fn is_webp(data: &[u8]) -> bool {
+    data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP"
+}
+
+/// Extract C2PA JUMBF data from the `C2PA` chunk of a WEBP file.
+///
+/// RIFF chunks are [4 bytes: FourCC][4 bytes: size LE][payload], padded to
+/// an even length. The `C2PA` chunk payload is the raw JUMBF.
+fn extract_c2pa_from_webp(data: &[u8]) -> Option<Vec<u8>> {
+    if !is_webp(data) {
+        return None;
+    }
+
+    // The RIFF size covers everything after itself; don't read past it
+    let riff_size = u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+    let end = data.len().min(riff_size.saturating_add(8));
+    let mut pos = 12;
+
+    while pos + 8 <= end {
+        let fourcc = &data[pos..pos + 4];
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        let payload_start = pos + 8;
+        let payload_end = payload_start.checked_add(size)?;
+        if payload_end > end {
+            break;
+        }
+
+        if fourcc == b"C2PA" {
+            return Some(data[payload_start..payload_end].to_vec());
+        }
+
+        // Odd-sized chunks are followed by a pad byte
+        pos = payload_end + (size & 1);
+    }
+
+    None
+}
+
 // ---------------------------------------------------------------------------
 // MP4/BMFF C2PA extraction
 // ---------------------------------------------------------------------------
@@ -237,31 +377,91 @@ const C2PA_UUID: [u8; 16] = [
     0x81,
 ];
 
+/// `ftyp` brands used by HEVC/H.265 video in MP4 containers.
+const HEVC_BRANDS: [&[u8; 4]; 2] = [b"hvc1", b"hev1"];
+
+/// `ftyp` major brands of HEIF still images and image sequences.
+const HEIF_BRANDS: [&[u8; 4]; 4] = [b"heic", b"heix", b"mif1", b"msf1"];
+
+/// `ftyp` major brands of AVIF still images and image sequences.
+const AVIF_BRANDS: [&[u8; 4]; 2] = [b"avif", b"avis"];
+
+/// `infe` content type of a C2PA manifest item (C2PA spec §14.3)
+const C2PA_CONTENT_TYPE: &str = "application/c2pa";
+
+/// Boxes that can hold the C2PA `uuid` box below the top level.
+const CONTAINER_BOXES: [&[u8; 4]; 3] = [b"moov", b"meta", b"udta"];
+
+/// Nesting limit for `CONTAINER_BOXES`, so a crafted file can't recurse deeply.
+const MAX_CONTAINER_DEPTH: usize = 8;
+
+/// BMFF flavours whose C2PA box placement differs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BmffFormat {
+    /// AVIF image: as HEIF, or a `mime` item in `meta/iinf`
+    Avif,
+    /// HEIF/HEIC image: manifest under the top-level `meta` box
+    Heif,
+    /// HEVC video: top-level, or under `moov/udta`
+    Hevc,
+    /// Other MP4/MOV: top-level, or under `moov/udta`
+    Mp4,
+}
+
+/// Classify a BMFF file by its `ftyp` brands, or None if it isn't BMFF.
+fn detect_format(data: &[u8]) -> Option<BmffFormat> {
+    if !is_bmff(data) {
+        return None;
+    }
+    let (_, ftyp) = bmff_boxes(data).into_iter().find(|(t, _)| t == b"ftyp")?;
+    let major = ftyp.get(..4).unwrap_or_default();
+    if AVIF_BRANDS.iter().any(|b| major == b.as_slice()) {
+        Some(BmffFormat::Avif)
+    } else if HEIF_BRANDS.iter().any(|b| major == b.as_slice()) {
+        Some(BmffFormat::Heif)
+    } else if is_hevc(data) {
+        Some(BmffFormat::Hevc)
+    } else {
+        Some(BmffFormat::Mp4)
+    }
+}
+
 /// Check if a file is BMFF-based (MP4, MOV, HEIF, etc.) by looking for `ftyp` box.
 fn is_bmff(data: &[u8]) -> bool {
     // BMFF files start with a box whose type is `ftyp` at offset 4
     data.len() >= 8 && &data[4..8] == b"ftyp"
 }
 
-/// Extract C2PA JUMBF data from a BMFF container (MP4, MOV, HEIF, etc.).
+/// Check whether the `ftyp` major or compatible brands declare HEVC video.
+fn is_hevc(data: &[u8]) -> bool {
+    let Some((_, ftyp)) = bmff_boxes(data).into_iter().find(|(t, _)| t == b"ftyp") else {
+        return false;
+    };
+    // ftyp content: major_brand(4) + minor_version(4) + compatible_brands(4 each)
+    let major = ftyp.get(..4).into_iter();
+    let compatible = ftyp.get(8..).unwrap_or_default().chunks_exact(4);
+    major
+        .chain(compatible)
+        .any(|brand| HEVC_BRANDS.iter().any(|h| brand == h.as_slice()))
+}
+
+/// Split a run of BMFF boxes into `(type, content)` pairs.
 ///
-/// Scans top-level boxes for a `uuid` box with the C2PA UUID.
-/// The C2PA UUID box has internal structure (per c2pa-rs/C2PA spec):
-///   [16 bytes: UUID][4 bytes: FullBox version+flags][null-terminated purpose string]
-///   [8 bytes: aux uuid offset][JUMBF manifest data...]
-fn extract_c2pa_from_bmff(data: &[u8]) -> Option<Vec<u8>> {
+/// Stops at the first malformed or truncated box.
+fn bmff_boxes(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut boxes = Vec::new();
     let mut pos = 0;
 
     while pos + 8 <= data.len() {
-        let size = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?) as u64;
-        let box_type = &data[pos + 4..pos + 8];
+        let size = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as u64;
+        let box_type: [u8; 4] = data[pos + 4..pos + 8].try_into().unwrap();
 
         let (header_size, box_size) = if size == 1 {
             // Extended size: 64-bit size follows the box type
             if pos + 16 > data.len() {
                 break;
             }
-            let ext_size = u64::from_be_bytes(data[pos + 8..pos + 16].try_into().ok()?);
+            let ext_size = u64::from_be_bytes(data[pos + 8..pos + 16].try_into().unwrap());
             (16u64, ext_size)
         } else if size == 0 {
             // Box extends to end of file
@@ -274,40 +474,246 @@ fn extract_c2pa_from_bmff(data: &[u8]) -> Option<Vec<u8>> {
             break;
         }
 
-        if box_type == b"uuid" {
-            let content_start = pos + header_size as usize;
-            let content_end = pos + box_size as usize;
-            let content = &data[content_start..content_end];
+        let content_start = pos + header_size as usize;
+        let content_end = pos + box_size as usize;
+        boxes.push((box_type, &data[content_start..content_end]));
 
-            // uuid box content: 16-byte UUID + C2PA envelope
-            if content.len() >= 16 && content[..16] == C2PA_UUID {
-                let inner = &content[16..];
-                // Skip FullBox header (4 bytes: version + flags)
-                if inner.len() < 4 {
-                    continue;
-                }
-                let mut cursor = 4usize;
+        pos = content_end;
+    }
 
-                // Read null-terminated purpose string
-                let null_pos = inner[cursor..].iter().position(|&b| b == 0)?;
-                let purpose = std::str::from_utf8(&inner[cursor..cursor + null_pos]).ok()?;
-                cursor += null_pos + 1; // skip string + null
+    boxes
+}
 
-                if purpose != "manifest" && purpose != "original" {
-                    continue;
-                }
+/// Extract C2PA JUMBF data from a BMFF container (MP4, MOV, HEIF, etc.).
+///
+/// Scans top-level boxes for a `uuid` box with the C2PA UUID. HEIF puts
+/// the manifest inside `meta`, and some HEVC encoders use `moov/udta`, so
+/// when no top-level box is found the `CONTAINER_BOXES` are searched
+/// recursively, `meta` first for HEIF.
+fn extract_c2pa_from_bmff(data: &[u8], format: BmffFormat) -> Option<Vec<u8>> {
+    if let Some(jumbf) = find_c2pa_uuid_box(data) {
+        return Some(jumbf);
+    }
 
-                // Skip 8-byte aux uuid offset
-                if cursor + 8 > inner.len() {
-                    continue;
-                }
-                cursor += 8;
+    let mut boxes = bmff_boxes(data);
+    if matches!(format, BmffFormat::Heif | BmffFormat::Avif) {
+        // Stable sort: `meta` first, other boxes keep file order
+        boxes.sort_by_key(|(t, _)| t != b"meta");
+    }
+    boxes
+        .into_iter()
+        .find_map(|(box_type, content)| find_c2pa_in_container(&box_type, content, 1))
+}
 
-                return Some(inner[cursor..].to_vec());
-            }
+/// Search a container box's children for the C2PA `uuid` box, descending
+/// into nested `CONTAINER_BOXES` up to `MAX_CONTAINER_DEPTH`.
+fn find_c2pa_in_container(box_type: &[u8; 4], content: &[u8], depth: usize) -> Option<Vec<u8>> {
+    if depth > MAX_CONTAINER_DEPTH || !CONTAINER_BOXES.contains(&box_type) {
+        return None;
+    }
+    let children = if box_type == b"meta" {
+        meta_children(content)
+    } else {
+        content
+    };
+    if let Some(jumbf) = find_c2pa_uuid_box(children) {
+        return Some(jumbf);
+    }
+    bmff_boxes(children)
+        .into_iter()
+        .find_map(|(t, c)| find_c2pa_in_container(&t, c, depth + 1))
+}
+
+/// Extract C2PA JUMBF data from an AVIF file.
+///
+/// Tries the HEIF `uuid` box placement first, then the item form: an
+/// `infe` of type `mime` with content type `application/c2pa`, whose bytes
+/// `iloc` places in the file or in `meta/idat`. A manifest split across
+/// several extents is joined back together in extent order.
+fn extract_c2pa_from_avif(data: &[u8]) -> Option<Vec<u8>> {
+    if let Some(jumbf) = extract_c2pa_from_bmff(data, BmffFormat::Avif) {
+        return Some(jumbf);
+    }
+
+    let (_, meta) = bmff_boxes(data).into_iter().find(|(t, _)| t == b"meta")?;
+    let children = bmff_boxes(meta_children(meta));
+    let child = |name: &[u8; 4]| children.iter().find(|(t, _)| t == name).map(|(_, c)| *c);
+
+    let item_id = c2pa_item_id(child(b"iinf")?)?;
+    let extents = iloc_extents(child(b"iloc")?, item_id)?;
+
+    let mut jumbf = Vec::new();
+    for (construction_method, offset, length) in extents {
+        let source = match construction_method {
+            0 => data,
+            1 => child(b"idat")?,
+            _ => return None,
+        };
+        let start = usize::try_from(offset).ok()?;
+        let end = start.checked_add(usize::try_from(length).ok()?)?;
+        jumbf.extend_from_slice(source.get(start..end)?);
+    }
+    (!jumbf.is_empty()).then_some(jumbf)
+}
+
+/// Big-endian reader over a box payload; every read is bounds-checked.
+struct BeReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BeReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let out = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(out)
+    }
+
+    /// Unsigned integer of `size` bytes (0, 2, 4 or 8; 0 reads as 0)
+    fn uint(&mut self, size: usize) -> Option<u64> {
+        let bytes = self.bytes(size)?;
+        Some(bytes.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b)))
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    /// NUL-terminated UTF-8 string (lossy)
+    fn cstr(&mut self) -> Option<String> {
+        let rest = self.rest();
+        let len = rest.iter().position(|&b| b == 0)?;
+        self.pos += len + 1;
+        Some(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+}
+
+/// ID of the first `application/c2pa` `mime` item in an `iinf` payload.
+fn c2pa_item_id(iinf: &[u8]) -> Option<u32> {
+    let mut r = BeReader::new(iinf);
+    let version = r.uint(4)? >> 24;
+    let _entry_count = r.uint(if version == 0 { 2 } else { 4 })?;
+    // Version 2/3 `infe` boxes follow the entry count
+    for (box_type, infe) in bmff_boxes(r.rest()) {
+        if &box_type != b"infe" {
+            continue;
+        }
+        let mut e = BeReader::new(infe);
+        let version = e.uint(4)? >> 24;
+        let item_id = match version {
+            2 => e.uint(2)?,
+            3 => e.uint(4)?,
+            _ => continue,
+        };
+        let _protection_index = e.uint(2)?;
+        if e.bytes(4)? != b"mime" {
+            continue;
+        }
+        let _item_name = e.cstr()?;
+        if e.cstr()? == C2PA_CONTENT_TYPE {
+            return u32::try_from(item_id).ok();
+        }
+    }
+    None
+}
+
+/// `(construction_method, offset, length)` of each extent of `item_id` in
+/// an `iloc` payload, with the item's base offset already applied.
+fn iloc_extents(iloc: &[u8], item_id: u32) -> Option<Vec<(u8, u64, u64)>> {
+    let mut r = BeReader::new(iloc);
+    let version = r.uint(4)? >> 24;
+    if version > 2 {
+        return None;
+    }
+    let sizes = r.uint(2)?;
+    let offset_size = (sizes >> 12) as usize;
+    let length_size = ((sizes >> 8) & 0xf) as usize;
+    let base_offset_size = ((sizes >> 4) & 0xf) as usize;
+    let index_size = if version > 0 {
+        (sizes & 0xf) as usize
+    } else {
+        0
+    };
+    let item_count = r.uint(if version < 2 { 2 } else { 4 })?;
+
+    for _ in 0..item_count {
+        let id = r.uint(if version < 2 { 2 } else { 4 })?;
+        let construction_method = if version > 0 {
+            (r.uint(2)? & 0xf) as u8
+        } else {
+            0
+        };
+        let _data_reference_index = r.uint(2)?;
+        let base_offset = r.uint(base_offset_size)?;
+        let extent_count = r.uint(2)?;
+
+        let mut extents = Vec::new();
+        for _ in 0..extent_count {
+            let _extent_index = r.uint(index_size)?;
+            let offset = r.uint(offset_size)?;
+            let length = r.uint(length_size)?;
+            let offset = base_offset.checked_add(offset)?;
+            extents.push((construction_method, offset, length));
+        }
+        if id == u64::from(item_id) {
+            return Some(extents);
+        }
+    }
+    None
+}
+
+/// Child boxes of a `meta` box. ISO BMFF `meta` is a FullBox (4 bytes of
+/// version + flags before the children); QuickTime's `meta` isn't, and
+/// starts directly with its `hdlr` child.
+fn meta_children(content: &[u8]) -> &[u8] {
+    if content.get(4..8) == Some(b"hdlr".as_slice()) {
+        content
+    } else {
+        content.get(4..).unwrap_or_default()
+    }
+}
+
+/// Find a C2PA `uuid` box among sibling boxes and return its JUMBF payload.
+///
+/// The C2PA UUID box has internal structure (per c2pa-rs/C2PA spec):
+///   [16 bytes: UUID][4 bytes: FullBox version+flags][null-terminated purpose string]
+///   [8 bytes: aux uuid offset][JUMBF manifest data...]
+fn find_c2pa_uuid_box(data: &[u8]) -> Option<Vec<u8>> {
+    for (box_type, content) in bmff_boxes(data) {
+        // uuid box content: 16-byte UUID + C2PA envelope
+        if &box_type != b"uuid" || content.len() < 16 || content[..16] != C2PA_UUID {
+            continue;
+        }
+
+        let inner = &content[16..];
+        // Skip FullBox header (4 bytes: version + flags)
+        if inner.len() < 4 {
+            continue;
         }
+        let mut cursor = 4usize;
 
-        pos += box_size as usize;
+        // Read null-terminated purpose string
+        let Some(null_pos) = inner[cursor..].iter().position(|&b| b == 0) else {
+            continue;
+        };
+        let purpose = std::str::from_utf8(&inner[cursor..cursor + null_pos]).unwrap_or("");
+        cursor += null_pos + 1; // skip string + null
+
+        if purpose != "manifest" && purpose != "original" {
+            continue;
+        }
+
+        // Skip 8-byte aux uuid offset
+        if cursor + 8 > inner.len() {
+            continue;
+        }
+        cursor += 8;
+
+        return Some(inner[cursor..].to_vec());
     }
 
     None
@@ -456,13 +862,13 @@ fn try_extract_c2pa_from_filespec(
 // JUMBF / ISO BMFF box parsing
 // ---------------------------------------------------------------------------
 
-struct BmffBox<'a> {
+struct BmffBox {
     box_type: [u8; 4],
-    data: &'a [u8], // content after the 8-byte header
+    data: Bytes, // content after the 8-byte header, a view into the parent
 }
 
-/// Parse consecutive ISO BMFF boxes from a byte slice.
-fn parse_boxes(data: &[u8]) -> Vec<BmffBox<'_>> {
+/// Parse consecutive ISO BMFF boxes from a buffer.
+fn parse_boxes(data: &Bytes) -> Vec<BmffBox> {
     let mut result = Vec::new();
     let mut pos = 0;
 
@@ -475,7 +881,7 @@ fn parse_boxes(data: &[u8]) -> Vec<BmffBox<'_>> {
         }
 
         let box_type: [u8; 4] = data[pos + 4..pos + 8].try_into().unwrap_or([0; 4]);
-        let content = &data[pos + 8..pos + size];
+        let content = data.slice(pos + 8..pos + size);
 
         result.push(BmffBox {
             box_type,
@@ -511,15 +917,14 @@ fn parse_jumd_label(data: &[u8]) -> Option<String> {
 /// Walk the JUMBF box tree to find claim CBOR, COSE_Sign1 signature,
 /// and assertion boxes. Claim + signature come from the active (last) manifest.
 /// Assertions are collected from ALL manifests so ingredient metadata is available.
-/// Returns (claim_cbor, cose_sign1, assertion_boxes).
-fn extract_manifest_parts(
-    jumbf: &[u8],
-) -> Option<(Vec<u8>, Vec<u8>, Vec<(String, Vec<u8>)>)> {
+/// Returns (claim_cbor, cose_sign1, assertion_boxes), each a view into
+/// `jumbf`'s buffer rather than a copy, as is every box along the way.
+pub fn extract_manifest_parts(jumbf: &Bytes) -> Option<(Bytes, Bytes, Vec<(String, Bytes)>)> {
     let top_boxes = parse_boxes(jumbf);
 
     // Top-level should be a single jumb box (C2PA manifest store)
     let store = top_boxes.iter().find(|b| &b.box_type == b"jumb")?;
-    let store_children = parse_boxes(store.data);
+    let store_children = parse_boxes(&store.data);
 
     let manifests: Vec<_> = store_children
         .iter()
@@ -532,7 +937,7 @@ fn extract_manifest_parts(
 
     // Active manifest = last jumb child in the store (per C2PA spec)
     let active = manifests.last().unwrap();
-    let active_children = parse_boxes(active.data);
+    let active_children = parse_boxes(&active.data);
 
     let mut claim_cbor = None;
     let mut cose_sign1 = None;
@@ -541,21 +946,20 @@ fn extract_manifest_parts(
         if &child.box_type != b"jumb" {
             continue;
         }
-        let inner = parse_boxes(child.data);
+        let inner = parse_boxes(&child.data);
         let label = inner
             .first()
             .filter(|b| &b.box_type == b"jumd")
-            .and_then(|b| parse_jumd_label(b.data));
+            .and_then(|b| parse_jumd_label(&b.data));
         match label.as_deref() {
             Some(l) if l.starts_with("c2pa.claim") => {
                 if let Some(content_box) = inner.get(1) {
-                    claim_cbor = Some(content_box.data.to_vec());
+                    claim_cbor = Some(content_box.data.clone());
                 }
             }
             Some(l) if l.starts_with("c2pa.signature") => {
                 if let Some(content_box) = inner.get(1) {
-                    let raw = extract_embedded_content(content_box);
-                    cose_sign1 = Some(raw.to_vec());
+                    cose_sign1 = Some(extract_embedded_content(content_box));
                 }
             }
             _ => {}
@@ -565,16 +969,16 @@ fn extract_manifest_parts(
     // Collect assertions from ALL manifests (active + ingredients)
     let mut assertions = Vec::new();
     for manifest in &manifests {
-        let children = parse_boxes(manifest.data);
+        let children = parse_boxes(&manifest.data);
         for child in &children {
             if &child.box_type != b"jumb" {
                 continue;
             }
-            let inner = parse_boxes(child.data);
+            let inner = parse_boxes(&child.data);
             let label = inner
                 .first()
                 .filter(|b| &b.box_type == b"jumd")
-                .and_then(|b| parse_jumd_label(b.data));
+                .and_then(|b| parse_jumd_label(&b.data));
             if let Some(l) = &label {
                 if l == "c2pa.assertions" {
                     extract_assertions_from_store(&inner[1..], &mut assertions);
@@ -589,24 +993,62 @@ fn extract_manifest_parts(
     }
 }
 
+/// Collect the active manifest's assertions as (label, superbox payload).
+/// The payload is the assertion's jumb contents (jumd + content boxes, no
+/// header), which is what the claim's hashed URIs commit to.
+pub fn extract_assertion_superboxes(jumbf: &Bytes) -> Vec<(String, Bytes)> {
+    let top_boxes = parse_boxes(jumbf);
+    let Some(store) = top_boxes.iter().find(|b| &b.box_type == b"jumb") else {
+        return Vec::new();
+    };
+    let store_children = parse_boxes(&store.data);
+    let Some(active) = store_children.iter().rfind(|b| &b.box_type == b"jumb") else {
+        return Vec::new();
+    };
+
+    let mut out = Vec::new();
+    for child in parse_boxes(&active.data) {
+        if &child.box_type != b"jumb" {
+            continue;
+        }
+        let inner = parse_boxes(&child.data);
+        let label = inner
+            .first()
+            .filter(|b| &b.box_type == b"jumd")
+            .and_then(|b| parse_jumd_label(&b.data));
+        if label.as_deref() != Some("c2pa.assertions") {
+            continue;
+        }
+        for assertion in inner.iter().skip(1).filter(|b| &b.box_type == b"jumb") {
+            let label = parse_boxes(&assertion.data)
+                .first()
+                .filter(|b| &b.box_type == b"jumd")
+                .and_then(|b| parse_jumd_label(&b.data));
+            if let Some(label) = label {
+                out.push((label, assertion.data.clone()));
+            }
+        }
+    }
+    out
+}
+
 /// Parse individual assertion boxes from an assertion store superbox.
 fn extract_assertions_from_store(
-    children: &[BmffBox<'_>],
-    out: &mut Vec<(String, Vec<u8>)>,
+    children: &[BmffBox],
+    out: &mut Vec<(String, Bytes)>,
 ) {
     for child in children {
         if &child.box_type != b"jumb" {
             continue;
         }
-        let inner = parse_boxes(child.data);
+        let inner = parse_boxes(&child.data);
         let label = inner
             .first()
             .filter(|b| &b.box_type == b"jumd")
-            .and_then(|b| parse_jumd_label(b.data));
+            .and_then(|b| parse_jumd_label(&b.data));
         if let Some(al) = label {
             if let Some(content_box) = inner.get(1) {
-                let raw = extract_embedded_content(content_box);
-                out.push((al, raw.to_vec()));
+                out.push((al, extract_embedded_content(content_box)));
             }
         }
     }
@@ -615,11 +1057,11 @@ fn extract_assertions_from_store(
 /// For a bfdb (embedded file content) box, skip the toggle byte and
 /// optional media-type/filename strings to get to the raw content.
 /// For any other box type, return the data as-is.
-fn extract_embedded_content<'a>(content_box: &BmffBox<'a>) -> &'a [u8] {
+fn extract_embedded_content(content_box: &BmffBox) -> Bytes {
     if &content_box.box_type == b"bfdb" {
-        skip_bfdb_header(content_box.data)
+        content_box.data.slice_ref(skip_bfdb_header(&content_box.data))
     } else {
-        content_box.data
+        content_box.data.clone()
     }
 }
 
@@ -651,7 +1093,7 @@ fn skip_bfdb_header(data: &[u8]) -> &[u8] {
 // ---------------------------------------------------------------------------
 
 /// Extract DER-encoded certificate chain from COSE_Sign1 x5chain header.
-fn extract_cert_chain_from_cose(cose_bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
+pub fn extract_cert_chain_from_cose(cose_bytes: &[u8]) -> Result<Vec<Vec<u8>>> {
     use coset::{CborSerializable, CoseSign1, Label, TaggedCborSerializable};
 
     // C2PA uses COSE_Sign1_Tagged (CBOR tag 18). Try tagged first, then untagged.
@@ -729,3 +1171,104 @@ fn load_trust_anchors_der(dir: &Path) -> Result<Vec<Vec<u8>>> {
 
     Ok(anchors)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generated by fixtures/jumbf/gen.py, each wrapping the manifest store
+    // from data/test_files/a2.png
+    const HEIC: &[u8] = include_bytes!("../fixtures/jumbf/signed.heic");
+    const HEVC_MP4: &[u8] = include_bytes!("../fixtures/jumbf/signed_hevc.mp4");
+    const AVIF: &[u8] = include_bytes!("../fixtures/jumbf/signed.avif");
+    const UNSIGNED_AVIF: &[u8] = include_bytes!("../fixtures/jumbf/unsigned.avif");
+    const WEBP: &[u8] = include_bytes!("../fixtures/jumbf/signed.webp");
+
+    /// No trust anchors: the directories don't exist
+    const NO_TRUST_DIR: &str = "/nonexistent/r3l-trust";
+
+    /// The JUMBF pulled out of `file`, checking the detected container.
+    fn jumbf_of(file: &[u8], format: &str) -> Bytes {
+        let (detected, jumbf) = extract_jumbf(file);
+        assert_eq!(detected, format);
+        Bytes::from(jumbf.expect("C2PA JUMBF extracted"))
+    }
+
+    fn assert_manifest_store(jumbf: &Bytes) {
+        assert_eq!(&jumbf[4..8], b"jumb");
+        let (claim, signature, assertions) = extract_manifest_parts(jumbf).expect("claim and signature");
+        assert!(!claim.is_empty());
+        assert!(!signature.is_empty());
+        assert!(!assertions.is_empty());
+    }
+
+    fn assert_signed_evidence(file: &[u8]) {
+        let evidence = extract_crypto_evidence_from_bytes(file, NO_TRUST_DIR).unwrap();
+        assert!(evidence.has_manifest);
+        assert!(!evidence.claim_cbor.is_empty());
+        assert!(!evidence.cose_sign1_bytes.is_empty());
+        assert!(!evidence.cert_chain_der.is_empty());
+    }
+
+    #[test]
+    fn heic_manifest_inside_meta() {
+        assert_eq!(detect_format(HEIC), Some(BmffFormat::Heif));
+        assert!(find_c2pa_uuid_box(HEIC).is_none(), "fixture keeps the manifest out of the top level");
+        assert_manifest_store(&jumbf_of(HEIC, "HEIF/BMFF"));
+        assert_signed_evidence(HEIC);
+    }
+
+    #[test]
+    fn hevc_manifest_under_moov_udta() {
+        assert!(is_hevc(HEVC_MP4));
+        assert_eq!(detect_format(HEVC_MP4), Some(BmffFormat::Hevc));
+        assert_manifest_store(&jumbf_of(HEVC_MP4, "HEVC/BMFF"));
+        assert_signed_evidence(HEVC_MP4);
+    }
+
+    #[test]
+    fn heic_and_hevc_carry_the_same_store() {
+        assert_eq!(jumbf_of(HEIC, "HEIF/BMFF"), jumbf_of(HEVC_MP4, "HEVC/BMFF"));
+    }
+
+    #[test]
+    fn avif_manifest_item_reassembled_from_idat_extents() {
+        assert_eq!(detect_format(AVIF), Some(BmffFormat::Avif));
+        let jumbf = jumbf_of(AVIF, "AVIF/BMFF");
+        assert_manifest_store(&jumbf);
+        assert_eq!(jumbf, jumbf_of(HEIC, "HEIF/BMFF"));
+        assert_signed_evidence(AVIF);
+    }
+
+    #[test]
+    fn unsigned_avif_has_no_manifest() {
+        assert_eq!(extract_jumbf(UNSIGNED_AVIF), ("AVIF/BMFF", None));
+        let evidence = extract_crypto_evidence_from_bytes(UNSIGNED_AVIF, NO_TRUST_DIR).unwrap();
+        assert!(!evidence.has_manifest);
+        assert!(evidence.claim_cbor.is_empty());
+        assert!(evidence.cert_chain_der.is_empty());
+    }
+
+    #[test]
+    fn webp_evidence_from_path() {
+        assert_manifest_store(&jumbf_of(WEBP, "WEBP"));
+
+        let path = std::env::temp_dir().join(format!("r3l-signed-{}.webp", std::process::id()));
+        std::fs::write(&path, WEBP).unwrap();
+        let evidence = extract_crypto_evidence(path.to_str().unwrap(), NO_TRUST_DIR);
+        std::fs::remove_file(&path).unwrap();
+
+        let evidence = evidence.unwrap();
+        assert!(evidence.has_manifest);
+        assert!(!evidence.cose_sign1_bytes.is_empty());
+        assert!(!evidence.cert_chain_der.is_empty());
+        let heic = extract_crypto_evidence_from_bytes(HEIC, NO_TRUST_DIR).unwrap();
+        assert_eq!(evidence.claim_cbor, heic.claim_cbor);
+    }
+
+    #[test]
+    fn nesting_past_the_depth_limit_is_not_searched() {
+        let uuid = find_c2pa_in_container(b"udta", &HEVC_MP4[..0], MAX_CONTAINER_DEPTH + 1);
+        assert!(uuid.is_none());
+    }
+}
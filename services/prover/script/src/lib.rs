@@ -1 +1,2 @@
+pub mod crl;
 pub mod jumbf_extract;
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+# Regenerate the leaf certificate fixtures used by src/key_usage.rs tests.
+# Each is a self-signed P-256 cert (DER) with only the extensions listed.
+set -euo pipefail
+cd "$(dirname "$0")"
+
+leaf() {
+    local name=$1; shift
+    local cfg
+    cfg=$(mktemp)
+    {
+        printf '[req]\ndistinguished_name = dn\nx509_extensions = ext\nprompt = no\n'
+        printf '[dn]\nCN = r3l %s fixture\n' "$name"
+        printf '[ext]\n'
+        for ext in "$@"; do printf '%s\n' "$ext"; done
+    } > "$cfg"
+    openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes \
+        -keyout /dev/null -days 36500 -config "$cfg" -outform DER -out "$name.der" 2>/dev/null
+    rm "$cfg"
+}
+
+leaf ku_email_protection "keyUsage = critical, digitalSignature" "extendedKeyUsage = emailProtection"
+leaf ku_key_cert_sign_only "keyUsage = critical, keyCertSign"
+leaf no_key_usage "subjectKeyIdentifier = hash"
+leaf eku_server_auth "keyUsage = critical, digitalSignature" "extendedKeyUsage = serverAuth"
+leaf eku_c2pa_claim_signing "keyUsage = critical, digitalSignature" "extendedKeyUsage = 1.3.6.1.4.1.62558.2.1"
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# Regenerate the ES256 / ES384 signature fixtures used by src/sig.rs tests.
+# Writes <alg>.pub (SEC1 uncompressed point) and <alg>.sig (raw r||s) over
+# tbs.bin for each algorithm.
+set -euo pipefail
+cd "$(dirname "$0")"
+
+printf 'R3L sig fixture: Sig_structure1 stand-in' > tbs.bin
+
+sign() {
+    local name=$1 curve=$2 digest=$3 len=$4
+    openssl ecparam -name "$curve" -genkey -noout -out "$name.key"
+    openssl ec -in "$name.key" -pubout -outform DER 2>/dev/null | tail -c $((2 * len + 1)) > "$name.pub"
+    openssl dgst "-$digest" -sign "$name.key" tbs.bin > "$name.der-sig"
+    # DER ECDSA-Sig-Value -> fixed-width r||s
+    python3 - "$name.der-sig" "$name.sig" "$len" <<'PY'
+import sys
+der = open(sys.argv[1], "rb").read()
+n = int(sys.argv[3])
+def read_int(b, i):
+    assert b[i] == 0x02
+    l = b[i + 1]
+    return int.from_bytes(b[i + 2:i + 2 + l], "big"), i + 2 + l
+i = 3 if der[1] & 0x80 else 2
+r, i = read_int(der, i)
+s, _ = read_int(der, i)
+open(sys.argv[2], "wb").write(r.to_bytes(n, "big") + s.to_bytes(n, "big"))
+PY
+    rm "$name.key" "$name.der-sig"
+}
+
+sign es256 prime256v1 sha256 32
+sign es384 secp384r1 sha384 48
@@ -0,0 +1,46 @@
+#!/usr/bin/env bash
+# Regenerate the RFC 3161 fixtures used by src/timestamp.rs tests: a
+# self-signed TSA, a time stamp response for tbs.bin (response.tsr), the
+# bare token inside it (token.der), and its genTime in unix seconds.
+set -euo pipefail
+cd "$(dirname "$0")"
+work=$(mktemp -d)
+trap 'rm -rf "$work"' EXIT
+
+cat > "$work/tsa.cnf" <<'CNF'
+[req]
+distinguished_name = dn
+x509_extensions = ext
+prompt = no
+[dn]
+CN = r3l TSA fixture
+[ext]
+keyUsage = critical, digitalSignature
+extendedKeyUsage = critical, timeStamping
+CNF
+cat > "$work/ts.cnf" <<'CNF'
+[tsa]
+default_tsa = tsa_config
+[tsa_config]
+serial = SERIAL_FILE
+default_policy = 1.2.3.4.1
+digests = sha256
+signer_digest = sha256
+accuracy = secs:1
+clock_precision_digits = 3
+ess_cert_id_chain = no
+CNF
+
+openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes \
+    -keyout "$work/tsa.key" -days 36500 -config "$work/tsa.cnf" -out "$work/tsa.pem" 2>/dev/null
+printf '01\n' > "$work/serial"
+sed -i "s|SERIAL_FILE|$work/serial|" "$work/ts.cnf"
+printf 'r3l time stamp fixture\n' > tbs.bin
+openssl ts -query -data tbs.bin -sha256 -cert -out "$work/query.tsq"
+openssl ts -reply -config "$work/ts.cnf" -queryfile "$work/query.tsq" \
+    -signer "$work/tsa.pem" -inkey "$work/tsa.key" -out response.tsr 2>/dev/null
+openssl ts -reply -in response.tsr -token_out -out token.der
+
+# "Time stamp: Oct 16 18:02:11.123 2026 GMT" -> unix seconds
+stamp=$(openssl ts -reply -in response.tsr -text | sed -n 's/^Time stamp: //p')
+date -u -d "$(sed 's/\.[0-9]*//' <<<"$stamp")" +%s > gen_time.txt
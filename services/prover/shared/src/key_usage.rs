@@ -0,0 +1,88 @@
+//! Leaf certificate key usage checks run by the zkVM guest. They live here
+//! rather than in the guest binary so they can be tested on the host.
+
+use der::Decode;
+use x509_cert::ext::pkix::{ExtendedKeyUsage, KeyUsage};
+use x509_cert::Certificate;
+
+const OID_KEY_USAGE: der::oid::ObjectIdentifier =
+    der::oid::ObjectIdentifier::new_unwrap("2.5.29.15");
+const OID_EXT_KEY_USAGE: der::oid::ObjectIdentifier =
+    der::oid::ObjectIdentifier::new_unwrap("2.5.29.37");
+
+/// EKUs accepted for C2PA claim signing: id-kp-emailProtection,
+/// id-kp-documentSigning and c2pa-kp-claimSigning.
+const C2PA_SIGNING_EKUS: [der::oid::ObjectIdentifier; 3] = [
+    der::oid::ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.3.4"),
+    der::oid::ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.3.36"),
+    der::oid::ObjectIdentifier::new_unwrap("1.3.6.1.4.1.62558.2.1"),
+];
+
+/// Raw value of the extension `oid`, if the cert has it.
+fn extension(cert: &Certificate, oid: der::oid::ObjectIdentifier) -> Option<&[u8]> {
+    cert.tbs_certificate
+        .extensions
+        .iter()
+        .flatten()
+        .find(|e| e.extn_id == oid)
+        .map(|e| e.extn_value.as_bytes())
+}
+
+/// True if the cert may sign C2PA claims: keyUsage must be present with
+/// digitalSignature set, and extendedKeyUsage, if present, must include
+/// one of `C2PA_SIGNING_EKUS`.
+pub fn check_key_usage(cert: &Certificate) -> bool {
+    let Some(Ok(key_usage)) = extension(cert, OID_KEY_USAGE).map(KeyUsage::from_der) else {
+        return false;
+    };
+    if !key_usage.digital_signature() {
+        return false;
+    }
+    match extension(cert, OID_EXT_KEY_USAGE) {
+        None => true,
+        Some(bytes) => ExtendedKeyUsage::from_der(bytes)
+            .is_ok_and(|eku| eku.0.iter().any(|oid| C2PA_SIGNING_EKUS.contains(oid))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_key_usage;
+    use der::Decode;
+    use x509_cert::Certificate;
+
+    // Generated by fixtures/key_usage/gen.sh
+    fn leaf(der: &[u8]) -> Certificate {
+        Certificate::from_der(der).expect("fixture cert parses")
+    }
+
+    #[test]
+    fn accepts_digital_signature_with_email_protection() {
+        let cert = leaf(include_bytes!("../fixtures/key_usage/ku_email_protection.der"));
+        assert!(check_key_usage(&cert));
+    }
+
+    #[test]
+    fn accepts_c2pa_claim_signing_eku() {
+        let cert = leaf(include_bytes!("../fixtures/key_usage/eku_c2pa_claim_signing.der"));
+        assert!(check_key_usage(&cert));
+    }
+
+    #[test]
+    fn rejects_key_cert_sign_without_digital_signature() {
+        let cert = leaf(include_bytes!("../fixtures/key_usage/ku_key_cert_sign_only.der"));
+        assert!(!check_key_usage(&cert));
+    }
+
+    #[test]
+    fn rejects_missing_key_usage() {
+        let cert = leaf(include_bytes!("../fixtures/key_usage/no_key_usage.der"));
+        assert!(!check_key_usage(&cert));
+    }
+
+    #[test]
+    fn rejects_server_auth_only_eku() {
+        let cert = leaf(include_bytes!("../fixtures/key_usage/eku_server_auth.der"));
+        assert!(!check_key_usage(&cert));
+    }
+}
@@ -1,4 +1,95 @@
-use serde::{Deserialize, Serialize};
+use borsh::{BorshDeserialize, BorshSerialize};
+pub use bytes::Bytes;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+#[cfg(feature = "guest-crypto")]
+pub mod key_usage;
+#[cfg(feature = "guest-crypto")]
+pub mod sig;
+#[cfg(feature = "guest-crypto")]
+pub mod timestamp;
+
+/// SHA-256 of an asset's bytes, the key attestations are stored under.
+///
+/// Displays and parses as 64 lowercase hex chars. Serializes as that hex
+/// string for human-readable formats (JSON) and as the raw 32 bytes
+/// otherwise, so the bincode layout of `PublicOutputs` matches `[u8; 32]`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ContentHash([u8; 32]);
+
+impl ContentHash {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for ContentHash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ContentHash> for [u8; 32] {
+    fn from(hash: ContentHash) -> Self {
+        hash.0
+    }
+}
+
+impl AsRef<[u8]> for ContentHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+/// A string that isn't a 64-char hex content hash.
+#[derive(Debug)]
+pub struct InvalidContentHash(pub String);
+
+impl std::fmt::Display for InvalidContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "content hash must be 64 hex chars, got {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidContentHash {}
+
+impl std::str::FromStr for ContentHash {
+    type Err = InvalidContentHash;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(s, &mut bytes).map_err(|_| InvalidContentHash(s.to_string()))?;
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for ContentHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            Serialize::serialize(&self.0, serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = <String as Deserialize>::deserialize(deserializer)?;
+            s.parse().map_err(serde::de::Error::custom)
+        } else {
+            <[u8; 32] as Deserialize>::deserialize(deserializer).map(Self)
+        }
+    }
+}
 
 /// Private inputs fed from host to guest.
 /// The host extracts raw crypto evidence from the C2PA manifest;
@@ -6,21 +97,27 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize)]
 pub struct CryptoEvidence {
     /// SHA-256 hash of the original asset (computed outside zkVM for efficiency)
-    pub asset_hash: [u8; 32],
+    pub asset_hash: ContentHash,
     /// Whether the file had a C2PA manifest
     pub has_manifest: bool,
     /// Raw COSE_Sign1_Tagged bytes (the entire COSE structure from the signature box)
-    pub cose_sign1_bytes: Vec<u8>,
+    pub cose_sign1_bytes: Bytes,
     /// X.509 certificate chain (DER-encoded, leaf first)
     pub cert_chain_der: Vec<Vec<u8>>,
     /// Raw CBOR claim payload bytes (from the c2pa.claim box, detached payload)
-    pub claim_cbor: Vec<u8>,
+    pub claim_cbor: Bytes,
     /// Assertion boxes from the manifest: Vec of (label, raw CBOR bytes)
-    pub assertion_boxes: Vec<(String, Vec<u8>)>,
+    pub assertion_boxes: Vec<(String, Bytes)>,
     /// Official trust anchor certificates (DER-encoded)
     pub official_trust_anchors_der: Vec<Vec<u8>>,
     /// Curated trust anchor certificates (DER-encoded)
     pub curated_trust_anchors_der: Vec<Vec<u8>>,
+    /// Active manifest's assertion superbox payloads (jumd + content boxes),
+    /// the bytes the claim's assertion hashes are computed over: Vec of (label, payload)
+    pub assertion_superboxes: Vec<(String, Bytes)>,
+    /// DER CRLs from the leaf cert's cRLDistributionPoints (empty if none
+    /// were fetched, in which case revocation isn't checked)
+    pub crl_bytes: Vec<Vec<u8>>,
 }
 
 /// Public outputs committed by the guest.
@@ -29,13 +126,13 @@ pub struct CryptoEvidence {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PublicOutputs {
     /// SHA-256 of the original file bytes
-    pub content_hash: [u8; 32],
+    pub content_hash: ContentHash,
     /// Whether the file had valid C2PA metadata with a verified signature
     pub has_c2pa: bool,
-    /// "official", "curated", or "untrusted"
-    pub trust_list_match: String,
-    /// "Verified" (sig + trust anchor valid), "SignatureOnly" (sig valid, no trust anchor), or "None"
-    pub validation_state: String,
+    /// Which trust list anchors the signing chain (`None` without a manifest)
+    pub trust_list_match: TrustLevel,
+    /// Outcome of the signature, revocation, key usage and validity checks
+    pub validation_state: ValidationState,
     /// IPTC digital source type URI (from claim, if available)
     pub digital_source_type: String,
     /// Certificate issuer organization (from verified leaf cert)
@@ -48,4 +145,330 @@ pub struct PublicOutputs {
     pub signing_time: String,
     /// SHA-256 fingerprint of the leaf signing certificate (hex-encoded)
     pub cert_fingerprint: String,
+    /// Every assertion referenced by the claim matched its committed hash
+    pub assertions_hash_verified: bool,
+    /// RFC 3339 `notAfter` of the leaf signing certificate ("" if unsigned)
+    pub cert_valid_until: String,
+    /// Leaf cert has keyUsage digitalSignature and, if it has an
+    /// extendedKeyUsage, a C2PA signing EKU
+    pub key_usage_ok: bool,
+}
+
+/// Trust list that anchors a signing certificate chain.
+/// Serialized as "official", "curated", "untrusted" or "none".
+///
+/// The bincode variant index is part of the proof's public values: the
+/// Anchor program maps it back to these names, so only append variants.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustLevel {
+    Official,
+    Curated,
+    Untrusted,
+    /// No C2PA manifest, so no chain to anchor
+    None,
+}
+
+impl TrustLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Official => "official",
+            Self::Curated => "curated",
+            Self::Untrusted => "untrusted",
+            Self::None => "none",
+        }
+    }
+
+    /// Official or curated.
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, Self::Official | Self::Curated)
+    }
+}
+
+/// R3L validation vocabulary shared by the prover guest and the verifier.
+///
+/// The bincode variant index is part of the proof's public values: the
+/// Anchor program maps it back to these names, so only append variants.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum ValidationState {
+    /// Signature valid and chained to a trust anchor
+    Verified,
+    /// Signature valid, no trust anchor
+    SignatureOnly,
+    /// Signature valid, no trusted signing time or one outside the cert's validity
+    ExpiredCertificate,
+    /// Signature valid, leaf serial listed in a supplied CRL
+    Revoked,
+    /// Signature valid, leaf not allowed to sign claims
+    InvalidKeyUsage,
+    /// No verifiable signature
+    None,
+    /// Manifest present but only raw JUMBF extraction could read it (verifier only)
+    ParseError,
+}
+
+impl ValidationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Verified => "Verified",
+            Self::SignatureOnly => "SignatureOnly",
+            Self::ExpiredCertificate => "ExpiredCertificate",
+            Self::Revoked => "Revoked",
+            Self::InvalidKeyUsage => "InvalidKeyUsage",
+            Self::None => "None",
+            Self::ParseError => "ParseError",
+        }
+    }
+}
+
+/// A string that isn't the name of any variant of the enum being parsed.
+#[derive(Debug)]
+pub struct UnknownVariant(pub String);
+
+impl std::fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown variant: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownVariant {}
+
+impl std::fmt::Display for TrustLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TrustLevel {
+    type Err = UnknownVariant;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "official" => Ok(Self::Official),
+            "curated" => Ok(Self::Curated),
+            "untrusted" => Ok(Self::Untrusted),
+            "none" => Ok(Self::None),
+            other => Err(UnknownVariant(other.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ValidationState {
+    type Err = UnknownVariant;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Verified" => Ok(Self::Verified),
+            "SignatureOnly" => Ok(Self::SignatureOnly),
+            "ExpiredCertificate" => Ok(Self::ExpiredCertificate),
+            "Revoked" => Ok(Self::Revoked),
+            "InvalidKeyUsage" => Ok(Self::InvalidKeyUsage),
+            "None" => Ok(Self::None),
+            "ParseError" => Ok(Self::ParseError),
+            other => Err(UnknownVariant(other.to_string())),
+        }
+    }
+}
+
+/// Known issuer organization spellings, keyed by `issuer_key` form,
+/// mapped to one canonical name so attestations group consistently.
+pub static ISSUER_ALIASES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "adobe" => "Adobe Inc.",
+    "adobe inc" => "Adobe Inc.",
+    "adobe systems" => "Adobe Inc.",
+    "adobe systems inc" => "Adobe Inc.",
+    "adobe systems incorporated" => "Adobe Inc.",
+    "digicert" => "DigiCert, Inc.",
+    "digicert inc" => "DigiCert, Inc.",
+    "google" => "Google LLC",
+    "google llc" => "Google LLC",
+    "google trust services" => "Google LLC",
+    "google trust services llc" => "Google LLC",
+    "microsoft" => "Microsoft Corporation",
+    "microsoft corp" => "Microsoft Corporation",
+    "microsoft corporation" => "Microsoft Corporation",
+    "openai" => "OpenAI",
+    "openai inc" => "OpenAI",
+    "openai llc" => "OpenAI",
+    "truepic" => "Truepic",
+    "truepic inc" => "Truepic",
+    "leica camera ag" => "Leica Camera AG",
+    "leica camera" => "Leica Camera AG",
+    "sony" => "Sony Corporation",
+    "sony corp" => "Sony Corporation",
+    "sony corporation" => "Sony Corporation",
+    "nikon" => "Nikon Corporation",
+    "nikon corporation" => "Nikon Corporation",
+    "canon" => "Canon Inc.",
+    "canon inc" => "Canon Inc.",
+};
+
+/// Lowercase, drop `.`/`,` and collapse whitespace: "ADOBE SYSTEMS, INC." -> "adobe systems inc".
+fn issuer_key(raw: &str) -> String {
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| *c != '.' && *c != ',')
+        .flat_map(char::to_lowercase)
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Map a raw X.509 Organization Name to its canonical form.
+/// Unknown issuers are returned trimmed but otherwise unchanged.
+pub fn normalize_issuer(raw: &str) -> String {
+    match ISSUER_ALIASES.get(issuer_key(raw).as_str()) {
+        Some(canonical) => canonical.to_string(),
+        None => raw.trim().to_string(),
+    }
+}
+
+/// Lowercase substrings of C2PA `softwareAgent` strings, mapped to a
+/// canonical product name so "Adobe Photoshop 25.0", "com.adobe.photoshop"
+/// and "photoshop/25.0" all filter as one tool.
+pub static CANONICAL_AGENTS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "photoshop" => "Adobe Photoshop",
+    "lightroom" => "Adobe Lightroom",
+    "firefly" => "Adobe Firefly",
+    "illustrator" => "Adobe Illustrator",
+    "premiere" => "Adobe Premiere Pro",
+    "after effects" => "Adobe After Effects",
+    "aftereffects" => "Adobe After Effects",
+    "adobe express" => "Adobe Express",
+    "dall-e" => "DALL-E",
+    "dall·e" => "DALL-E",
+    "dalle" => "DALL-E",
+    "chatgpt" => "ChatGPT",
+    "gpt-4o" => "GPT-4o",
+    "sora" => "Sora",
+    "midjourney" => "Midjourney",
+    "stable diffusion" => "Stable Diffusion",
+    "imagen" => "Google Imagen",
+    "gemini" => "Google Gemini",
+    "bing image creator" => "Bing Image Creator",
+    "microsoft designer" => "Microsoft Designer",
+    "truepic" => "Truepic",
+    "capture one" => "Capture One",
+    "gimp" => "GIMP",
+    "c2patool" => "c2patool",
+};
+
+/// Canonical product name for a `softwareAgent` string, or `None` if no
+/// `CANONICAL_AGENTS` key occurs in it (case-insensitive). The longest
+/// matching key wins, so "adobe express" beats shorter keys it contains.
+pub fn canonical_software_agent(raw: &str) -> Option<String> {
+    let lowered = raw.to_lowercase();
+    CANONICAL_AGENTS
+        .entries()
+        .filter(|(key, _)| lowered.contains(*key))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, canonical)| canonical.to_string())
+}
+
+#[derive(Debug)]
+pub enum AssertionHashError {
+    /// Claim CBOR is not a map or its assertion list is malformed
+    InvalidClaim,
+    /// Hash algorithm other than sha256 / sha384 / sha512
+    UnsupportedAlg(String),
+}
+
+impl std::fmt::Display for AssertionHashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidClaim => write!(f, "claim CBOR has no valid assertions list"),
+            Self::UnsupportedAlg(alg) => write!(f, "unsupported assertion hash algorithm: {alg}"),
+        }
+    }
+}
+
+impl std::error::Error for AssertionHashError {}
+
+fn cbor_get<'a>(
+    map: &'a [(ciborium::Value, ciborium::Value)],
+    key: &str,
+) -> Option<&'a ciborium::Value> {
+    map.iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, v)| v)
+}
+
+fn hash_by_alg(alg: &str, data: &[u8]) -> Result<Vec<u8>, AssertionHashError> {
+    match alg {
+        "sha256" => Ok(Sha256::digest(data).to_vec()),
+        "sha384" => Ok(Sha384::digest(data).to_vec()),
+        "sha512" => Ok(Sha512::digest(data).to_vec()),
+        other => Err(AssertionHashError::UnsupportedAlg(other.to_string())),
+    }
+}
+
+/// Labels of claim-referenced assertions whose hash doesn't match, or that
+/// are missing from `assertion_boxes` (label, superbox payload).
+///
+/// Reads v1 `assertions` and v2 `created_assertions` / `gathered_assertions`
+/// hashed-URI lists. Per-entry `alg` overrides the claim-level `alg`
+/// (default sha256).
+pub fn assertion_hash_mismatches(
+    claim_cbor: &[u8],
+    assertion_boxes: &[(String, Bytes)],
+) -> Result<Vec<String>, AssertionHashError> {
+    let claim: ciborium::Value =
+        ciborium::de::from_reader(claim_cbor).map_err(|_| AssertionHashError::InvalidClaim)?;
+    let map = claim.as_map().ok_or(AssertionHashError::InvalidClaim)?;
+    let claim_alg = cbor_get(map, "alg")
+        .and_then(|v| v.as_text())
+        .unwrap_or("sha256");
+
+    let refs: Vec<&ciborium::Value> = ["assertions", "created_assertions", "gathered_assertions"]
+        .iter()
+        .filter_map(|key| cbor_get(map, key))
+        .filter_map(|v| v.as_array())
+        .flatten()
+        .collect();
+    if refs.is_empty() {
+        return Err(AssertionHashError::InvalidClaim);
+    }
+
+    let mut mismatches = Vec::new();
+    for r in refs {
+        let entry = r.as_map().ok_or(AssertionHashError::InvalidClaim)?;
+        let url = cbor_get(entry, "url")
+            .and_then(|v| v.as_text())
+            .ok_or(AssertionHashError::InvalidClaim)?;
+        let expected = cbor_get(entry, "hash")
+            .and_then(|v| v.as_bytes())
+            .ok_or(AssertionHashError::InvalidClaim)?;
+        let alg = cbor_get(entry, "alg")
+            .and_then(|v| v.as_text())
+            .unwrap_or(claim_alg);
+
+        // "self#jumbf=c2pa.assertions/c2pa.actions" → "c2pa.actions"
+        let label = url.rsplit('/').next().unwrap_or(url);
+        let matched = match assertion_boxes.iter().find(|(l, _)| l == label) {
+            Some((_, payload)) => hash_by_alg(alg, payload)? == *expected,
+            None => false,
+        };
+        if !matched {
+            mismatches.push(label.to_string());
+        }
+    }
+    Ok(mismatches)
+}
+
+/// True if every assertion the claim references is present and hashes to
+/// the committed value.
+pub fn verify_assertion_hashes(
+    claim_cbor: &[u8],
+    assertion_boxes: &[(String, Bytes)],
+) -> Result<bool, AssertionHashError> {
+    Ok(assertion_hash_mismatches(claim_cbor, assertion_boxes)?.is_empty())
 }
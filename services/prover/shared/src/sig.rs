@@ -0,0 +1,92 @@
+//! COSE signature checks run by the zkVM guest. They live here rather than
+//! in the guest binary so they can be tested on the host.
+
+use p256::ecdsa::signature::Verifier;
+
+/// COSE signature algorithms the guest can verify.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SigAlg {
+    /// ECDSA P-256 with SHA-256 (COSE alg -7)
+    Es256,
+    /// ECDSA P-384 with SHA-384 (COSE alg -35)
+    Es384,
+}
+
+impl SigAlg {
+    /// Verify a raw r||s `signature` over `tbs` with the SEC1 public key.
+    pub fn verify(self, public_key: &[u8], tbs: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Es256 => verify_es256(public_key, tbs, signature),
+            Self::Es384 => verify_es384(public_key, tbs, signature),
+        }
+    }
+}
+
+fn verify_es256(public_key: &[u8], tbs: &[u8], signature: &[u8]) -> bool {
+    let (Ok(key), Ok(sig)) = (
+        p256::ecdsa::VerifyingKey::from_sec1_bytes(public_key),
+        p256::ecdsa::Signature::from_slice(signature),
+    ) else {
+        return false;
+    };
+    key.verify(tbs, &sig).is_ok()
+}
+
+fn verify_es384(public_key: &[u8], tbs: &[u8], signature: &[u8]) -> bool {
+    let (Ok(key), Ok(sig)) = (
+        p384::ecdsa::VerifyingKey::from_sec1_bytes(public_key),
+        p384::ecdsa::Signature::from_slice(signature),
+    ) else {
+        return false;
+    };
+    key.verify(tbs, &sig).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SigAlg;
+
+    // Generated by fixtures/sig/gen.sh
+    const TBS: &[u8] = include_bytes!("../fixtures/sig/tbs.bin");
+    const ES256_PUB: &[u8] = include_bytes!("../fixtures/sig/es256.pub");
+    const ES256_SIG: &[u8] = include_bytes!("../fixtures/sig/es256.sig");
+    const ES384_PUB: &[u8] = include_bytes!("../fixtures/sig/es384.pub");
+    const ES384_SIG: &[u8] = include_bytes!("../fixtures/sig/es384.sig");
+
+    #[test]
+    fn es256_accepts_valid_signature() {
+        assert!(SigAlg::Es256.verify(ES256_PUB, TBS, ES256_SIG));
+    }
+
+    #[test]
+    fn es384_accepts_valid_signature() {
+        assert!(SigAlg::Es384.verify(ES384_PUB, TBS, ES384_SIG));
+    }
+
+    #[test]
+    fn rejects_modified_message() {
+        let mut tbs = TBS.to_vec();
+        tbs[0] ^= 1;
+        assert!(!SigAlg::Es256.verify(ES256_PUB, &tbs, ES256_SIG));
+        assert!(!SigAlg::Es384.verify(ES384_PUB, &tbs, ES384_SIG));
+    }
+
+    #[test]
+    fn rejects_modified_signature() {
+        let mut sig = ES384_SIG.to_vec();
+        sig[10] ^= 1;
+        assert!(!SigAlg::Es384.verify(ES384_PUB, TBS, &sig));
+    }
+
+    #[test]
+    fn rejects_key_for_other_curve() {
+        assert!(!SigAlg::Es256.verify(ES384_PUB, TBS, ES384_SIG));
+        assert!(!SigAlg::Es384.verify(ES256_PUB, TBS, ES256_SIG));
+    }
+
+    #[test]
+    fn rejects_truncated_signature() {
+        assert!(!SigAlg::Es256.verify(ES256_PUB, TBS, &ES256_SIG[..63]));
+        assert!(!SigAlg::Es384.verify(ES384_PUB, TBS, &[]));
+    }
+}
@@ -0,0 +1,158 @@
+//! RFC 3161 time stamps carried in a COSE `sigTst`/`sigTst2` header, read
+//! by the zkVM guest for a signing time it doesn't have to take from the
+//! (possibly unverified) actions assertion. Only `genTime` is read; the
+//! TSA's own signature isn't checked.
+
+use der::asn1::{AnyRef, ObjectIdentifier, OctetStringRef};
+use der::{Decode, Reader, SliceReader, Tag, Tagged};
+
+/// id-signedData
+const OID_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+/// id-ct-TSTInfo
+const OID_TST_INFO: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.1.4");
+
+/// Unix seconds of the first token in a C2PA `sigTst`/`sigTst2` header
+/// value, `{"tstTokens": [{"val": bstr}]}`.
+pub fn sig_tst_time(header: &ciborium::Value) -> Option<i64> {
+    let tokens = map_get(header, "tstTokens")?.as_array()?;
+    let val = map_get(tokens.first()?, "val")?.as_bytes()?;
+    gen_time(val)
+}
+
+fn map_get<'a>(value: &'a ciborium::Value, key: &str) -> Option<&'a ciborium::Value> {
+    value
+        .as_map()?
+        .iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, v)| v)
+}
+
+/// Unix seconds of `genTime` in a DER TimeStampResp or bare TimeStampToken.
+pub fn gen_time(der: &[u8]) -> Option<i64> {
+    let outer = AnyRef::from_der(der).ok()?;
+    let items = children(outer)?;
+    // TimeStampResp is { status PKIStatusInfo, timeStampToken }; a token
+    // (ContentInfo) starts with its content type OID
+    let content_info = if items.first()?.tag() == Tag::Sequence {
+        *items.get(1)?
+    } else {
+        outer
+    };
+
+    let [content_type, content, ..] = children(content_info)?[..] else {
+        return None;
+    };
+    if content_type.decode_as::<ObjectIdentifier>().ok()? != OID_SIGNED_DATA {
+        return None;
+    }
+    // SignedData { version, digestAlgorithms, encapContentInfo, ... }
+    let signed_data = AnyRef::from_der(content.value()).ok()?;
+    let encap = *children(signed_data)?.get(2)?;
+    let [e_content_type, e_content, ..] = children(encap)?[..] else {
+        return None;
+    };
+    if e_content_type.decode_as::<ObjectIdentifier>().ok()? != OID_TST_INFO {
+        return None;
+    }
+    let tst_info = OctetStringRef::from_der(e_content.value()).ok()?;
+
+    // TSTInfo { version, policy, messageImprint, serialNumber, genTime, ... }
+    let tst_info = AnyRef::from_der(tst_info.as_bytes()).ok()?;
+    let gen_time = *children(tst_info)?.get(4)?;
+    if gen_time.tag() != Tag::GeneralizedTime {
+        return None;
+    }
+    parse_generalized_time(gen_time.value())
+}
+
+/// The elements of a constructed value.
+fn children(any: AnyRef<'_>) -> Option<Vec<AnyRef<'_>>> {
+    let mut reader = SliceReader::new(any.value()).ok()?;
+    let mut out = Vec::new();
+    while !reader.is_finished() {
+        out.push(AnyRef::decode(&mut reader).ok()?);
+    }
+    Some(out)
+}
+
+/// "YYYYMMDDHHMMSS[.fff]Z" as unix seconds. `der`'s GeneralizedTime
+/// rejects the fractional seconds TSAs commonly include, so parse by hand.
+fn parse_generalized_time(value: &[u8]) -> Option<i64> {
+    if value.len() < 15 || value.last() != Some(&b'Z') {
+        return None;
+    }
+    let digits = |range: core::ops::Range<usize>| -> Option<u16> {
+        core::str::from_utf8(value.get(range)?).ok()?.parse().ok()
+    };
+    let fraction_ok = match &value[14..value.len() - 1] {
+        [] => true,
+        [b'.', frac @ ..] => !frac.is_empty() && frac.iter().all(u8::is_ascii_digit),
+        _ => false,
+    };
+    if !fraction_ok {
+        return None;
+    }
+    let time = der::DateTime::new(
+        digits(0..4)?,
+        digits(4..6)? as u8,
+        digits(6..8)? as u8,
+        digits(8..10)? as u8,
+        digits(10..12)? as u8,
+        digits(12..14)? as u8,
+    )
+    .ok()?;
+    Some(time.unix_duration().as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generated by fixtures/timestamp/gen.sh
+    const RESPONSE: &[u8] = include_bytes!("../fixtures/timestamp/response.tsr");
+    const TOKEN: &[u8] = include_bytes!("../fixtures/timestamp/token.der");
+
+    fn expected() -> i64 {
+        include_str!("../fixtures/timestamp/gen_time.txt").trim().parse().unwrap()
+    }
+
+    fn header(val: &[u8]) -> ciborium::Value {
+        ciborium::Value::Map(vec![(
+            "tstTokens".into(),
+            ciborium::Value::Array(vec![ciborium::Value::Map(vec![(
+                "val".into(),
+                ciborium::Value::Bytes(val.to_vec()),
+            )])]),
+        )])
+    }
+
+    #[test]
+    fn reads_gen_time_from_response() {
+        assert_eq!(gen_time(RESPONSE), Some(expected()));
+    }
+
+    #[test]
+    fn reads_gen_time_from_token() {
+        assert_eq!(gen_time(TOKEN), Some(expected()));
+    }
+
+    #[test]
+    fn reads_sig_tst_header() {
+        assert_eq!(sig_tst_time(&header(RESPONSE)), Some(expected()));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert_eq!(gen_time(b"not der"), None);
+        assert_eq!(sig_tst_time(&header(b"not der")), None);
+        assert_eq!(sig_tst_time(&ciborium::Value::Map(vec![])), None);
+    }
+
+    #[test]
+    fn generalized_time_with_and_without_fraction() {
+        assert_eq!(parse_generalized_time(b"20240101000000Z"), Some(1_704_067_200));
+        assert_eq!(parse_generalized_time(b"20240101000000.25Z"), Some(1_704_067_200));
+        assert_eq!(parse_generalized_time(b"20240101000000.Z"), None);
+        assert_eq!(parse_generalized_time(b"20240101000000+0100"), None);
+    }
+}
@@ -0,0 +1,129 @@
+//! JUMBF box-tree parsing benchmarks.
+//!
+//!     cargo bench --bench jumbf_bench
+//!
+//! The C2PA JUMBF is pulled out of each fixture once; the benchmarks time
+//! walking it for the claim, signature and assertions (`_parts`) and the
+//! active manifest's assertion superboxes (`_superboxes`), as the prover
+//! does before building `CryptoEvidence`. Each is timed with the current
+//! extractor (`new`) and the copying walk it replaced (`old`, in
+//! `support/jumbf_baseline.rs`):
+//!
+//! | benchmark         | fixture                                  | JUMBF                         |
+//! |-------------------|------------------------------------------|-------------------------------|
+//! | jumbf_png         | chatgpt.png                              | 96 KB, 2 manifests            |
+//! | jumbf_mp4         | truepic-20230212-zoetrope.mp4            | 30 KB in a 15 MB file         |
+//! | jumbf_jpeg_multi  | adobe-20220124-CAIAIIICAICIICAIICICA.jpg | 1.5 MB, ingredient manifests  |
+//! | jumbf_synth_10mb  | generated by [`synthetic_store`]         | 10 MB, 10 manifests           |
+
+use std::path::{Path, PathBuf};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[allow(dead_code, unused_imports, clippy::collapsible_if, clippy::type_complexity)]
+#[path = "../../prover/script/src/jumbf_extract.rs"]
+mod jumbf_extract;
+
+#[allow(dead_code, clippy::collapsible_if, clippy::type_complexity)]
+#[path = "support/jumbf_baseline.rs"]
+mod jumbf_baseline;
+
+use prover_shared::Bytes;
+
+fn repo_path(rel: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..").join(rel)
+}
+
+/// Box with a 4-byte type around `content`.
+fn jumbf_box(box_type: &[u8; 4], content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(8 + content.len());
+    out.extend_from_slice(&(8 + content.len() as u32).to_be_bytes());
+    out.extend_from_slice(box_type);
+    out.extend_from_slice(content);
+    out
+}
+
+/// Labelled JUMBF superbox: a jumd description followed by `children`.
+fn superbox(label: &str, children: &[Vec<u8>]) -> Vec<u8> {
+    let mut jumd = vec![0u8; 16]; // type UUID, not read by the extractor
+    jumd.push(0x03); // requestable, label present
+    jumd.extend_from_slice(label.as_bytes());
+    jumd.push(0);
+    let mut content = jumbf_box(b"jumd", &jumd);
+    for child in children {
+        content.extend_from_slice(child);
+    }
+    jumbf_box(b"jumb", &content)
+}
+
+/// Manifest store of `manifests` manifests, each with a claim, a signature
+/// and `assertions` assertions of `assertion_size` bytes. The last manifest
+/// is the active one, the rest stand in for ingredients.
+fn synthetic_store(manifests: usize, assertions: usize, assertion_size: usize) -> Bytes {
+    let payload = vec![0xa5u8; assertion_size];
+    let manifests: Vec<_> = (0..manifests)
+        .map(|m| {
+            let assertions: Vec<_> = (0..assertions)
+                .map(|a| superbox(&format!("r3l.synthetic.{a}"), &[jumbf_box(b"cbor", &payload)]))
+                .collect();
+            superbox(
+                &format!("urn:uuid:00000000-0000-0000-0000-{m:012}"),
+                &[
+                    superbox("c2pa.assertions", &assertions),
+                    superbox("c2pa.claim.v2", &[jumbf_box(b"cbor", &[0xa0; 512])]),
+                    superbox("c2pa.signature", &[jumbf_box(b"cbor", &[0xd2; 4096])]),
+                ],
+            )
+        })
+        .collect();
+    Bytes::from(superbox("c2pa", &manifests))
+}
+
+fn bench_store(c: &mut Criterion, name: &str, jumbf: &Bytes) {
+    let mut parts = c.benchmark_group(format!("{name}_parts"));
+    parts.bench_function("old", |b| b.iter(|| jumbf_baseline::extract_manifest_parts(black_box(jumbf))));
+    parts.bench_function("new", |b| b.iter(|| jumbf_extract::extract_manifest_parts(black_box(jumbf))));
+    parts.finish();
+
+    let mut superboxes = c.benchmark_group(format!("{name}_superboxes"));
+    superboxes.bench_function("old", |b| {
+        b.iter(|| jumbf_baseline::extract_assertion_superboxes(black_box(jumbf)))
+    });
+    superboxes.bench_function("new", |b| {
+        b.iter(|| jumbf_extract::extract_assertion_superboxes(black_box(jumbf)))
+    });
+    superboxes.finish();
+}
+
+fn bench_fixture(c: &mut Criterion, name: &str, fixture: &str) {
+    let path = repo_path(&format!("data/test_files/{fixture}"));
+    let Ok(file_bytes) = std::fs::read(&path) else {
+        eprintln!("skipping {name}: {} not found", path.display());
+        return;
+    };
+    let Some(jumbf) = jumbf_extract::extract_jumbf(&file_bytes).1.map(Bytes::from) else {
+        eprintln!("skipping {name}: no JUMBF in {fixture}");
+        return;
+    };
+    bench_store(c, name, &jumbf);
+}
+
+fn jumbf_benches(c: &mut Criterion) {
+    bench_fixture(c, "jumbf_png", "chatgpt.png");
+    bench_fixture(c, "jumbf_mp4", "truepic-20230212-zoetrope.mp4");
+    bench_fixture(c, "jumbf_jpeg_multi", "adobe-20220124-CAIAIIICAICIICAIICICA.jpg");
+
+    let synthetic = synthetic_store(10, 20, 50 * 1024);
+    let old = jumbf_baseline::extract_manifest_parts(&synthetic).expect("synthetic store parses");
+    let new = jumbf_extract::extract_manifest_parts(&synthetic).expect("synthetic store parses");
+    assert_eq!(old.2.len(), 10 * 20);
+    assert_eq!((&old.0[..], &old.1[..]), (&new.0[..], &new.1[..]));
+    bench_store(c, "jumbf_synth_10mb", &synthetic);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = jumbf_benches
+}
+criterion_main!(benches);
@@ -0,0 +1,233 @@
+//! The JUMBF box walk as it was before payloads became `Bytes` views:
+//! boxes borrow `&[u8]` and each payload is copied out with `to_vec()`.
+//! Kept verbatim so `jumbf_bench` can time old against new on the same input.
+
+struct BmffBox<'a> {
+    box_type: [u8; 4],
+    data: &'a [u8], // content after the 8-byte header
+}
+
+/// Parse consecutive ISO BMFF boxes from a byte slice.
+fn parse_boxes(data: &[u8]) -> Vec<BmffBox<'_>> {
+    let mut result = Vec::new();
+    let mut pos = 0;
+
+    while pos + 8 <= data.len() {
+        let size =
+            u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap_or([0; 4])) as usize;
+
+        if size < 8 || pos + size > data.len() {
+            break;
+        }
+
+        let box_type: [u8; 4] = data[pos + 4..pos + 8].try_into().unwrap_or([0; 4]);
+        let content = &data[pos + 8..pos + size];
+
+        result.push(BmffBox {
+            box_type,
+            data: content,
+        });
+        pos += size;
+    }
+
+    result
+}
+
+/// Extract the label from a JUMD (JUMBF Description) box's content.
+/// Layout: [UUID:16][toggles:1][label?][id?][hash?]
+fn parse_jumd_label(data: &[u8]) -> Option<String> {
+    if data.len() < 17 {
+        return None;
+    }
+
+    let toggles = data[16];
+    let has_label = toggles & 0x02 != 0;
+
+    if !has_label {
+        return None;
+    }
+
+    let label_start = 17;
+    let null_pos = data[label_start..].iter().position(|&b| b == 0)?;
+    std::str::from_utf8(&data[label_start..label_start + null_pos])
+        .ok()
+        .map(String::from)
+}
+
+/// Walk the JUMBF box tree to find claim CBOR, COSE_Sign1 signature,
+/// and assertion boxes. Claim + signature come from the active (last) manifest.
+/// Assertions are collected from ALL manifests so ingredient metadata is available.
+/// Returns (claim_cbor, cose_sign1, assertion_boxes).
+pub fn extract_manifest_parts(
+    jumbf: &[u8],
+) -> Option<(Vec<u8>, Vec<u8>, Vec<(String, Vec<u8>)>)> {
+    let top_boxes = parse_boxes(jumbf);
+
+    // Top-level should be a single jumb box (C2PA manifest store)
+    let store = top_boxes.iter().find(|b| &b.box_type == b"jumb")?;
+    let store_children = parse_boxes(store.data);
+
+    let manifests: Vec<_> = store_children
+        .iter()
+        .filter(|b| &b.box_type == b"jumb")
+        .collect();
+
+    if manifests.is_empty() {
+        return None;
+    }
+
+    // Active manifest = last jumb child in the store (per C2PA spec)
+    let active = manifests.last().unwrap();
+    let active_children = parse_boxes(active.data);
+
+    let mut claim_cbor = None;
+    let mut cose_sign1 = None;
+
+    for child in &active_children {
+        if &child.box_type != b"jumb" {
+            continue;
+        }
+        let inner = parse_boxes(child.data);
+        let label = inner
+            .first()
+            .filter(|b| &b.box_type == b"jumd")
+            .and_then(|b| parse_jumd_label(b.data));
+        match label.as_deref() {
+            Some(l) if l.starts_with("c2pa.claim") => {
+                if let Some(content_box) = inner.get(1) {
+                    claim_cbor = Some(content_box.data.to_vec());
+                }
+            }
+            Some(l) if l.starts_with("c2pa.signature") => {
+                if let Some(content_box) = inner.get(1) {
+                    let raw = extract_embedded_content(content_box);
+                    cose_sign1 = Some(raw.to_vec());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Collect assertions from ALL manifests (active + ingredients)
+    let mut assertions = Vec::new();
+    for manifest in &manifests {
+        let children = parse_boxes(manifest.data);
+        for child in &children {
+            if &child.box_type != b"jumb" {
+                continue;
+            }
+            let inner = parse_boxes(child.data);
+            let label = inner
+                .first()
+                .filter(|b| &b.box_type == b"jumd")
+                .and_then(|b| parse_jumd_label(b.data));
+            if let Some(l) = &label {
+                if l == "c2pa.assertions" {
+                    extract_assertions_from_store(&inner[1..], &mut assertions);
+                }
+            }
+        }
+    }
+
+    match (claim_cbor, cose_sign1) {
+        (Some(claim), Some(sig)) => Some((claim, sig, assertions)),
+        _ => None,
+    }
+}
+
+/// Collect the active manifest's assertions as (label, superbox payload).
+/// The payload is the assertion's jumb contents (jumd + content boxes, no
+/// header), which is what the claim's hashed URIs commit to.
+pub fn extract_assertion_superboxes(jumbf: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let top_boxes = parse_boxes(jumbf);
+    let Some(store) = top_boxes.iter().find(|b| &b.box_type == b"jumb") else {
+        return Vec::new();
+    };
+    let store_children = parse_boxes(store.data);
+    let Some(active) = store_children.iter().rfind(|b| &b.box_type == b"jumb") else {
+        return Vec::new();
+    };
+
+    let mut out = Vec::new();
+    for child in parse_boxes(active.data) {
+        if &child.box_type != b"jumb" {
+            continue;
+        }
+        let inner = parse_boxes(child.data);
+        let label = inner
+            .first()
+            .filter(|b| &b.box_type == b"jumd")
+            .and_then(|b| parse_jumd_label(b.data));
+        if label.as_deref() != Some("c2pa.assertions") {
+            continue;
+        }
+        for assertion in inner.iter().skip(1).filter(|b| &b.box_type == b"jumb") {
+            let label = parse_boxes(assertion.data)
+                .first()
+                .filter(|b| &b.box_type == b"jumd")
+                .and_then(|b| parse_jumd_label(b.data));
+            if let Some(label) = label {
+                out.push((label, assertion.data.to_vec()));
+            }
+        }
+    }
+    out
+}
+
+/// Parse individual assertion boxes from an assertion store superbox.
+fn extract_assertions_from_store(
+    children: &[BmffBox<'_>],
+    out: &mut Vec<(String, Vec<u8>)>,
+) {
+    for child in children {
+        if &child.box_type != b"jumb" {
+            continue;
+        }
+        let inner = parse_boxes(child.data);
+        let label = inner
+            .first()
+            .filter(|b| &b.box_type == b"jumd")
+            .and_then(|b| parse_jumd_label(b.data));
+        if let Some(al) = label {
+            if let Some(content_box) = inner.get(1) {
+                let raw = extract_embedded_content(content_box);
+                out.push((al, raw.to_vec()));
+            }
+        }
+    }
+}
+
+/// For a bfdb (embedded file content) box, skip the toggle byte and
+/// optional media-type/filename strings to get to the raw content.
+/// For any other box type, return the data as-is.
+fn extract_embedded_content<'a>(content_box: &BmffBox<'a>) -> &'a [u8] {
+    if &content_box.box_type == b"bfdb" {
+        skip_bfdb_header(content_box.data)
+    } else {
+        content_box.data
+    }
+}
+
+fn skip_bfdb_header(data: &[u8]) -> &[u8] {
+    if data.is_empty() {
+        return data;
+    }
+    let toggle = data[0];
+    let mut pos = 1;
+
+    // Bit 0: media type present (null-terminated string)
+    if toggle & 0x01 != 0 {
+        if let Some(null_pos) = data[pos..].iter().position(|&b| b == 0) {
+            pos += null_pos + 1;
+        }
+    }
+    // Bit 1: file name present (null-terminated string)
+    if toggle & 0x02 != 0 {
+        if let Some(null_pos) = data[pos..].iter().position(|&b| b == 0) {
+            pos += null_pos + 1;
+        }
+    }
+
+    &data[pos..]
+}
+
@@ -0,0 +1,66 @@
+//! Verifier critical-path benchmarks.
+//!
+//!     cargo bench --bench verify_bench
+//!
+//! Fixtures come from `data/test_files`; the benchmark names describe the
+//! size class, the closest file in the repo is used for each:
+//!
+//! | benchmark         | fixture                          | size   |
+//! |-------------------|----------------------------------|--------|
+//! | verify_png_100kb  | a2.png                           | 50 KB  |
+//! | verify_png_5mb    | chatgpt.png                      | 2.2 MB |
+//! | verify_jpeg_1mb   | nikon-20221019-building.jpeg     | 1.5 MB |
+//! | verify_mp4_50mb   | truepic-20230212-zoetrope.mp4    | 15 MB  |
+
+use std::path::{Path, PathBuf};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn repo_path(rel: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..").join(rel)
+}
+
+fn trust_dir() -> PathBuf {
+    repo_path("data/trust")
+}
+
+fn bench_verify(c: &mut Criterion, name: &str, fixture: &str) {
+    let path = repo_path(&format!("data/test_files/{fixture}"));
+    if !path.exists() {
+        eprintln!("skipping {name}: {} not found", path.display());
+        return;
+    }
+    let path = path.to_str().unwrap().to_string();
+    let trust = trust_dir().to_str().unwrap().to_string();
+    c.bench_function(name, |b| {
+        b.iter(|| verifier::verify(black_box(&path), black_box(&trust)).unwrap())
+    });
+}
+
+fn verify_benches(c: &mut Criterion) {
+    bench_verify(c, "verify_png_100kb", "a2.png");
+    bench_verify(c, "verify_png_5mb", "chatgpt.png");
+    bench_verify(c, "verify_jpeg_1mb", "nikon-20221019-building.jpeg");
+    bench_verify(c, "verify_mp4_50mb", "truepic-20230212-zoetrope.mp4");
+}
+
+fn trust_benches(c: &mut Criterion) {
+    let official = trust_dir().join("official");
+    c.bench_function("load_pems_official", |b| {
+        b.iter(|| verifier::load_pems(black_box(&official)).unwrap())
+    });
+
+    let official_pem = verifier::load_pems(&official).unwrap();
+    let path = repo_path("data/test_files/a2.png");
+    let path = path.to_str().unwrap();
+    c.bench_function("resolve_trust_official", |b| {
+        b.iter(|| verifier::resolve_trust(black_box(path), black_box(&official_pem), "").unwrap())
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = verify_benches, trust_benches
+}
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+# Regenerate the OCSP fixtures used by src/ocsp.rs tests: a CA, three
+# leaves whose AIA names an OCSP responder, and the CA's OCSP responses
+# for each leaf (good, revoked, unknown to the responder).
+set -euo pipefail
+cd "$(dirname "$0")"
+work=$(mktemp -d)
+trap 'rm -rf "$work"' EXIT
+
+key() { openssl ecparam -name prime256v1 -genkey -noout -out "$1"; }
+
+key "$work/ca.key"
+openssl req -x509 -new -key "$work/ca.key" -subj "/CN=r3l OCSP fixture CA" -days 36500 \
+    -addext "basicConstraints = critical, CA:true" -addext "keyUsage = critical, keyCertSign, cRLSign" \
+    -out "$work/ca.pem"
+openssl x509 -in "$work/ca.pem" -outform DER -out ca.der
+
+printf '[ext]\nkeyUsage = critical, digitalSignature\nauthorityInfoAccess = OCSP;URI:http://ocsp.r3l.test/\n' > "$work/leaf.cnf"
+: > "$work/index.txt"
+for name in good revoked unknown; do
+    key "$work/$name.key"
+    openssl req -new -key "$work/$name.key" -subj "/CN=r3l OCSP $name leaf" -out "$work/$name.csr"
+    openssl x509 -req -in "$work/$name.csr" -CA "$work/ca.pem" -CAkey "$work/ca.key" -CAcreateserial \
+        -days 36500 -extfile "$work/leaf.cnf" -extensions ext -out "$work/$name.pem" 2>/dev/null
+    openssl x509 -in "$work/$name.pem" -outform DER -out "$name.der"
+done
+
+# The responder's database knows "good" and "revoked" but not "unknown"
+serial() { openssl x509 -in "$work/$1.pem" -noout -serial | cut -d= -f2; }
+printf 'V\t21000101000000Z\t\t%s\tunknown\t/CN=r3l OCSP good leaf\n' "$(serial good)" >> "$work/index.txt"
+printf 'R\t21000101000000Z\t240101000000Z\t%s\tunknown\t/CN=r3l OCSP revoked leaf\n' "$(serial revoked)" >> "$work/index.txt"
+
+for name in good revoked unknown; do
+    openssl ocsp -issuer "$work/ca.pem" -cert "$work/$name.pem" -no_nonce -reqout "$work/$name.req"
+    openssl ocsp -index "$work/index.txt" -CA "$work/ca.pem" -rsigner "$work/ca.pem" -rkey "$work/ca.key" \
+        -reqin "$work/$name.req" -respout "$name.resp" -ndays 36500 >/dev/null
+done
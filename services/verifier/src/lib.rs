@@ -1,58 +1,235 @@
 use anyhow::{Context as AnyhowContext, Result};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{DebounceEventResult, Debouncer, new_debouncer};
+use rayon::prelude::*;
 use serde::Serialize;
 use serde_json::Value;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha384};
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+// The prover's JUMBF extractor, used as a fallback when c2pa-rs can't parse a file.
+// Built as edition 2021 in the prover, so skip lints that need let-chains.
+#[allow(dead_code, unused_imports, clippy::collapsible_if, clippy::type_complexity)]
+#[path = "../../prover/script/src/jumbf_extract.rs"]
+mod jumbf_extract;
+pub mod ocsp;
+
+pub use prover_shared::{canonical_software_agent, normalize_issuer, ContentHash, TrustLevel, ValidationState};
 
 const DEFAULT_TRUST_DIR: &str = "/data/trust";
 
+/// Quiet period after a trust dir change before [`TrustStore`] reloads, so
+/// a copy of several PEMs triggers one reload
+const TRUST_RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// HTTP client for c2pa-rs fetches and [`ocsp`] requests. `Context::new()`
+/// builds a fresh agent each time, so every verification opened its own
+/// connections; this one is cloned into each context and shares its pool
+/// across threads. Short timeout since OCSP is on the verification path.
+static HTTP_CLIENT: LazyLock<reqwest::blocking::Client> = LazyLock::new(|| {
+    reqwest::blocking::Client::builder()
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(8)
+        .timeout(Duration::from_secs(3))
+        .build()
+        .expect("building HTTP client")
+});
+
+/// Extensions of formats that can carry an embedded C2PA manifest.
+const C2PA_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "webp", "mp4", "m4v", "mov", "heic", "heif", "avif", "pdf",
+];
+
+/// Hash used for `cert_fingerprint`. The prover's `PublicOutputs` always
+/// commits SHA-256; other algorithms are for off-chain PKI lookups.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FingerprintAlg {
+    Sha1,
+    #[default]
+    Sha256,
+    Sha384,
+}
+
+impl FingerprintAlg {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sha1 => "sha1",
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+        }
+    }
+
+    /// Hex digest of a DER certificate
+    pub fn fingerprint(self, der: &[u8]) -> String {
+        match self {
+            Self::Sha1 => hex::encode(sha1::Sha1::digest(der)),
+            Self::Sha256 => hex::encode(Sha256::digest(der)),
+            Self::Sha384 => hex::encode(Sha384::digest(der)),
+        }
+    }
+}
+
+impl FromStr for FingerprintAlg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace('-', "").as_str() {
+            "sha1" => Ok(Self::Sha1),
+            "sha256" => Ok(Self::Sha256),
+            "sha384" => Ok(Self::Sha384),
+            _ => anyhow::bail!("unknown fingerprint algorithm {s:?} (expected sha1, sha256 or sha384)"),
+        }
+    }
+}
+
+impl fmt::Display for FingerprintAlg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Serialize)]
 pub struct VerifyOutput {
     pub path: String,
-    pub content_hash: Option<String>,
+    pub content_hash: Option<ContentHash>,
     pub has_c2pa: bool,
-    pub trust_list_match: Option<String>,
-    pub validation_state: Option<String>,
+    pub trust_list_match: Option<TrustLevel>,
+    /// "Verified", "SignatureOnly" or "None" (same vocabulary as the prover
+    /// guest), or "ParseError" when only the raw JUMBF fallback could read it
+    pub validation_state: Option<ValidationState>,
+    /// 0 = None, 1 = SignatureOnly, 2 = Verified
+    pub validation_state_code: Option<u8>,
     pub validation_error_count: Option<usize>,
     pub validation_codes: Option<Vec<String>>,
     pub title: Option<String>,
     pub format: Option<String>,
+    /// MIME type from magic-byte sniffing of the file itself
+    pub format_detected: Option<String>,
+    /// Format the manifest claims (same as `format`)
+    pub format_claimed: Option<String>,
+    /// Claimed and detected formats are both known and disagree
+    pub format_mismatch: bool,
     pub digital_source_type: Option<String>,
     pub claim_generator: Option<String>,
     pub software_agent: Option<String>,
+    /// `software_agent` mapped to a canonical product name for filtering by tool
+    pub software_agent_canonical: Option<String>,
     pub issuer: Option<String>,
+    /// `issuer` mapped to a canonical organization name for grouping
+    pub issuer_normalized: Option<String>,
     pub common_name: Option<String>,
     pub signing_time: Option<String>,
     pub sig_algorithm: Option<String>,
     pub actions: Option<Value>,
     pub ingredients: Option<Value>,
+    /// Active manifest lists at least one ingredient (derived work)
+    pub has_ingredients: bool,
+    pub ingredient_count: u32,
     pub manifest_store: Option<Value>,
+    /// SHA-256 hex of the store's manifests (JSON, sorted keys, no validation
+    /// results); the same manifest re-embedded in a different file keeps the same hash
+    pub manifest_hash: Option<String>,
+    /// SHA-256 hex of the trust list PEMs this file was checked against
+    pub trust_bundle_hash: Option<String>,
+    /// Labels of the active manifest's assertions, sorted
+    pub assertion_labels: Option<Vec<String>>,
+    /// SHA-256 hex of `assertion_labels` as JSON; differs between two
+    /// versions of a manifest that add or drop assertions
+    pub assertions_digest: Option<String>,
+    pub exif_make: Option<String>,
+    pub exif_model: Option<String>,
+    /// GPS position in decimal degrees (negative = S / W)
+    pub exif_gps_lat: Option<f64>,
+    pub exif_gps_lon: Option<f64>,
+    /// Location from `c2pa.location.broad` or CreativeWork `locationCreated`
+    pub location_country: Option<String>,
+    pub location_region: Option<String>,
+    pub location_city: Option<String>,
+    /// Decimal degrees from CreativeWork `locationCreated.geo`
+    pub location_lat: Option<f64>,
+    pub location_lon: Option<f64>,
+    /// JUMD label of the active manifest (e.g. "urn:uuid:...")
+    pub manifest_label: Option<String>,
+    /// Key of the active manifest in the store's `manifests` map
+    pub active_manifest_label: Option<String>,
+    pub container_brand: Option<String>,
+    pub compatible_brands: Option<Vec<String>>,
+    /// Hex digest of the leaf signing cert, using `fingerprint_alg`
+    pub cert_fingerprint: Option<String>,
+    /// "sha1", "sha256" or "sha384"; set whenever `cert_fingerprint` is
+    pub fingerprint_alg: Option<String>,
+    /// Certificates in the signer's chain, leaf included
+    pub cert_chain_depth: Option<usize>,
+    /// Signing cert revocation per OCSP: "good", "revoked", "unknown" or
+    /// "error". Only checked with `ENABLE_OCSP_CHECK=true`; None if not
+    /// checked or the cert names no OCSP responder.
+    pub ocsp_status: Option<String>,
+    /// `ocsp_status` is "revoked"
+    pub cert_revoked: bool,
+    /// c2pa-rs failed and fields came from raw JUMBF extraction instead
+    pub parse_fallback_used: bool,
     pub error: Option<String>,
 }
 
 impl VerifyOutput {
-    fn unsigned(path: String, content_hash: Option<String>) -> Self {
+    fn unsigned(path: String, content_hash: Option<ContentHash>) -> Self {
         Self {
             path,
             content_hash,
             has_c2pa: false,
             trust_list_match: None,
             validation_state: None,
+            validation_state_code: None,
             validation_error_count: None,
             validation_codes: None,
             title: None,
             format: None,
+            format_detected: None,
+            format_claimed: None,
+            format_mismatch: false,
             digital_source_type: None,
             claim_generator: None,
             software_agent: None,
+            software_agent_canonical: None,
             issuer: None,
+            issuer_normalized: None,
             common_name: None,
             signing_time: None,
             sig_algorithm: None,
             actions: None,
             ingredients: None,
+            has_ingredients: false,
+            ingredient_count: 0,
             manifest_store: None,
+            manifest_hash: None,
+            trust_bundle_hash: None,
+            assertion_labels: None,
+            assertions_digest: None,
+            exif_make: None,
+            exif_model: None,
+            exif_gps_lat: None,
+            exif_gps_lon: None,
+            location_country: None,
+            location_region: None,
+            location_city: None,
+            location_lat: None,
+            location_lon: None,
+            manifest_label: None,
+            active_manifest_label: None,
+            container_brand: None,
+            compatible_brands: None,
+            cert_fingerprint: None,
+            fingerprint_alg: None,
+            cert_chain_depth: None,
+            ocsp_status: None,
+            cert_revoked: false,
+            parse_fallback_used: false,
             error: None,
         }
     }
@@ -62,89 +239,347 @@ impl VerifyOutput {
         o.error = Some(error);
         o
     }
+
+    /// Mark an output c2pa-rs couldn't parse with what raw JUMBF extraction found.
+    fn apply_partial(&mut self, partial: PartialManifest, alg: FingerprintAlg) {
+        self.has_c2pa = true;
+        self.validation_state = Some(ValidationState::ParseError);
+        self.fingerprint_alg = partial.cert_fingerprint.as_ref().map(|_| alg.to_string());
+        self.cert_fingerprint = partial.cert_fingerprint;
+        self.cert_chain_depth = partial.cert_chain_depth;
+        self.parse_fallback_used = true;
+    }
 }
 
 /// Verify a file's C2PA provenance and return structured output.
 pub fn verify(path: &str, trust_dir: &str) -> Result<VerifyOutput> {
+    verify_with_alg(path, trust_dir, FingerprintAlg::default())
+}
+
+/// Like [`verify`], fingerprinting the signing cert with `alg`.
+pub fn verify_with_alg(path: &str, trust_dir: &str, alg: FingerprintAlg) -> Result<VerifyOutput> {
+    verify_with_anchors(path, &TrustAnchors::load(trust_dir)?, alg)
+}
+
+/// Like [`verify_with_alg`], using the anchors currently in `store`.
+pub fn verify_with_store(path: &str, store: &TrustStore, alg: FingerprintAlg) -> Result<VerifyOutput> {
+    verify_with_anchors(path, &store.anchors(), alg)
+}
+
+fn verify_with_anchors(path: &str, anchors: &TrustAnchors, alg: FingerprintAlg) -> Result<VerifyOutput> {
     anyhow::ensure!(Path::new(path).exists(), "File not found: {}", path);
 
     // Compute content hash (SHA-256 of file bytes)
     let file_bytes = fs::read(path)
         .with_context(|| format!("reading file: {path}"))?;
-    let content_hash = Some(hex::encode(Sha256::digest(&file_bytes)));
+    let content_hash = Some(ContentHash::from(<[u8; 32]>::from(Sha256::digest(&file_bytes))));
+    let (container_brand, compatible_brands) = parse_ftyp(&file_bytes).unzip();
+    let detected = infer::get(&file_bytes);
+    let format_detected = detected.map(|t| t.mime_type().to_string());
 
-    let trust_path = Path::new(trust_dir);
-    let official_pem = load_pems(&trust_path.join("official"))?;
-    let curated_pem = load_pems(&trust_path.join("curated"))?;
+    let trust_bundle_hash = Some(anchors.bundle_hash.clone());
 
-    let (reader, trust_list_match) = match resolve_trust(path, &official_pem, &curated_pem)? {
-        None => return Ok(VerifyOutput::unsigned(path.to_string(), content_hash)),
-        Some(pair) => pair,
+    let mut out = match resolve_trust(path, &anchors.official_pem, &anchors.curated_pem)? {
+        None => {
+            let mut out = VerifyOutput::unsigned(path.to_string(), content_hash);
+            if let Some(partial) = parse_fallback(path, &file_bytes, alg) {
+                out.apply_partial(partial, alg);
+            }
+            out
+        }
+        Some((reader, trust_list_match)) => {
+            let mut out = reader_output(path.to_string(), content_hash, &reader, trust_list_match, alg, false)?;
+            out.format_mismatch = match (&out.format, detected) {
+                (Some(claimed), Some(detected)) => !format_matches(claimed, &detected),
+                _ => false,
+            };
+            out
+        }
     };
+    out.format_detected = format_detected;
+    out.container_brand = container_brand;
+    out.compatible_brands = compatible_brands;
+    out.trust_bundle_hash = trust_bundle_hash;
+    Ok(out)
+}
 
+/// Verify a bare C2PA manifest store (JUMBF) with no container file around
+/// it, e.g. one kept in a database or received over the wire.
+///
+/// The asset bytes aren't available, so `content_hash` is reported as given
+/// and hard-binding hash mismatches don't count as integrity failures.
+pub fn verify_jumbf(jumbf_bytes: &[u8], content_hash: ContentHash, trust_dir: &str) -> Result<VerifyOutput> {
+    verify_jumbf_with_alg(jumbf_bytes, content_hash, trust_dir, FingerprintAlg::default())
+}
+
+/// Like [`verify_jumbf`], fingerprinting the signing cert with `alg`.
+pub fn verify_jumbf_with_alg(
+    jumbf_bytes: &[u8],
+    content_hash: ContentHash,
+    trust_dir: &str,
+    alg: FingerprintAlg,
+) -> Result<VerifyOutput> {
+    verify_jumbf_with_anchors(jumbf_bytes, content_hash, &TrustAnchors::load(trust_dir)?, alg)
+}
+
+/// Like [`verify_jumbf_with_alg`], using the anchors currently in `store`.
+pub fn verify_jumbf_with_store(
+    jumbf_bytes: &[u8],
+    content_hash: ContentHash,
+    store: &TrustStore,
+    alg: FingerprintAlg,
+) -> Result<VerifyOutput> {
+    verify_jumbf_with_anchors(jumbf_bytes, content_hash, &store.anchors(), alg)
+}
+
+fn verify_jumbf_with_anchors(
+    jumbf_bytes: &[u8],
+    content_hash: ContentHash,
+    anchors: &TrustAnchors,
+    alg: FingerprintAlg,
+) -> Result<VerifyOutput> {
+    let content_hash = Some(content_hash);
+    let trust_bundle_hash = Some(anchors.bundle_hash.clone());
+
+    // No claim + signature boxes: nothing to verify
+    let Some(partial) = partial_manifest(&prover_shared::Bytes::copy_from_slice(jumbf_bytes), alg) else {
+        let mut out = VerifyOutput::unsigned(String::new(), content_hash);
+        out.trust_bundle_hash = trust_bundle_hash;
+        return Ok(out);
+    };
+
+    let read = |trust_pem: &str| try_read_jumbf(jumbf_bytes, trust_pem);
+    let mut out = match resolve_trust_with(&anchors.official_pem, &anchors.curated_pem, read)? {
+        None => {
+            let mut out = VerifyOutput::unsigned(String::new(), content_hash);
+            out.apply_partial(partial, alg);
+            out
+        }
+        Some((reader, trust_list_match)) => {
+            reader_output(String::new(), content_hash, &reader, trust_list_match, alg, true)?
+        }
+    };
+    out.trust_bundle_hash = trust_bundle_hash;
+    Ok(out)
+}
+
+/// Output for a manifest c2pa-rs could read. Fields that need the file
+/// itself (sniffed format, `ftyp` brands, trust bundle) are left for the
+/// caller. `detached` is set when there is no asset to check hashes against.
+fn reader_output(
+    path: String,
+    content_hash: Option<ContentHash>,
+    reader: &c2pa::Reader,
+    trust_list_match: TrustLevel,
+    alg: FingerprintAlg,
+    detached: bool,
+) -> Result<VerifyOutput> {
     let has_c2pa = reader.active_manifest().is_some();
-    let validation_state = Some(format!("{:?}", reader.validation_state()));
+    let state = effective_validation_state(reader, trust_list_match, detached);
+    let cert_chain = cert_chain_der(reader);
+    let ocsp_status = ocsp_check_enabled()
+        .then(|| cert_chain.as_deref().and_then(leaf_ocsp_status))
+        .flatten();
+    let cert_revoked = ocsp_status.as_deref() == Some("revoked");
+    let validation_state = Some(if cert_revoked {
+        ValidationState::Revoked
+    } else if only_failure_is_expired_credential(reader, detached) {
+        ValidationState::ExpiredCertificate
+    } else {
+        normalize_validation_state(&state)
+    });
+    let validation_state_code = validation_state.as_ref().and_then(validation_state_code);
     let validation_error_count = reader.validation_status().map(|arr| arr.len());
     let validation_codes = reader
         .validation_status()
         .map(|arr| arr.iter().map(|s| s.code().to_string()).collect());
 
-    let manifest_store = if has_c2pa {
-        Some(serde_json::from_str::<Value>(&reader.json())?)
+    let (manifest_store, manifest_hash) = if has_c2pa {
+        let json = serde_json::from_str::<Value>(&reader.json())?;
+        let hash = manifest_hash(&json)?;
+        (Some(json), Some(hash))
     } else {
-        None
+        (None, None)
     };
 
+    let leaf_cert = cert_chain.as_ref().and_then(|chain| chain.first());
     let props = manifest_store
         .as_ref()
-        .map(extract_props)
+        .map(|json| extract_props(json, leaf_cert.map(Vec::as_slice), alg))
         .unwrap_or_default();
+    let assertions_digest = props.assertion_labels.as_deref().map(assertions_digest).transpose()?;
 
     Ok(VerifyOutput {
-        path: path.to_string(),
+        path,
         content_hash,
         has_c2pa,
         trust_list_match: Some(trust_list_match),
         validation_state,
+        validation_state_code,
         validation_error_count,
         validation_codes,
         title: props.title,
+        format_claimed: props.format.clone(),
         format: props.format,
+        format_detected: None,
+        format_mismatch: false,
         digital_source_type: props.digital_source_type,
         claim_generator: props.claim_generator,
         software_agent: props.software_agent,
+        software_agent_canonical: props.software_agent_canonical,
         issuer: props.issuer,
+        issuer_normalized: props.issuer_normalized,
         common_name: props.common_name,
         signing_time: props.signing_time,
         sig_algorithm: props.sig_algorithm,
         actions: props.actions,
         ingredients: props.ingredients,
+        has_ingredients: props.ingredient_count > 0,
+        ingredient_count: props.ingredient_count,
         manifest_store,
+        manifest_hash,
+        trust_bundle_hash: None,
+        assertion_labels: props.assertion_labels,
+        assertions_digest,
+        exif_make: props.exif_make,
+        exif_model: props.exif_model,
+        exif_gps_lat: props.exif_gps_lat,
+        exif_gps_lon: props.exif_gps_lon,
+        location_country: props.location.country,
+        location_region: props.location.region,
+        location_city: props.location.city,
+        location_lat: props.location.lat,
+        location_lon: props.location.lon,
+        manifest_label: props.manifest_label,
+        active_manifest_label: props.active_manifest_label,
+        container_brand: None,
+        compatible_brands: None,
+        fingerprint_alg: props.cert_fingerprint.as_ref().map(|_| alg.to_string()),
+        cert_fingerprint: props.cert_fingerprint,
+        cert_chain_depth: cert_chain.as_ref().map(Vec::len),
+        cert_revoked,
+        ocsp_status,
+        parse_fallback_used: false,
         error: None,
     })
 }
 
+
+/// [`verify_jumbf_with_alg`] using the default or TRUST_DIR env var.
+pub fn verify_jumbf_with_env_alg(jumbf_bytes: &[u8], content_hash: ContentHash, alg: FingerprintAlg) -> Result<VerifyOutput> {
+    let trust_dir = std::env::var("TRUST_DIR").unwrap_or_else(|_| DEFAULT_TRUST_DIR.to_string());
+    verify_jumbf_with_alg(jumbf_bytes, content_hash, &trust_dir, alg)
+}
+
 /// Convenience: verify using the default or TRUST_DIR env var.
 pub fn verify_with_env(path: &str) -> Result<VerifyOutput> {
+    verify_with_env_alg(path, FingerprintAlg::default())
+}
+
+/// [`verify_with_env`] with an explicit fingerprint algorithm.
+pub fn verify_with_env_alg(path: &str, alg: FingerprintAlg) -> Result<VerifyOutput> {
     let trust_dir = std::env::var("TRUST_DIR").unwrap_or_else(|_| DEFAULT_TRUST_DIR.to_string());
-    verify(path, &trust_dir)
+    verify_with_alg(path, &trust_dir, alg)
 }
 
-/// Load and concatenate all .pem files from a directory.
-fn load_pems(dir: &Path) -> Result<String> {
-    let mut combined = String::new();
+/// DER of the active manifest's signing cert chain, leaf first. The chain
+/// isn't in the manifest store JSON, so it comes from the reader's signature info.
+fn cert_chain_der(reader: &c2pa::Reader) -> Option<Vec<Vec<u8>>> {
+    let chain = reader.active_manifest()?.signature_info()?.cert_chain();
+    let certs: Vec<_> = pem::parse_many(chain).ok()?.into_iter().map(|p| p.into_contents()).collect();
+    (!certs.is_empty()).then_some(certs)
+}
+
+struct PartialManifest {
+    cert_fingerprint: Option<String>,
+    cert_chain_depth: Option<usize>,
+}
+
+/// Look for a raw JUMBF manifest when c2pa-rs couldn't read the file
+/// (e.g. truncated COSE_Sign1 or unsupported algorithm).
+///
+/// Only tried for extensions that can carry C2PA. Returns None if no
+/// claim + signature boxes are found.
+fn parse_fallback(path: &str, file_bytes: &[u8], alg: FingerprintAlg) -> Option<PartialManifest> {
+    let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    if !C2PA_EXTENSIONS.contains(&ext.as_str()) {
+        return None;
+    }
+
+    let (_, jumbf) = jumbf_extract::extract_jumbf(file_bytes);
+    partial_manifest(&jumbf?.into(), alg)
+}
+
+/// Claim + signature boxes of a raw JUMBF manifest store, or None.
+fn partial_manifest(jumbf: &prover_shared::Bytes, alg: FingerprintAlg) -> Option<PartialManifest> {
+    let (_, cose_sign1, _) = jumbf_extract::extract_manifest_parts(jumbf)?;
+
+    // Leaf cert is first in the COSE x5chain, if the DER is readable
+    let certs = jumbf_extract::extract_cert_chain_from_cose(&cose_sign1).ok();
+    let cert_fingerprint = certs.as_ref().and_then(|c| c.first()).map(|leaf| alg.fingerprint(leaf));
+    let cert_chain_depth = certs.as_ref().map(Vec::len).filter(|&n| n > 0);
+
+    Some(PartialManifest { cert_fingerprint, cert_chain_depth })
+}
+
+/// Whether a manifest's claimed format names the sniffed file type.
+/// Claims are usually MIME types but some signers write a bare
+/// extension (`"jpeg"`), so both the MIME subtype and extension count.
+fn format_matches(claimed: &str, detected: &infer::Type) -> bool {
+    fn canonical(s: &str) -> String {
+        let s = s.trim().to_ascii_lowercase();
+        let sub = s.rsplit('/').next().unwrap_or(&s).trim_start_matches("x-");
+        match sub {
+            "jpg" | "pjpeg" => "jpeg",
+            "quicktime" => "mov",
+            "tif" => "tiff",
+            other => other,
+        }
+        .to_string()
+    }
+
+    let claimed = canonical(claimed);
+    claimed == canonical(detected.mime_type()) || claimed == canonical(detected.extension())
+}
+
+/// Read the major and compatible brands from a leading BMFF `ftyp` box.
+///
+/// Returns None for non-BMFF files (anything not starting with `ftyp`).
+fn parse_ftyp(data: &[u8]) -> Option<(String, Vec<String>)> {
+    if data.len() < 16 || &data[4..8] != b"ftyp" {
+        return None;
+    }
+    let size = u32::from_be_bytes(data[0..4].try_into().ok()?) as usize;
+    let end = if size == 0 { data.len() } else { size.clamp(16, data.len()) };
+
+    // ftyp content: major_brand(4) + minor_version(4) + compatible_brands(4 each)
+    let brand = |b: &[u8]| String::from_utf8_lossy(b).trim_end().to_string();
+    let major = brand(&data[8..12]);
+    let compatible = data[16..end].chunks_exact(4).map(brand).collect();
+    Some((major, compatible))
+}
+
+/// The .pem files in a trust list directory, sorted by file name.
+fn pem_paths(dir: &Path) -> Result<Vec<PathBuf>> {
     if !dir.exists() {
-        return Ok(combined);
+        return Ok(Vec::new());
     }
-    let mut entries: Vec<_> = fs::read_dir(dir)
+    let mut paths: Vec<_> = fs::read_dir(dir)
         .with_context(|| format!("reading trust dir: {}", dir.display()))?
         .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "pem"))
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "pem"))
         .collect();
-    entries.sort_by_key(|e| e.file_name());
-    for entry in entries {
-        let content = fs::read_to_string(entry.path())
-            .with_context(|| format!("reading PEM: {}", entry.path().display()))?;
+    paths.sort();
+    Ok(paths)
+}
+
+/// Load and concatenate all .pem files from a directory.
+pub fn load_pems(dir: &Path) -> Result<String> {
+    let mut combined = String::new();
+    for path in pem_paths(dir)? {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("reading PEM: {}", path.display()))?;
         combined.push_str(&content);
         if !combined.ends_with('\n') {
             combined.push('\n');
@@ -153,19 +588,83 @@ fn load_pems(dir: &Path) -> Result<String> {
     Ok(combined)
 }
 
+/// SHA-256 hex of every PEM under `trust_dir`, official/ then curated/, each
+/// in file name order. Raw file bytes are hashed so the value matches the
+/// API's `versioning.compute_trust_bundle_hash`.
+pub fn compute_trust_bundle_hash(trust_dir: &str) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for subdir in ["official", "curated"] {
+        for path in pem_paths(&Path::new(trust_dir).join(subdir))? {
+            let bytes = fs::read(&path).with_context(|| format!("reading PEM: {}", path.display()))?;
+            hasher.update(&bytes);
+        }
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The PEM bundles of a trust dir, read once.
+pub struct TrustAnchors {
+    pub official_pem: String,
+    pub curated_pem: String,
+    /// [`compute_trust_bundle_hash`] of the same files
+    pub bundle_hash: String,
+}
+
+impl TrustAnchors {
+    pub fn load(trust_dir: &str) -> Result<Self> {
+        let trust_path = Path::new(trust_dir);
+        Ok(Self {
+            official_pem: load_pems(&trust_path.join("official"))?,
+            curated_pem: load_pems(&trust_path.join("curated"))?,
+            bundle_hash: compute_trust_bundle_hash(trust_dir)?,
+        })
+    }
+}
+
+/// Trust anchors for long-lived callers, reloaded when the trust dir
+/// changes. The CLI reads the dir on every run and doesn't need one.
+///
+/// A reload that fails (e.g. a half-written PEM) keeps the previous
+/// anchors and logs the error; the next change retries.
+pub struct TrustStore {
+    anchors: Arc<RwLock<Arc<TrustAnchors>>>,
+    _watcher: Debouncer<RecommendedWatcher>,
+}
+
+impl TrustStore {
+    /// Load `trust_dir` and watch it (and its subdirectories) for changes.
+    pub fn new(trust_dir: &str) -> Result<Self> {
+        let anchors = Arc::new(RwLock::new(Arc::new(TrustAnchors::load(trust_dir)?)));
+
+        let shared = Arc::clone(&anchors);
+        let dir = trust_dir.to_string();
+        let mut watcher = new_debouncer(TRUST_RELOAD_DEBOUNCE, move |res: DebounceEventResult| {
+            if res.is_err() {
+                return;
+            }
+            match TrustAnchors::load(&dir) {
+                Ok(reloaded) => *shared.write().unwrap() = Arc::new(reloaded),
+                Err(e) => eprintln!("trust reload failed, keeping previous anchors: {e:#}"),
+            }
+        })
+        .context("starting trust dir watcher")?;
+        watcher
+            .watcher()
+            .watch(Path::new(trust_dir), RecursiveMode::Recursive)
+            .with_context(|| format!("watching trust dir: {trust_dir}"))?;
+
+        Ok(Self { anchors, _watcher: watcher })
+    }
+
+    /// The anchors as of now; a reload doesn't affect one already taken.
+    pub fn anchors(&self) -> Arc<TrustAnchors> {
+        Arc::clone(&self.anchors.read().unwrap())
+    }
+}
+
 /// Try to open a C2PA file with trust anchors. Returns None if unsigned.
 fn try_read(path: &str, trust_pem: &str) -> Result<Option<c2pa::Reader>> {
-    let result = if trust_pem.is_empty() {
-        c2pa::Reader::from_file(path)
-    } else {
-        let settings = c2pa::settings::Settings::new()
-            .with_value("trust.trust_anchors", trust_pem)
-            .map_err(|e| anyhow::anyhow!("settings: {e}"))?;
-        let context = c2pa::Context::new()
-            .with_settings(settings)
-            .map_err(|e| anyhow::anyhow!("context: {e}"))?;
-        c2pa::Reader::from_context(context).with_file(path)
-    };
+    let result = c2pa::Reader::from_context(c2pa_context(trust_pem)?).with_file(path);
     match result {
         Ok(r) => Ok(Some(r)),
         Err(e) => {
@@ -177,6 +676,154 @@ fn try_read(path: &str, trust_pem: &str) -> Result<Option<c2pa::Reader>> {
     }
 }
 
+/// Like [`try_read`] for a bare manifest store, read as a `.c2pa` sidecar.
+fn try_read_jumbf(jumbf: &[u8], trust_pem: &str) -> Result<Option<c2pa::Reader>> {
+    match c2pa::Reader::from_context(c2pa_context(trust_pem)?).with_stream("application/c2pa", std::io::Cursor::new(jumbf)) {
+        Ok(r) => Ok(Some(r)),
+        Err(e) => {
+            eprintln!("c2pa-rs could not read JUMBF: {e}");
+            Ok(None)
+        }
+    }
+}
+
+/// c2pa-rs context that trusts the anchors in `trust_pem` ("" for none) and
+/// uses the shared [`HTTP_CLIENT`]. OCSP is checked separately by [`ocsp`].
+fn c2pa_context(trust_pem: &str) -> Result<c2pa::Context> {
+    let mut settings = c2pa::settings::Settings::new();
+    if !trust_pem.is_empty() {
+        settings = settings
+            .with_value("trust.trust_anchors", trust_pem)
+            .map_err(|e| anyhow::anyhow!("settings: {e}"))?;
+    }
+    c2pa::Context::new()
+        .with_resolver(HTTP_CLIENT.clone())
+        .with_settings(settings)
+        .map_err(|e| anyhow::anyhow!("context: {e}"))
+}
+
+/// Map a c2pa-rs validation state onto the R3L vocabulary shared with the
+/// prover guest: a trusted signer is "Verified", a good signature from an
+/// unknown signer is "SignatureOnly", anything else is "None".
+pub fn normalize_validation_state(c2pa_state: &c2pa::ValidationState) -> ValidationState {
+    match c2pa_state {
+        c2pa::ValidationState::Trusted => ValidationState::Verified,
+        c2pa::ValidationState::Valid => ValidationState::SignatureOnly,
+        c2pa::ValidationState::Invalid => ValidationState::None,
+    }
+}
+
+/// Numeric form of a normalized `validation_state`, for ordering/comparison.
+pub fn validation_state_code(state: &ValidationState) -> Option<u8> {
+    match state {
+        ValidationState::None => Some(0),
+        ValidationState::SignatureOnly => Some(1),
+        ValidationState::Verified => Some(2),
+        _ => None,
+    }
+}
+
+/// Failure codes about whether the signer is trusted, not about integrity.
+/// The prover guest doesn't check these either. An OCSP responder that
+/// doesn't know the cert is no evidence either way (a revoked cert still fails).
+const CREDENTIAL_TRUST_CODES: &[&str] = &[
+    "signingCredential.untrusted",
+    "signingCredential.ocsp.unknown",
+];
+
+/// The signing cert wasn't valid at signing time. Never "Verified", even on
+/// a trust list: reported as "ExpiredCertificate", as the prover guest does.
+const CREDENTIAL_EXPIRED_CODE: &str = "signingCredential.expired";
+
+/// Set by [`skip_ocsp`]; wins over `ENABLE_OCSP_CHECK`.
+static OCSP_SKIPPED: AtomicBool = AtomicBool::new(false);
+
+/// Never fetch OCSP status in this process, whatever `ENABLE_OCSP_CHECK`
+/// says (the CLI's `--skip-ocsp`).
+pub fn skip_ocsp() {
+    OCSP_SKIPPED.store(true, Ordering::Relaxed);
+}
+
+/// `ENABLE_OCSP_CHECK=true`: fetch OCSP status for the signing cert. Off by
+/// default so offline verification doesn't wait on network timeouts.
+/// `SKIP_OCSP=1` or [`skip_ocsp`] turns it off again for hosts without
+/// outbound HTTP that share the same environment.
+fn ocsp_check_enabled() -> bool {
+    !OCSP_SKIPPED.load(Ordering::Relaxed) && !env_flag("SKIP_OCSP") && env_flag("ENABLE_OCSP_CHECK")
+}
+
+/// Environment variable set to "true", "1" or "yes"
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| matches!(v.to_ascii_lowercase().as_str(), "true" | "1" | "yes"))
+}
+
+/// `ocsp_status` for the leaf of a DER chain (leaf first), asking the
+/// responder named in the leaf. None without an issuer cert or responder.
+fn leaf_ocsp_status(chain: &[Vec<u8>]) -> Option<String> {
+    let [leaf, issuer, ..] = chain else { return None };
+    ocsp::responder_url(leaf)?;
+    match ocsp::check_ocsp_status(leaf, issuer) {
+        Ok(status) => Some(status.as_str().to_string()),
+        Err(e) => {
+            eprintln!("OCSP check failed: {e:#}");
+            Some("error".to_string())
+        }
+    }
+}
+
+/// Hard-binding failures that only mean the asset bytes weren't there to
+/// hash, as with a detached manifest store.
+const HARD_BINDING_CODES: &[&str] = &[
+    "assertion.dataHash.mismatch",
+    "assertion.bmffHash.mismatch",
+    "assertion.boxesHash.mismatch",
+    "assertion.collectionHash.mismatch",
+];
+
+/// c2pa-rs reports `Invalid` as soon as the signing credential is untrusted
+/// or expired, even when the signature and hashes check out. Judge integrity
+/// on the remaining failure codes and take trust from our own trust lists.
+/// With `detached`, hard-binding mismatches are ignored too.
+fn effective_validation_state(reader: &c2pa::Reader, trust_list_match: TrustLevel, detached: bool) -> c2pa::ValidationState {
+    use c2pa::ValidationState;
+
+    let state = reader.validation_state();
+    if state == ValidationState::Trusted {
+        return state;
+    }
+    let integrity_failure = reader
+        .validation_status()
+        .is_some_and(|arr| arr.iter().any(|s| is_integrity_failure(s.code(), detached)));
+    if integrity_failure {
+        ValidationState::Invalid
+    } else if trust_list_match.is_trusted() {
+        ValidationState::Trusted
+    } else {
+        ValidationState::Valid
+    }
+}
+
+/// Whether failure `code` says something about the manifest's integrity or
+/// the signing cert's validity rather than only about trust.
+fn is_integrity_failure(code: &str, detached: bool) -> bool {
+    let ignored = CREDENTIAL_TRUST_CODES.contains(&code) || (detached && HARD_BINDING_CODES.contains(&code));
+    !ignored
+}
+
+/// True if the signing cert had expired at signing time and nothing else
+/// about the manifest failed.
+fn only_failure_is_expired_credential(reader: &c2pa::Reader, detached: bool) -> bool {
+    let Some(statuses) = reader.validation_status() else {
+        return false;
+    };
+    let failures: Vec<&str> = statuses
+        .iter()
+        .map(|s| s.code())
+        .filter(|code| is_integrity_failure(code, detached))
+        .collect();
+    !failures.is_empty() && failures.iter().all(|&code| code == CREDENTIAL_EXPIRED_CODE)
+}
+
 /// Check whether signingCredential.untrusted is absent from validation statuses.
 fn is_trusted(reader: &c2pa::Reader) -> bool {
     match reader.validation_status() {
@@ -186,31 +833,42 @@ fn is_trusted(reader: &c2pa::Reader) -> bool {
 }
 
 /// Determine trust level by trying official list, then curated.
-fn resolve_trust(
+/// Returns None if the file has no readable C2PA manifest.
+pub fn resolve_trust(
     path: &str,
     official_pem: &str,
     curated_pem: &str,
-) -> Result<Option<(c2pa::Reader, String)>> {
+) -> Result<Option<(c2pa::Reader, TrustLevel)>> {
+    resolve_trust_with(official_pem, curated_pem, |trust_pem| try_read(path, trust_pem))
+}
+
+/// [`resolve_trust`] over any manifest source; `read` opens it against the
+/// given trust anchors ("" for none).
+fn resolve_trust_with(
+    official_pem: &str,
+    curated_pem: &str,
+    read: impl Fn(&str) -> Result<Option<c2pa::Reader>>,
+) -> Result<Option<(c2pa::Reader, TrustLevel)>> {
     // 1. Try official trust list
     if !official_pem.is_empty() {
-        match try_read(path, official_pem)? {
+        match read(official_pem)? {
             None => return Ok(None),
-            Some(r) if is_trusted(&r) => return Ok(Some((r, "official".into()))),
+            Some(r) if is_trusted(&r) => return Ok(Some((r, TrustLevel::Official))),
             Some(_) => {} // not trusted by official, fall through
         }
     }
     // 2. Try curated trust list
     if !curated_pem.is_empty() {
-        match try_read(path, curated_pem)? {
+        match read(curated_pem)? {
             None => return Ok(None),
-            Some(r) if is_trusted(&r) => return Ok(Some((r, "curated".into()))),
-            Some(r) => return Ok(Some((r, "untrusted".into()))),
+            Some(r) if is_trusted(&r) => return Ok(Some((r, TrustLevel::Curated))),
+            Some(r) => return Ok(Some((r, TrustLevel::Untrusted))),
         }
     }
     // 3. No trust lists — still read the file
-    match try_read(path, "")? {
+    match read("")? {
         None => Ok(None),
-        Some(r) => Ok(Some((r, "untrusted".into()))),
+        Some(r) => Ok(Some((r, TrustLevel::Untrusted))),
     }
 }
 
@@ -230,16 +888,166 @@ struct Props {
     digital_source_type: Option<String>,
     claim_generator: Option<String>,
     software_agent: Option<String>,
+    software_agent_canonical: Option<String>,
     issuer: Option<String>,
+    issuer_normalized: Option<String>,
     common_name: Option<String>,
     signing_time: Option<String>,
     sig_algorithm: Option<String>,
     actions: Option<Value>,
     ingredients: Option<Value>,
+    ingredient_count: u32,
+    assertion_labels: Option<Vec<String>>,
+    manifest_label: Option<String>,
+    active_manifest_label: Option<String>,
+    exif_make: Option<String>,
+    exif_model: Option<String>,
+    exif_gps_lat: Option<f64>,
+    exif_gps_lon: Option<f64>,
+    location: Location,
+    cert_fingerprint: Option<String>,
+}
+
+#[derive(Default)]
+struct Location {
+    country: Option<String>,
+    region: Option<String>,
+    city: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+}
+
+/// SHA-256 hex of the manifests in a store JSON. The store's validation_*
+/// entries depend on the trust list and the asset bytes, not the manifest,
+/// so they're left out.
+fn manifest_hash(store: &Value) -> Result<String> {
+    let manifests: serde_json::Map<String, Value> = ["active_manifest", "manifests"]
+        .into_iter()
+        .filter_map(|k| Some((k.to_string(), store.get(k)?.clone())))
+        .collect();
+    let canonical = serde_json::to_string(&sort_keys(&Value::Object(manifests)))?;
+    Ok(hex::encode(Sha256::digest(canonical.as_bytes())))
+}
+
+/// SHA-256 hex of sorted assertion labels serialized as a JSON array.
+fn assertions_digest(sorted_labels: &[String]) -> Result<String> {
+    let json = serde_json::to_string(sorted_labels)?;
+    Ok(hex::encode(Sha256::digest(json.as_bytes())))
+}
+
+/// Copy of `v` with object keys in sorted order. c2pa keeps manifests in a
+/// HashMap, so `Reader::json()` lists them in a different order per run.
+fn sort_keys(v: &Value) -> Value {
+    match v {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(entries.into_iter().map(|(k, v)| (k.clone(), sort_keys(v))).collect())
+        }
+        Value::Array(arr) => Value::Array(arr.iter().map(sort_keys).collect()),
+        other => other.clone(),
+    }
 }
 
-/// Pull flat provenance properties from the manifest store JSON.
-fn extract_props(json: &Value) -> Props {
+/// Manifests with at least this many assertions are scanned on the rayon
+/// pool; below it the thread hand-off costs more than it saves.
+const PARALLEL_ASSERTION_THRESHOLD: usize = 10;
+
+/// Fields pulled from the active manifest's assertions. Each assertion is
+/// scanned on its own and the partial results are merged in manifest order,
+/// so a parallel scan gives the same answer as a sequential one.
+#[derive(Default)]
+struct AssertionScan<'a> {
+    /// From the last CreativeWork assertion that has one
+    creative_work_source_type: Option<String>,
+    /// First digitalSourceType found in an action
+    action_source_type: Option<String>,
+    /// First softwareAgent found in an action
+    software_agent: Option<String>,
+    /// Action list of the last c2pa.actions assertion
+    actions: Option<Value>,
+    exif: Option<&'a Value>,
+    /// Broad location, first value per field
+    location: Location,
+    /// `locationCreated` of the last CreativeWork assertion
+    creative_work: Option<&'a Value>,
+}
+
+impl<'a> AssertionScan<'a> {
+    /// Combine with the scan of assertions that come after `self`.
+    fn merge(self, later: Self) -> Self {
+        Self {
+            creative_work_source_type: later.creative_work_source_type.or(self.creative_work_source_type),
+            action_source_type: self.action_source_type.or(later.action_source_type),
+            software_agent: self.software_agent.or(later.software_agent),
+            actions: later.actions.or(self.actions),
+            exif: later.exif.or(self.exif),
+            location: Location {
+                country: self.location.country.or(later.location.country),
+                region: self.location.region.or(later.location.region),
+                city: self.location.city.or(later.location.city),
+                ..self.location
+            },
+            creative_work: later.creative_work.or(self.creative_work),
+        }
+    }
+}
+
+fn scan_assertion(a: &Value) -> AssertionScan<'_> {
+    let mut scan = AssertionScan::default();
+    let label = a.get("label").and_then(|v| v.as_str()).unwrap_or("");
+    let Some(d) = a.get("data") else {
+        return scan;
+    };
+
+    if label == "stds.exif" {
+        scan.exif = Some(d);
+    } else if label.starts_with("c2pa.location.broad") {
+        scan.location.country = json_str(d, "country");
+        scan.location.region = json_str(d, "region");
+        scan.location.city = json_str(d, "city");
+    } else if label == "stds.schema-org.CreativeWork" {
+        scan.creative_work = d.get("locationCreated");
+        scan.creative_work_source_type = d
+            .get("digitalSourceType")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+    } else if label.starts_with("c2pa.actions") {
+        scan.actions = d.get("actions").cloned();
+        // Scan actions for softwareAgent and digitalSourceType
+        if let Some(action_arr) = d.get("actions").and_then(|a| a.as_array()) {
+            for act in action_arr {
+                if scan.software_agent.is_none() {
+                    scan.software_agent = act.get("softwareAgent").and_then(|v| {
+                        v.as_str()
+                            .map(String::from)
+                            .or_else(|| v.get("name").and_then(|n| n.as_str()).map(String::from))
+                    });
+                }
+                if scan.action_source_type.is_none() {
+                    scan.action_source_type = act
+                        .get("digitalSourceType")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+                // Check vendor-specific parameters
+                if let Some(params) = act.get("parameters")
+                    && scan.action_source_type.is_none()
+                {
+                    scan.action_source_type = params
+                        .get("com.adobe.digitalSourceType")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+            }
+        }
+    }
+    scan
+}
+
+/// Pull flat provenance properties from the manifest store JSON, plus the
+/// `alg` fingerprint of the leaf cert (which the JSON doesn't carry).
+fn extract_props(json: &Value, leaf_cert: Option<&[u8]>, alg: FingerprintAlg) -> Props {
     let active_id = json
         .get("active_manifest")
         .and_then(|v| v.as_str())
@@ -248,6 +1056,13 @@ fn extract_props(json: &Value) -> Props {
         Some(m) => m,
         None => return Props::default(),
     };
+    let active_manifest_label = Some(active_id.to_string());
+    let cert_fingerprint = leaf_cert.map(|der| alg.fingerprint(der));
+    let manifest_label = manifest
+        .get("label")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| active_manifest_label.clone());
 
     let title = manifest
         .get("title")
@@ -269,6 +1084,7 @@ fn extract_props(json: &Value) -> Props {
         .and_then(|s| s.get("issuer"))
         .and_then(|v| v.as_str())
         .map(String::from);
+    let issuer_normalized = issuer.as_deref().map(normalize_issuer);
     let common_name = sig
         .and_then(|s| s.get("common_name"))
         .and_then(|v| v.as_str())
@@ -284,35 +1100,54 @@ fn extract_props(json: &Value) -> Props {
         .map(String::from);
 
     // Assertions
-    let assertions = manifest.get("assertions").and_then(|v| v.as_array());
-    let mut digital_source_type = None;
-    let mut software_agent = None;
-    let mut actions = None;
-
-    if let Some(arr) = assertions {
-        for a in arr {
-            let label = a.get("label").and_then(|v| v.as_str()).unwrap_or("");
-            let data = a.get("data");
-
-            if label == "stds.schema-org.CreativeWork" {
-                if let Some(d) = data {
-                    digital_source_type = d
-                        .get("digitalSourceType")
-                        .and_then(|v| v.as_str())
-                        .map(String::from);
-                }
-            } else if label.starts_with("c2pa.actions") {
-                if let Some(d) = data {
-                    actions = d.get("actions").cloned();
-                    // Scan actions for softwareAgent and digitalSourceType
-                    if let Some(action_arr) = d.get("actions").and_then(|a| a.as_array()) {
+    let assertion_labels = manifest.get("assertions").and_then(|v| v.as_array()).map(|arr| {
+        let mut labels: Vec<String> = arr
+            .iter()
+            .filter_map(|a| a.get("label").and_then(|v| v.as_str()).map(String::from))
+            .collect();
+        labels.sort();
+        labels
+    });
+    let scan = match manifest.get("assertions").and_then(|v| v.as_array()) {
+        Some(arr) if arr.len() >= PARALLEL_ASSERTION_THRESHOLD => arr
+            .par_iter()
+            .map(scan_assertion)
+            .reduce(AssertionScan::default, AssertionScan::merge),
+        Some(arr) => arr.iter().map(scan_assertion).fold(AssertionScan::default(), AssertionScan::merge),
+        None => AssertionScan::default(),
+    };
+    let AssertionScan { creative_work_source_type, action_source_type, mut software_agent, actions, exif, mut location, creative_work } = scan;
+    let mut digital_source_type = creative_work_source_type.or(action_source_type);
+
+    let exif_make = exif.and_then(|d| exif_str(d, "Make"));
+    let exif_model = exif.and_then(|d| exif_str(d, "Model"));
+    let exif_gps_lat = exif.and_then(|d| exif_gps(d, "GPSLatitude"));
+    let exif_gps_lon = exif.and_then(|d| exif_gps(d, "GPSLongitude"));
+    if let Some(place) = creative_work {
+        fill_location_created(&mut location, place);
+    }
+
+    let ingredients = manifest.get("ingredients").cloned();
+    let ingredient_count = ingredients
+        .as_ref()
+        .and_then(|v| v.as_array())
+        .map_or(0, |arr| arr.len() as u32);
+
+    // Fallback: search ingredient manifests for c2pa.created action data
+    if (digital_source_type.is_none() || software_agent.is_none())
+        && let Some(manifests) = json.get("manifests").and_then(|v| v.as_object())
+    {
+        for (_, m) in manifests {
+            if let Some(asserts) = m.get("assertions").and_then(|v| v.as_array()) {
+                for a in asserts {
+                    let label = a.get("label").and_then(|v| v.as_str()).unwrap_or("");
+                    if !label.starts_with("c2pa.actions") {
+                        continue;
+                    }
+                    if let Some(action_arr) = a.get("data").and_then(|d| d.get("actions")).and_then(|a| a.as_array()) {
                         for act in action_arr {
-                            if software_agent.is_none() {
-                                software_agent = act.get("softwareAgent").and_then(|v| {
-                                    v.as_str()
-                                        .map(String::from)
-                                        .or_else(|| v.get("name").and_then(|n| n.as_str()).map(String::from))
-                                });
+                            if act.get("action").and_then(|v| v.as_str()) != Some("c2pa.created") {
+                                continue;
                             }
                             if digital_source_type.is_none() {
                                 digital_source_type = act
@@ -320,14 +1155,12 @@ fn extract_props(json: &Value) -> Props {
                                     .and_then(|v| v.as_str())
                                     .map(String::from);
                             }
-                            // Check vendor-specific parameters
-                            if let Some(params) = act.get("parameters") {
-                                if digital_source_type.is_none() {
-                                    digital_source_type = params
-                                        .get("com.adobe.digitalSourceType")
-                                        .and_then(|v| v.as_str())
-                                        .map(String::from);
-                                }
+                            if software_agent.is_none() {
+                                software_agent = act.get("softwareAgent").and_then(|v| {
+                                    v.as_str()
+                                        .map(String::from)
+                                        .or_else(|| v.get("name").and_then(|n| n.as_str()).map(String::from))
+                                });
                             }
                         }
                     }
@@ -336,43 +1169,7 @@ fn extract_props(json: &Value) -> Props {
         }
     }
 
-    let ingredients = manifest.get("ingredients").cloned();
-
-    // Fallback: search ingredient manifests for c2pa.created action data
-    if digital_source_type.is_none() || software_agent.is_none() {
-        if let Some(manifests) = json.get("manifests").and_then(|v| v.as_object()) {
-            for (_, m) in manifests {
-                if let Some(asserts) = m.get("assertions").and_then(|v| v.as_array()) {
-                    for a in asserts {
-                        let label = a.get("label").and_then(|v| v.as_str()).unwrap_or("");
-                        if !label.starts_with("c2pa.actions") {
-                            continue;
-                        }
-                        if let Some(action_arr) = a.get("data").and_then(|d| d.get("actions")).and_then(|a| a.as_array()) {
-                            for act in action_arr {
-                                if act.get("action").and_then(|v| v.as_str()) != Some("c2pa.created") {
-                                    continue;
-                                }
-                                if digital_source_type.is_none() {
-                                    digital_source_type = act
-                                        .get("digitalSourceType")
-                                        .and_then(|v| v.as_str())
-                                        .map(String::from);
-                                }
-                                if software_agent.is_none() {
-                                    software_agent = act.get("softwareAgent").and_then(|v| {
-                                        v.as_str()
-                                            .map(String::from)
-                                            .or_else(|| v.get("name").and_then(|n| n.as_str()).map(String::from))
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let software_agent_canonical = software_agent.as_deref().and_then(canonical_software_agent);
 
     Props {
         title,
@@ -380,11 +1177,170 @@ fn extract_props(json: &Value) -> Props {
         digital_source_type,
         claim_generator,
         software_agent,
+        software_agent_canonical,
         issuer,
+        issuer_normalized,
         common_name,
         signing_time,
         sig_algorithm,
         actions,
         ingredients,
+        ingredient_count,
+        assertion_labels,
+        manifest_label,
+        active_manifest_label,
+        exif_make,
+        exif_model,
+        exif_gps_lat,
+        exif_gps_lon,
+        location,
+        cert_fingerprint,
+    }
+}
+
+fn json_str(data: &Value, key: &str) -> Option<String> {
+    data.get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Fill gaps in `location` from a schema.org `locationCreated` Place. Address
+/// fields may sit on the Place itself or under `address`; coordinates under
+/// `geo` (numbers or numeric strings).
+fn fill_location_created(location: &mut Location, place: &Value) {
+    let address = place.get("address").filter(|a| a.is_object()).unwrap_or(place);
+    let country = address.get("addressCountry").and_then(|c| {
+        c.as_str()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .or_else(|| json_str(c, "name"))
+    });
+    location.country = location.country.take().or(country);
+    location.region = location.region.take().or_else(|| json_str(address, "addressRegion"));
+    location.city = location.city.take().or_else(|| json_str(address, "addressLocality"));
+
+    let geo = place.get("geo").unwrap_or(place);
+    let coord = |key: &str| {
+        geo.get(key)
+            .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok())))
+            .filter(|d: &f64| d.is_finite())
+    };
+    location.lat = location.lat.or_else(|| coord("latitude"));
+    location.lon = location.lon.or_else(|| coord("longitude"));
+}
+
+/// Look up an EXIF tag, with or without its XMP namespace prefix
+/// (`Make`, `exif:Make`, `tiff:Make`).
+fn exif_field<'a>(data: &'a Value, tag: &str) -> Option<&'a Value> {
+    ["", "exif:", "tiff:"]
+        .iter()
+        .find_map(|prefix| data.get(format!("{prefix}{tag}")))
+}
+
+fn exif_str(data: &Value, tag: &str) -> Option<String> {
+    exif_field(data, tag)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Read a GPS coordinate tag plus its `...Ref` and convert to decimal degrees.
+fn exif_gps(data: &Value, tag: &str) -> Option<f64> {
+    let value = exif_field(data, tag)?;
+    let reference = exif_str(data, &format!("{tag}Ref"));
+
+    let (degrees, inline_ref) = match value {
+        Value::Number(n) => (n.as_f64()?, None),
+        Value::Array(parts) => {
+            let parts: Option<Vec<f64>> = parts.iter().map(|p| p.as_f64()).collect();
+            (dms_to_decimal(&parts?)?, None)
+        }
+        Value::String(s) => parse_gps_string(s)?,
+        _ => return None,
+    };
+
+    let hemisphere = reference.as_deref().and_then(|r| r.chars().next()).or(inline_ref);
+    let degrees = match hemisphere {
+        Some('S' | 's' | 'W' | 'w') => -degrees.abs(),
+        _ => degrees,
+    };
+    degrees.is_finite().then_some(degrees)
+}
+
+fn dms_to_decimal(parts: &[f64]) -> Option<f64> {
+    let (d, rest) = parts.split_first()?;
+    let m = rest.first().copied().unwrap_or(0.0);
+    let s = rest.get(1).copied().unwrap_or(0.0);
+    Some(d.abs() + m / 60.0 + s / 3600.0)
+}
+
+/// Parse string coordinates: XMP `"39,21.102N"`, `"39,21,6.12N"`,
+/// exiftool-style `"39 deg 21' 6.12\" N"`, or plain decimal `"39.3517"`.
+fn parse_gps_string(s: &str) -> Option<(f64, Option<char>)> {
+    let s = s.trim();
+    let hemisphere = s.chars().last().filter(|c| "NSEWnsew".contains(*c));
+    let body = hemisphere.map_or(s, |_| &s[..s.len() - 1]);
+    let parts: Vec<f64> = body
+        .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let degrees = dms_to_decimal(&parts)?;
+    let degrees = if parts[0] < 0.0 { -degrees } else { degrees };
+    Some((degrees, hemisphere))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn trust_store_picks_up_new_pem() {
+        let dir = std::env::temp_dir().join(format!("r3l-trust-store-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("official")).unwrap();
+        let asset = dir.join("asset.txt");
+        fs::write(&asset, b"not a C2PA file").unwrap();
+        let trust_dir = dir.to_str().unwrap();
+        let asset = asset.to_str().unwrap();
+
+        let store = TrustStore::new(trust_dir).unwrap();
+        let before = verify_with_store(asset, &store, FingerprintAlg::default()).unwrap();
+        assert!(store.anchors().official_pem.is_empty());
+
+        let ca = pem::encode(&pem::Pem::new("CERTIFICATE", include_bytes!("../fixtures/ocsp/ca.der").to_vec()));
+        fs::write(dir.join("official").join("ca.pem"), &ca).unwrap();
+        let expected = compute_trust_bundle_hash(trust_dir).unwrap();
+        assert_ne!(before.trust_bundle_hash.as_deref(), Some(expected.as_str()));
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let after = loop {
+            let out = verify_with_store(asset, &store, FingerprintAlg::default()).unwrap();
+            if out.trust_bundle_hash.as_deref() == Some(expected.as_str()) || Instant::now() > deadline {
+                break out;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        };
+        assert_eq!(after.trust_bundle_hash, Some(expected));
+        assert_eq!(store.anchors().official_pem, ca);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unsigned_avif_has_no_c2pa() {
+        let path = std::env::temp_dir().join(format!("r3l-unsigned-{}.avif", std::process::id()));
+        // Generated by prover/script/fixtures/jumbf/gen.py
+        fs::write(&path, include_bytes!("../../prover/script/fixtures/jumbf/unsigned.avif")).unwrap();
+
+        let out = verify(path.to_str().unwrap(), "/nonexistent/r3l-trust").unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(!out.has_c2pa);
+        assert_eq!(out.trust_list_match, None);
+        assert_eq!(out.validation_state, None);
+        assert_eq!(out.container_brand.as_deref(), Some("avif"));
     }
 }
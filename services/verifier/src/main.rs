@@ -1,11 +1,69 @@
 use std::env;
 
+use anyhow::{Context, Result};
+use verifier::{ContentHash, FingerprintAlg, VerifyOutput};
+
 fn main() {
-    let path = env::args().nth(1).unwrap_or_default();
-    let out = verifier::verify_with_env(&path)
-        .unwrap_or_else(|e| verifier::VerifyOutput::with_error(path, format!("{:#}", e)));
+    let mut path = String::new();
+    let mut alg = FingerprintAlg::default();
+    let mut jumbf = None;
+    let mut content_hash = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--skip-ocsp" {
+            verifier::skip_ocsp();
+            continue;
+        }
+        let Some((flag, value)) = split_flag(&arg, &mut args) else {
+            path = arg;
+            continue;
+        };
+        match flag {
+            "--fingerprint-alg" => match value.parse() {
+                Ok(a) => alg = a,
+                Err(e) => {
+                    eprintln!("--fingerprint-alg: {e:#}");
+                    std::process::exit(2);
+                }
+            },
+            "--jumbf" => jumbf = Some(value),
+            "--content-hash" => content_hash = Some(value),
+            _ => {
+                eprintln!("unknown flag: {flag}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let out = match jumbf {
+        Some(jumbf_path) => verify_jumbf_file(&jumbf_path, content_hash.as_deref(), alg)
+            .unwrap_or_else(|e| VerifyOutput::with_error(jumbf_path, format!("{:#}", e))),
+        None => verifier::verify_with_env_alg(&path, alg)
+            .unwrap_or_else(|e| VerifyOutput::with_error(path, format!("{:#}", e))),
+    };
     println!("{}", serde_json::to_string_pretty(&out).unwrap());
     if out.error.is_some() {
         std::process::exit(1);
     }
 }
+
+/// `--flag value` or `--flag=value`; None for a positional argument.
+fn split_flag<'a>(arg: &'a str, rest: &mut impl Iterator<Item = String>) -> Option<(&'a str, String)> {
+    if !arg.starts_with("--") {
+        return None;
+    }
+    Some(match arg.split_once('=') {
+        Some((flag, value)) => (flag, value.to_string()),
+        None => (arg, rest.next().unwrap_or_default()),
+    })
+}
+
+/// `--jumbf <file> --content-hash <hex>`: verify a bare manifest store.
+fn verify_jumbf_file(path: &str, content_hash: Option<&str>, alg: FingerprintAlg) -> Result<VerifyOutput> {
+    let content_hash = content_hash.context("--jumbf requires --content-hash")?;
+    let content_hash: ContentHash = content_hash
+        .parse()
+        .context("--content-hash must be 64 hex characters")?;
+    let jumbf = std::fs::read(path).with_context(|| format!("reading JUMBF: {path}"))?;
+    verifier::verify_jumbf_with_env_alg(&jumbf, content_hash, alg)
+}
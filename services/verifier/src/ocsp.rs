@@ -0,0 +1,206 @@
+//! OCSP revocation check for the signing cert.
+//!
+//! Reads the responder URL from the leaf's Authority Information Access
+//! extension, POSTs an RFC 6960 request for the leaf's serial, and reads
+//! the status back. Like c2pa-rs's own OCSP fetch, the responder's
+//! signature isn't checked.
+
+use anyhow::{anyhow, bail, Context, Result};
+use rasn::types::{Any, ObjectIdentifier, OctetString};
+use rasn_ocsp::{BasicOcspResponse, CertId, CertStatus, OcspRequest, OcspResponseStatus, Request, TbsRequest};
+use rasn_pkix::{AlgorithmIdentifier, AuthorityInfoAccessSyntax, Certificate, GeneralName};
+use sha1::{Digest, Sha1};
+
+/// id-pe-authorityInfoAccess
+const AUTHORITY_INFO_ACCESS: &[u32] = &[1, 3, 6, 1, 5, 5, 7, 1, 1];
+/// id-ad-ocsp, the AIA access method naming an OCSP responder
+const AD_OCSP: &[u32] = &[1, 3, 6, 1, 5, 5, 7, 48, 1];
+/// id-pkix-ocsp-basic, the only response type responders send in practice
+const OCSP_BASIC: &[u32] = &[1, 3, 6, 1, 5, 5, 7, 48, 1, 1];
+/// id-sha1, the CertID hash every responder accepts
+const SHA1: &[u32] = &[1, 3, 14, 3, 2, 26];
+
+/// Revocation status of a certificate according to its issuer's responder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OcspStatus {
+    Good,
+    /// `revocation_time` is RFC 3339 UTC, e.g. "2024-01-01T00:00:00Z"
+    Revoked { revocation_time: String },
+    /// The responder doesn't know the certificate
+    Unknown,
+}
+
+impl OcspStatus {
+    /// Value reported as `ocsp_status` in the verifier output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Revoked { .. } => "revoked",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// First OCSP responder URL in the cert's AIA extension, if it names one.
+pub fn responder_url(cert_der: &[u8]) -> Option<String> {
+    let cert: Certificate = rasn::der::decode(cert_der).ok()?;
+    let aia = cert
+        .tbs_certificate
+        .extensions?
+        .iter()
+        .find(|e| &**e.extn_id == AUTHORITY_INFO_ACCESS)?
+        .extn_value
+        .clone();
+    let descriptions: AuthorityInfoAccessSyntax = rasn::der::decode(&aia).ok()?;
+    descriptions.into_iter().find_map(|d| match d.access_location {
+        GeneralName::Uri(uri) if &*d.access_method == AD_OCSP => Some(uri.to_string()),
+        _ => None,
+    })
+}
+
+/// Ask the leaf's OCSP responder whether `cert_der`, issued by
+/// `issuer_der`, is revoked. Fails if the cert names no responder, the
+/// request fails, or the response has no answer for this cert.
+pub fn check_ocsp_status(cert_der: &[u8], issuer_der: &[u8]) -> Result<OcspStatus> {
+    let url = responder_url(cert_der).ok_or_else(|| anyhow!("certificate names no OCSP responder"))?;
+    let (request, cert_id) = build_request(cert_der, issuer_der)?;
+    let response = crate::HTTP_CLIENT
+        .post(&url)
+        .header("Content-Type", "application/ocsp-request")
+        .body(request)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.bytes())
+        .with_context(|| format!("OCSP request to {url}"))?;
+    parse_response(&response, &cert_id)
+}
+
+/// DER OCSPRequest for the cert, and the CertID the response must echo.
+fn build_request(cert_der: &[u8], issuer_der: &[u8]) -> Result<(Vec<u8>, CertId)> {
+    let cert: Certificate = rasn::der::decode(cert_der).map_err(|e| anyhow!("certificate: {e}"))?;
+    let issuer: Certificate = rasn::der::decode(issuer_der).map_err(|e| anyhow!("issuer certificate: {e}"))?;
+
+    let issuer_name = rasn::der::encode(&issuer.tbs_certificate.subject).map_err(|e| anyhow!("issuer name: {e}"))?;
+    let issuer_key = issuer.tbs_certificate.subject_public_key_info.subject_public_key.as_raw_slice();
+    let cert_id = CertId {
+        hash_algorithm: AlgorithmIdentifier {
+            algorithm: ObjectIdentifier::new(SHA1).expect("valid OID"),
+            // Many responders expect NULL parameters rather than none
+            parameters: Some(Any::new(rasn::der::encode(&()).expect("NULL encodes"))),
+        },
+        issuer_name_hash: OctetString::from(Sha1::digest(&issuer_name).to_vec()),
+        issuer_key_hash: OctetString::from(Sha1::digest(issuer_key).to_vec()),
+        serial_number: cert.tbs_certificate.serial_number,
+    };
+    let request = OcspRequest {
+        tbs_request: TbsRequest {
+            version: 0.into(),
+            requestor_name: None,
+            request_list: vec![Request {
+                req_cert: cert_id.clone(),
+                single_request_extensions: None,
+            }],
+            request_extensions: None,
+        },
+        optional_signature: None,
+    };
+    let der = rasn::der::encode(&request).map_err(|e| anyhow!("OCSP request: {e}"))?;
+    Ok((der, cert_id))
+}
+
+/// Status for `cert_id` from a DER OCSPResponse.
+fn parse_response(der: &[u8], cert_id: &CertId) -> Result<OcspStatus> {
+    let response: rasn_ocsp::OcspResponse = rasn::der::decode(der).map_err(|e| anyhow!("OCSP response: {e}"))?;
+    if response.status != OcspResponseStatus::Successful {
+        bail!("OCSP responder returned {:?}", response.status);
+    }
+    let bytes = response.bytes.ok_or_else(|| anyhow!("OCSP response has no body"))?;
+    if &*bytes.r#type != OCSP_BASIC {
+        bail!("unsupported OCSP response type {}", bytes.r#type);
+    }
+    let basic: BasicOcspResponse =
+        rasn::der::decode(&bytes.response).map_err(|e| anyhow!("OCSP basic response: {e}"))?;
+    let single = basic
+        .tbs_response_data
+        .responses
+        .into_iter()
+        .find(|r| {
+            r.cert_id.serial_number == cert_id.serial_number && r.cert_id.issuer_key_hash == cert_id.issuer_key_hash
+        })
+        .ok_or_else(|| anyhow!("OCSP response doesn't cover the certificate"))?;
+    Ok(match single.cert_status {
+        CertStatus::Good => OcspStatus::Good,
+        CertStatus::Revoked(info) => OcspStatus::Revoked {
+            revocation_time: info.revocation_time.naive_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        },
+        CertStatus::Unknown(()) => OcspStatus::Unknown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generated by fixtures/ocsp/gen.sh
+    const CA: &[u8] = include_bytes!("../fixtures/ocsp/ca.der");
+    const GOOD: &[u8] = include_bytes!("../fixtures/ocsp/good.der");
+    const REVOKED: &[u8] = include_bytes!("../fixtures/ocsp/revoked.der");
+    const UNKNOWN: &[u8] = include_bytes!("../fixtures/ocsp/unknown.der");
+
+    fn status(cert: &[u8], response: &[u8]) -> Result<OcspStatus> {
+        let (_, cert_id) = build_request(cert, CA)?;
+        parse_response(response, &cert_id)
+    }
+
+    #[test]
+    fn reads_responder_from_aia() {
+        assert_eq!(responder_url(GOOD).as_deref(), Some("http://ocsp.r3l.test/"));
+    }
+
+    #[test]
+    fn no_responder_without_aia() {
+        assert_eq!(responder_url(CA), None);
+    }
+
+    #[test]
+    fn good_response() {
+        let status = status(GOOD, include_bytes!("../fixtures/ocsp/good.resp")).unwrap();
+        assert_eq!(status, OcspStatus::Good);
+        assert_eq!(status.as_str(), "good");
+    }
+
+    #[test]
+    fn revoked_response_carries_time() {
+        let status = status(REVOKED, include_bytes!("../fixtures/ocsp/revoked.resp")).unwrap();
+        assert_eq!(
+            status,
+            OcspStatus::Revoked {
+                revocation_time: "2024-01-01T00:00:00Z".into()
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_response() {
+        let status = status(UNKNOWN, include_bytes!("../fixtures/ocsp/unknown.resp")).unwrap();
+        assert_eq!(status, OcspStatus::Unknown);
+    }
+
+    #[test]
+    fn response_for_another_cert_is_an_error() {
+        assert!(status(GOOD, include_bytes!("../fixtures/ocsp/revoked.resp")).is_err());
+    }
+
+    #[test]
+    fn request_round_trips() {
+        let (der, cert_id) = build_request(GOOD, CA).unwrap();
+        let request: OcspRequest = rasn::der::decode(&der).unwrap();
+        assert_eq!(request.tbs_request.request_list[0].req_cert, cert_id);
+    }
+
+    #[test]
+    fn garbage_response_is_an_error() {
+        let (_, cert_id) = build_request(GOOD, CA).unwrap();
+        assert!(parse_response(b"not der", &cert_id).is_err());
+    }
+}
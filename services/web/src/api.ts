@@ -1,7 +1,7 @@
 import axios from 'axios'
 import type {
   VerifyOutput, ProveResponse, SubmitResponse, AttestResponse, AttestationResponse,
-  AttestationListItem, SimilarResponse, MeResponse, OrgInfo, OrgKeyItem,
+  AttestationListItem, Paginated, SimilarResponse, MeResponse, OrgInfo, OrgKeyItem,
 } from './types'
 
 const client = axios.create({ baseURL: '/api' })
@@ -56,8 +56,8 @@ export async function lookupAttestation(hash: string): Promise<AttestationRespon
   return data
 }
 
-export async function listAttestations(): Promise<AttestationListItem[]> {
-  const { data } = await client.get<AttestationListItem[]>('/attestations')
+export async function listAttestations(page = 1, limit = 50): Promise<Paginated<AttestationListItem>> {
+  const { data } = await client.get<Paginated<AttestationListItem>>('/attestations', { params: { page, limit } })
   return data
 }
 
@@ -155,6 +155,18 @@ export async function verifyOrgDns(domain: string): Promise<any> {
   return data
 }
 
+export async function startOrgDns(params: {
+  domain: string; admin_email?: string; name?: string
+}): Promise<any> {
+  const { data } = await client.post('/org/start-dns', params)
+  return data
+}
+
+export async function verifyOrgDnsToken(token: string): Promise<any> {
+  const { data } = await client.get(`/org/verify-dns/${token}`)
+  return data
+}
+
 export async function verifyOrgEmail(email: string, code: string): Promise<any> {
   const { data } = await client.post('/org/verify/email', { email, code })
   return data
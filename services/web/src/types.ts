@@ -17,8 +17,11 @@ export interface VerifyOutput {
   sig_algorithm: string | null
   actions: any | null
   ingredients: any | null
+  has_ingredients: boolean
+  ingredient_count: number
   manifest_store: any | null
   error: string | null
+  source_url?: string  // set by /verify-url
 }
 
 export interface ProveResponse {
@@ -84,6 +87,14 @@ export interface AttestationListItem {
   stored?: boolean
 }
 
+export interface Paginated<T> {
+  items: T[]
+  total: number
+  page: number
+  limit: number
+  pages: number
+}
+
 export interface SimilarMatch {
   content_hash: string
   match_type: 'exact' | 'near_duplicate' | 'visual_match' | 'unrelated'